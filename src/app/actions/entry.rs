@@ -4,9 +4,9 @@ use std::io;
 use chrono::NaiveDate;
 
 use crate::app::{App, DeleteTarget, EntryLocation, ViewMode};
-use crate::storage::{self, Entry, EntryType, Line};
+use crate::storage::{self, DayChange, Entry, EntryType, Journal, Line, TaskState};
 
-use super::types::{Action, ActionDescription, StatusVisibility};
+use super::types::{Action, ActionDescription, StatusVisibility, day_change_before};
 
 fn pluralize(count: usize) -> &'static str {
     if count == 1 { "entry" } else { "entries" }
@@ -93,6 +93,21 @@ impl Action for DeleteEntries {
             format!("Restored {}", pluralize(count)),
         )
     }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.targets
+            .iter()
+            .map(|target| {
+                let date = match target {
+                    DeleteTarget::Daily { .. } => app.current_date,
+                    DeleteTarget::Projected(entry) | DeleteTarget::Filter { entry, .. } => {
+                        entry.source_date
+                    }
+                };
+                (resolve_delete_path(app, target), date)
+            })
+            .collect()
+    }
 }
 
 pub struct RestoreEntries {
@@ -123,59 +138,86 @@ impl Action for RestoreEntries {
             clamp_daily_selection(app);
         } else {
             match &app.view {
-            ViewMode::Daily(_) => {
-                let (current_day_entries, other_day_entries): (Vec<_>, Vec<_>) = self
-                    .entries
-                    .iter()
-                    .cloned()
-                    .partition(|(date, _, _)| *date == app.current_date);
-
-                if !current_day_entries.is_empty() {
-                    let mut any_completed = false;
-                    let mut last_insert_idx = 0;
-
-                    for (i, (_date, line_idx, entry)) in current_day_entries.iter().enumerate() {
-                        let insert_idx = (line_idx + i).min(app.lines.len());
-                        if matches!(entry.entry_type, EntryType::Task { completed: true }) {
-                            any_completed = true;
+                ViewMode::Daily(_) => {
+                    let (current_day_entries, other_day_entries): (Vec<_>, Vec<_>) = self
+                        .entries
+                        .iter()
+                        .cloned()
+                        .partition(|(date, _, _)| *date == app.current_date);
+
+                    if !current_day_entries.is_empty() {
+                        let mut any_completed = false;
+                        let mut last_insert_idx = 0;
+
+                        for (_date, line_idx, entry) in &current_day_entries {
+                            let insert_idx = (*line_idx).min(app.lines.len());
+                            if entry.entry_type.is_closed_task() {
+                                any_completed = true;
+                            }
+
+                            delete_targets.push(DeleteTarget::Daily {
+                                line_idx: insert_idx,
+                                entry: entry.clone(),
+                            });
+
+                            app.lines.insert(insert_idx, Line::Entry(entry.to_raw()));
+                            last_insert_idx = insert_idx;
                         }
 
-                        delete_targets.push(DeleteTarget::Daily {
-                            line_idx: insert_idx,
-                            entry: entry.clone(),
-                        });
+                        app.entry_indices = App::compute_entry_indices(&app.lines);
 
-                        app.lines.insert(insert_idx, Line::Entry(entry.to_raw()));
-                        last_insert_idx = insert_idx;
-                    }
+                        if app.hide_completed && any_completed {
+                            app.hide_completed = false;
+                        }
 
-                    app.entry_indices = App::compute_entry_indices(&app.lines);
+                        let visible_idx = app
+                            .entry_indices
+                            .iter()
+                            .position(|&i| i == last_insert_idx)
+                            .map(|actual_idx| app.actual_to_visible_index(actual_idx));
 
-                    if app.hide_completed && any_completed {
-                        app.hide_completed = false;
+                        if let ViewMode::Daily(state) = &mut app.view
+                            && let Some(idx) = visible_idx
+                        {
+                            state.selected = idx;
+                        }
+                        app.save();
                     }
 
-                    let visible_idx = app
-                        .entry_indices
-                        .iter()
-                        .position(|&i| i == last_insert_idx)
-                        .map(|actual_idx| app.actual_to_visible_index(actual_idx));
+                    if !other_day_entries.is_empty() {
+                        let path = app.active_path().clone();
+
+                        // Group by date for efficient file operations
+                        let mut entries_by_date: HashMap<NaiveDate, Vec<(usize, Entry)>> =
+                            HashMap::new();
+                        for (date, line_idx, entry) in &other_day_entries {
+                            entries_by_date
+                                .entry(*date)
+                                .or_default()
+                                .push((*line_idx, entry.clone()));
+                        }
 
-                    if let ViewMode::Daily(state) = &mut app.view
-                        && let Some(idx) = visible_idx
-                    {
-                        state.selected = idx;
+                        for (date, date_entries) in entries_by_date {
+                            if let Ok(mut lines) = storage::load_day_lines(date, &path) {
+                                for (line_idx, entry) in date_entries {
+                                    let insert_idx = line_idx.min(lines.len());
+                                    lines.insert(insert_idx, Line::Entry(entry.to_raw()));
+
+                                    delete_targets.push(DeleteTarget::Projected(entry));
+                                }
+                                let _ = storage::save_day_lines(date, &path, &lines);
+                            }
+                        }
+
+                        app.refresh_projected_entries();
                     }
-                    app.save();
                 }
+                ViewMode::Filter(_) => {
+                    let path = app.active_path().clone();
 
-                if !other_day_entries.is_empty() {
-                    let path = app.active_path().to_path_buf();
-
-                    // Group by date for efficient file operations
                     let mut entries_by_date: HashMap<NaiveDate, Vec<(usize, Entry)>> =
                         HashMap::new();
-                    for (date, line_idx, entry) in &other_day_entries {
+                    for (date, line_idx, entry) in &self.entries {
                         entries_by_date
                             .entry(*date)
                             .or_default()
@@ -184,66 +226,43 @@ impl Action for RestoreEntries {
 
                     for (date, date_entries) in entries_by_date {
                         if let Ok(mut lines) = storage::load_day_lines(date, &path) {
-                            for (i, (line_idx, entry)) in date_entries.into_iter().enumerate() {
-                                let insert_idx = (line_idx + i).min(lines.len());
+                            for (line_idx, entry) in date_entries {
+                                let insert_idx = line_idx.min(lines.len());
+
+                                let restored_entry = Entry {
+                                    entry_type: entry.entry_type.clone(),
+                                    content: entry.content.clone(),
+                                    source_date: date,
+                                    line_index: insert_idx,
+                                    source_type: entry.source_type.clone(),
+                                    source_journal: entry.source_journal.clone(),
+                                    depth: entry.depth,
+                                    indent: entry.indent.clone(),
+                                    body: entry.body.clone(),
+                                };
                                 lines.insert(insert_idx, Line::Entry(entry.to_raw()));
 
-                                delete_targets.push(DeleteTarget::Projected(entry));
+                                if let ViewMode::Filter(state) = &mut app.view {
+                                    let filter_index = state.entries.len();
+                                    state.entries.push(restored_entry.clone());
+                                    state.selected = filter_index;
+
+                                    delete_targets.push(DeleteTarget::Filter {
+                                        index: filter_index,
+                                        entry: restored_entry,
+                                    });
+                                }
                             }
                             let _ = storage::save_day_lines(date, &path, &lines);
-                        }
-                    }
 
-                    app.refresh_projected_entries();
-                }
-            }
-            ViewMode::Filter(_) => {
-                let path = app.active_path().to_path_buf();
-
-                let mut entries_by_date: HashMap<NaiveDate, Vec<(usize, Entry)>> = HashMap::new();
-                for (date, line_idx, entry) in &self.entries {
-                    entries_by_date
-                        .entry(*date)
-                        .or_default()
-                        .push((*line_idx, entry.clone()));
-                }
-
-                for (date, date_entries) in entries_by_date {
-                    if let Ok(mut lines) = storage::load_day_lines(date, &path) {
-                        for (i, (line_idx, entry)) in date_entries.into_iter().enumerate() {
-                            let insert_idx = (line_idx + i).min(lines.len());
-
-                            let restored_entry = Entry {
-                                entry_type: entry.entry_type.clone(),
-                                content: entry.content.clone(),
-                                source_date: date,
-                                line_index: insert_idx,
-                                source_type: entry.source_type.clone(),
-                                source_journal: entry.source_journal.clone(),
-                            };
-                            lines.insert(insert_idx, Line::Entry(entry.to_raw()));
-
-                            if let ViewMode::Filter(state) = &mut app.view {
-                                let filter_index = state.entries.len();
-                                state.entries.push(restored_entry.clone());
-                                state.selected = filter_index;
-
-                                delete_targets.push(DeleteTarget::Filter {
-                                    index: filter_index,
-                                    entry: restored_entry,
-                                });
+                            if date == app.current_date {
+                                let _ = app.reload_current_day();
                             }
                         }
-                        let _ = storage::save_day_lines(date, &path, &lines);
-
-                        if date == app.current_date {
-                            let _ = app.reload_current_day();
-                        }
                     }
                 }
             }
         }
-        }
 
         Ok(Box::new(DeleteEntries {
             targets: delete_targets,
@@ -321,9 +340,9 @@ fn execute_delete_raw(
     }
 }
 
-fn resolve_delete_path(app: &App, target: &DeleteTarget) -> std::path::PathBuf {
+fn resolve_delete_path(app: &App, target: &DeleteTarget) -> Journal {
     if !app.combined_view {
-        return app.active_path().to_path_buf();
+        return app.active_path().clone();
     }
     match target {
         DeleteTarget::Projected(entry)
@@ -375,6 +394,20 @@ impl Action for CreateEntry {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn applied_changes(&self, _app: &App) -> io::Result<Vec<DayChange>> {
+        let line_index = self.target.line_index;
+        let change = day_change_before(
+            &self.target.entry.source_journal,
+            self.target.date,
+            |lines| {
+                if line_index < lines.len() {
+                    lines.remove(line_index);
+                }
+            },
+        )?;
+        Ok(vec![change])
+    }
 }
 
 struct UncreateEntry {
@@ -389,7 +422,7 @@ impl UncreateEntry {
 
 impl Action for UncreateEntry {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
-        let path = app.active_path().to_path_buf();
+        let path = app.active_path().clone();
 
         // Delete the entry
         storage::delete_entry(self.target.date, &path, self.target.line_index)?;
@@ -444,7 +477,7 @@ impl RecreateEntry {
 
 impl Action for RecreateEntry {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
-        let path = app.active_path().to_path_buf();
+        let path = app.active_path().clone();
 
         // Recreate the entry at the original position
         let mut lines = storage::load_day_lines(self.target.date, &path)?;
@@ -478,6 +511,7 @@ impl Action for RecreateEntry {
     }
 }
 
+/// Contents are edit text: the entry's content followed by its body lines.
 #[derive(Clone)]
 pub struct EditTarget {
     pub location: EntryLocation,
@@ -511,6 +545,23 @@ impl Action for EditEntry {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn applied_changes(&self, app: &App) -> io::Result<Vec<DayChange>> {
+        let (date, line_index) = match &self.target.location {
+            EntryLocation::Daily { line_idx, .. } => (app.current_date, *line_idx),
+            EntryLocation::Projected(entry) | EntryLocation::Filter { entry, .. } => {
+                (entry.source_date, entry.line_index)
+            }
+        };
+        let path = app.resolve_entry_path(&self.target.location);
+        let original_content = self.target.original_content.clone();
+        let change = day_change_before(&path, date, |lines| {
+            if let Some(Line::Entry(raw_entry)) = lines.get_mut(line_index) {
+                raw_entry.set_text(&original_content);
+            }
+        })?;
+        Ok(vec![change])
+    }
 }
 
 struct RestoreEdit {
@@ -581,7 +632,7 @@ fn set_entry_content_raw(app: &mut App, target: &EditTarget) -> io::Result<()> {
     match &target.location {
         EntryLocation::Projected(entry) => {
             storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                raw_entry.content = content.clone();
+                raw_entry.set_text(content);
             })?;
             app.refresh_projected_entries();
         }
@@ -590,22 +641,22 @@ fn set_entry_content_raw(app: &mut App, target: &EditTarget) -> io::Result<()> {
             source_path,
         } => {
             if source_path.is_some() || app.combined_view {
-                storage::update_entry_content(app.current_date, &path, *line_idx, content.clone())?;
+                storage::update_entry_text(app.current_date, &path, *line_idx, content)?;
                 let _ = app.load_combined_data();
             } else if let Line::Entry(raw_entry) = &mut app.lines[*line_idx] {
-                raw_entry.content = content.clone();
+                raw_entry.set_text(content);
                 app.save();
             }
         }
         EntryLocation::Filter { index, entry } => {
             storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                raw_entry.content = content.clone();
+                raw_entry.set_text(content);
             })?;
 
             if let ViewMode::Filter(state) = &mut app.view
                 && let Some(filter_entry) = state.entries.get_mut(*index)
             {
-                filter_entry.content = content.clone();
+                (filter_entry.content, filter_entry.body) = storage::split_entry_text(content);
             }
 
             if entry.source_date == app.current_date {
@@ -666,6 +717,13 @@ impl Action for CycleEntryType {
             }
         }
     }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.targets
+            .iter()
+            .map(|target| app.entry_day(&target.location))
+            .collect()
+    }
 }
 
 struct RestoreEntryType {
@@ -729,8 +787,7 @@ fn execute_cycle_raw(app: &mut App, location: &EntryLocation) -> io::Result<Opti
         } => {
             if source_path.is_some() || app.combined_view {
                 // Combined mode: persist via storage, then reload
-                let new_type =
-                    storage::cycle_entry_type(app.current_date, &path, *line_idx)?;
+                let new_type = storage::cycle_entry_type(app.current_date, &path, *line_idx)?;
                 let _ = app.load_combined_data();
                 Ok(new_type)
             } else if let Line::Entry(raw_entry) = &mut app.lines[*line_idx] {
@@ -813,6 +870,114 @@ fn set_entry_type_raw(
     Ok(())
 }
 
+/// Sets the state of task entries. Targets that aren't tasks are skipped.
+pub struct SetTaskState {
+    changes: Vec<(CycleTarget, TaskState)>,
+}
+
+impl SetTaskState {
+    /// Sets every task target to `state`.
+    #[must_use]
+    pub fn new(targets: Vec<CycleTarget>, state: TaskState) -> Self {
+        Self::from_changes(targets.into_iter().map(|target| (target, state)).collect())
+    }
+
+    /// Moves each task target on to its next state.
+    #[must_use]
+    pub fn advance(targets: Vec<CycleTarget>) -> Self {
+        Self::from_changes(
+            targets
+                .into_iter()
+                .filter_map(|target| match target.original_type {
+                    EntryType::Task { state } => Some((target, state.next_mark())),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    fn from_changes(changes: Vec<(CycleTarget, TaskState)>) -> Self {
+        let changes = changes
+            .into_iter()
+            .filter(|(target, _)| matches!(target.original_type, EntryType::Task { .. }))
+            .collect();
+        Self { changes }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Action for SetTaskState {
+    fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
+        for (target, state) in &self.changes {
+            set_entry_type_raw(app, &target.location, &EntryType::Task { state: *state })?;
+        }
+
+        Ok(Box::new(RestoreTaskState {
+            changes: self.changes.clone(),
+        }))
+    }
+
+    fn description(&self) -> ActionDescription {
+        task_state_description(&self.changes, false)
+    }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.changes
+            .iter()
+            .map(|(target, _)| app.entry_day(&target.location))
+            .collect()
+    }
+}
+
+struct RestoreTaskState {
+    changes: Vec<(CycleTarget, TaskState)>,
+}
+
+impl Action for RestoreTaskState {
+    fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
+        for (target, _) in &self.changes {
+            set_entry_type_raw(app, &target.location, &target.original_type)?;
+        }
+
+        Ok(Box::new(SetTaskState {
+            changes: self.changes.clone(),
+        }))
+    }
+
+    fn description(&self) -> ActionDescription {
+        task_state_description(&self.changes, true)
+    }
+}
+
+fn task_state_description(
+    changes: &[(CycleTarget, TaskState)],
+    restore: bool,
+) -> ActionDescription {
+    let count = changes.len();
+    let marked = match changes {
+        [(_, state)] => format!("Marked task {}", state.label()),
+        [(_, first), rest @ ..] if rest.iter().all(|(_, state)| state == first) => {
+            format!("Marked {count} tasks {}", first.label())
+        }
+        _ => format!("Changed state on {count} tasks"),
+    };
+    let restored = if count == 1 {
+        "Restored task state".to_string()
+    } else {
+        format!("Restored state on {count} tasks")
+    };
+
+    if restore {
+        ActionDescription::on_undo(restored, marked)
+    } else {
+        ActionDescription::on_undo(marked, restored)
+    }
+}
+
 #[derive(Clone)]
 pub struct PasteTarget {
     pub date: NaiveDate,
@@ -846,6 +1011,20 @@ impl Action for PasteEntries {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn applied_changes(&self, app: &App) -> io::Result<Vec<DayChange>> {
+        let journal = self.target.entries.first().map_or_else(
+            || app.active_path().clone(),
+            |entry| entry.source_journal.clone(),
+        );
+        let start = self.target.start_line_index;
+        let count = self.target.entries.len();
+        let change = day_change_before(&journal, self.target.date, |lines| {
+            let end = (start + count).min(lines.len());
+            lines.drain(start.min(end)..end);
+        })?;
+        Ok(vec![change])
+    }
 }
 
 struct UnpasteEntries {
@@ -860,7 +1039,7 @@ impl UnpasteEntries {
 
 impl Action for UnpasteEntries {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
-        let path = app.active_path().to_path_buf();
+        let path = app.active_path().clone();
 
         // Delete entries in reverse order to maintain indices
         for i in (0..self.target.entries.len()).rev() {
@@ -902,7 +1081,7 @@ impl RepasteEntries {
 
 impl Action for RepasteEntries {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
-        let path = app.active_path().to_path_buf();
+        let path = app.active_path().clone();
 
         let mut lines = storage::load_day_lines(self.target.date, &path)?;
 
@@ -4,7 +4,9 @@ mod types;
 
 pub use entry::{
     CreateEntry, CreateTarget, CycleEntryType, CycleTarget, DeleteEntries, EditEntry, EditTarget,
-    PasteEntries, PasteTarget, RestoreEntries,
+    PasteEntries, PasteTarget, RestoreEntries, SetTaskState,
 };
 pub use tag::{AppendTag, RemoveAllTags, RemoveLastTag, TagTarget};
-pub use types::{Action, ActionDescription, ActionExecutor, ContentTarget, StatusVisibility};
+pub use types::{
+    Action, ActionDescription, ActionExecutor, ContentTarget, StatusVisibility, UndoStep,
+};
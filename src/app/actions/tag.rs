@@ -1,6 +1,9 @@
 use std::io;
 
+use chrono::NaiveDate;
+
 use crate::app::{App, EntryLocation};
+use crate::storage::Journal;
 use crate::ui::{remove_all_trailing_tags, remove_last_trailing_tag};
 
 use super::types::{
@@ -79,6 +82,13 @@ impl Action for TagRemovalAction {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.targets
+            .iter()
+            .map(|target| app.entry_day(&target.location))
+            .collect()
+    }
 }
 
 pub struct RemoveLastTag(TagRemovalAction);
@@ -107,6 +117,10 @@ impl Action for RemoveLastTag {
     fn description(&self) -> ActionDescription {
         self.0.description()
     }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.0.changed_days(app)
+    }
 }
 
 pub struct RemoveAllTags(TagRemovalAction);
@@ -135,6 +149,10 @@ impl Action for RemoveAllTags {
     fn description(&self) -> ActionDescription {
         self.0.description()
     }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.0.changed_days(app)
+    }
 }
 
 pub struct AppendTag {
@@ -192,6 +210,13 @@ impl Action for AppendTag {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn changed_days(&self, app: &App) -> Vec<(Journal, NaiveDate)> {
+        self.targets
+            .iter()
+            .map(|target| app.entry_day(&target.location))
+            .collect()
+    }
 }
 
 #[derive(Clone)]
@@ -1,16 +1,21 @@
 use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 
 use super::super::App;
 use crate::app::EntryLocation;
+use crate::storage::{self, DayChange, Journal, Line};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum StatusVisibility {
     Silent,
     OnUndo,
     Always,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ActionDescription {
     pub past: String,
     pub past_reversed: String,
@@ -49,13 +54,73 @@ impl ActionDescription {
 pub trait Action: Send {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>>;
     fn description(&self) -> ActionDescription;
+
+    /// Changes saved before the action was recorded, such as an entry written
+    /// by edit mode, which the executor can't observe by running the action.
+    fn applied_changes(&self, _app: &App) -> io::Result<Vec<DayChange>> {
+        Ok(Vec::new())
+    }
+
+    /// Journal days the action changes when executed. The executor snapshots
+    /// only these around it, so recording a step costs the same however large
+    /// the journals are.
+    fn changed_days(&self, _app: &App) -> Vec<(Journal, NaiveDate)> {
+        Vec::new()
+    }
 }
 
 const MAX_UNDO_DEPTH: usize = 50;
 
+/// One step of the undo or redo stack.
+#[derive(Serialize, Deserialize)]
+pub struct UndoStep {
+    pub description: ActionDescription,
+    /// Days this step changed, which undoing it takes back
+    pub changes: Vec<DayChange>,
+    /// The reverse action, kept while the view it was recorded in is current.
+    /// Without it the step is undone from `changes`.
+    #[serde(skip)]
+    live: Option<Box<dyn Action>>,
+}
+
+impl UndoStep {
+    /// Takes the step back: through the live reverse action when there is one,
+    /// otherwise by restoring the recorded days and reloading the view.
+    fn reverse(&mut self, app: &mut App) -> io::Result<Self> {
+        let changes: Vec<DayChange> = self.changes.iter().map(DayChange::reversed).collect();
+        let (description, live) = match self.live.take() {
+            Some(mut action) => {
+                let description = action.description();
+                (description, Some(action.execute(app)?))
+            }
+            None => {
+                storage::apply_day_changes(&changes)?;
+                app.reload_current_view();
+                let description = ActionDescription {
+                    past: self.description.past_reversed.clone(),
+                    past_reversed: self.description.past.clone(),
+                    visibility: self.description.visibility.clone(),
+                };
+                (description, None)
+            }
+        };
+        Ok(Self {
+            description,
+            changes,
+            live,
+        })
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SavedHistory {
+    undo: Vec<UndoStep>,
+    redo: Vec<UndoStep>,
+}
+
 pub struct ActionExecutor {
-    undo_stack: Vec<(Box<dyn Action>, ActionDescription)>,
-    redo_stack: Vec<(Box<dyn Action>, ActionDescription)>,
+    undo_stack: Vec<UndoStep>,
+    redo_stack: Vec<UndoStep>,
 }
 
 impl Default for ActionExecutor {
@@ -73,15 +138,64 @@ impl ActionExecutor {
         }
     }
 
+    /// Loads the history saved beside the hub journal. A missing or unreadable
+    /// history starts empty.
+    #[must_use]
+    pub fn load(hub_journal: &Path) -> Self {
+        let history: SavedHistory = storage::read_undo_history(hub_journal)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            undo_stack: history.undo,
+            redo_stack: history.redo,
+        }
+    }
+
+    pub fn save(&mut self, hub_journal: &Path) -> io::Result<()> {
+        let history = SavedHistory {
+            undo: std::mem::take(&mut self.undo_stack),
+            redo: std::mem::take(&mut self.redo_stack),
+        };
+        let json = serde_json::to_string(&history).map_err(io::Error::other);
+        self.undo_stack = history.undo;
+        self.redo_stack = history.redo;
+        storage::write_undo_history(hub_journal, &json?)
+    }
+
     pub fn execute(
         &mut self,
         mut action: Box<dyn Action>,
         app: &mut App,
     ) -> io::Result<Option<String>> {
         let description = action.description();
+        let mut changes = action.applied_changes(app)?;
+
+        let mut days = action.changed_days(app);
+        days.sort_by(|a, b| (a.0.path(), a.1).cmp(&(b.0.path(), b.1)));
+        days.dedup();
+        let before: Vec<String> = days
+            .iter()
+            .map(|(journal, date)| storage::load_day(*date, journal))
+            .collect::<io::Result<_>>()?;
         let reverse_action = action.execute(app)?;
+        for ((journal, date), before) in days.into_iter().zip(before) {
+            let after = storage::load_day(date, &journal)?;
+            if after != before {
+                changes.push(DayChange {
+                    journal,
+                    date,
+                    before,
+                    after,
+                });
+            }
+        }
 
-        self.undo_stack.push((reverse_action, description.clone()));
+        self.undo_stack.push(UndoStep {
+            description: description.clone(),
+            changes,
+            live: Some(reverse_action),
+        });
         if self.undo_stack.len() > MAX_UNDO_DEPTH {
             self.undo_stack.remove(0);
         }
@@ -96,13 +210,20 @@ impl ActionExecutor {
     }
 
     pub fn undo(&mut self, app: &mut App) -> io::Result<Option<String>> {
-        let Some((mut action, original_desc)) = self.undo_stack.pop() else {
+        let Some(mut step) = self.undo_stack.pop() else {
             return Ok(None);
         };
 
-        let new_desc = action.description();
-        let reverse_action = action.execute(app)?;
-        self.redo_stack.push((reverse_action, new_desc));
+        let reversed = match step.reverse(app) {
+            Ok(reversed) => reversed,
+            Err(e) => {
+                // Keep the step so it can be retried once the conflict is resolved
+                self.undo_stack.push(step);
+                return Err(e);
+            }
+        };
+        let original_desc = step.description;
+        self.redo_stack.push(reversed);
 
         let message = match original_desc.visibility {
             StatusVisibility::Silent => None,
@@ -115,13 +236,20 @@ impl ActionExecutor {
     }
 
     pub fn redo(&mut self, app: &mut App) -> io::Result<Option<String>> {
-        let Some((mut action, original_desc)) = self.redo_stack.pop() else {
+        let Some(mut step) = self.redo_stack.pop() else {
             return Ok(None);
         };
 
-        let new_desc = action.description();
-        let reverse_action = action.execute(app)?;
-        self.undo_stack.push((reverse_action, new_desc));
+        let reversed = match step.reverse(app) {
+            Ok(reversed) => reversed,
+            Err(e) => {
+                // Keep the step so it can be retried once the conflict is resolved
+                self.redo_stack.push(step);
+                return Err(e);
+            }
+        };
+        let original_desc = step.description;
+        self.undo_stack.push(reversed);
 
         let message = match original_desc.visibility {
             StatusVisibility::Silent => None,
@@ -137,9 +265,22 @@ impl ActionExecutor {
         self.redo_stack.clear();
     }
 
-    pub fn clear(&mut self) {
-        self.undo_stack.clear();
-        self.redo_stack.clear();
+    /// Drops the live reverse actions, which refer to positions in the current
+    /// view. The steps stay undoable from their recorded days.
+    pub fn detach_from_view(&mut self) {
+        for step in self.undo_stack.iter_mut().chain(&mut self.redo_stack) {
+            step.live = None;
+        }
+    }
+
+    /// Undo steps, most recent first.
+    pub fn undo_steps(&self) -> impl Iterator<Item = &UndoStep> {
+        self.undo_stack.iter().rev()
+    }
+
+    /// Redo steps, most recently undone first.
+    pub fn redo_steps(&self) -> impl Iterator<Item = &UndoStep> {
+        self.redo_stack.iter().rev()
     }
 
     #[must_use]
@@ -204,3 +345,20 @@ pub fn execute_content_append(
     app.save_entry_content(location, new_content)?;
     Ok(())
 }
+
+/// Rebuilds a day as it was before an already-saved change by applying
+/// `revert` to its current lines.
+pub fn day_change_before<F>(journal: &Journal, date: NaiveDate, revert: F) -> io::Result<DayChange>
+where
+    F: FnOnce(&mut Vec<Line>),
+{
+    let after = storage::load_day(date, journal)?;
+    let mut lines = storage::parse_lines(&after);
+    revert(&mut lines);
+    Ok(DayChange {
+        journal: journal.clone(),
+        date,
+        before: storage::serialize_lines(&lines),
+        after,
+    })
+}
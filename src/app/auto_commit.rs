@@ -0,0 +1,206 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDate;
+
+use crate::storage::{self, Journal};
+
+use super::actions::UndoStep;
+use super::{App, InputMode};
+
+/// How long saves have to stop for before changed journals are committed
+const AUTO_COMMIT_DELAY: Duration = Duration::from_secs(5);
+
+/// Journal changes waiting for `auto_commit`.
+#[derive(Default)]
+pub(super) struct PendingCommit {
+    /// Each changed journal, oldest first
+    journals: Vec<JournalChanges>,
+    /// When a change was last saved
+    last_save: Option<Instant>,
+}
+
+/// What was done to one journal since its last commit.
+struct JournalChanges {
+    /// The journal first, then any archives that were written to
+    files: Vec<PathBuf>,
+    actions: Vec<String>,
+}
+
+impl PendingCommit {
+    /// Notes that `files` of `journal` were written, by `action` if given.
+    /// Files under the journal itself, like a folder journal's days, are
+    /// already covered by it.
+    fn note(&mut self, journal: &Journal, files: Vec<PathBuf>, action: Option<&str>) {
+        let index = match self
+            .journals
+            .iter()
+            .position(|changes| changes.files[0] == journal.path())
+        {
+            Some(index) => index,
+            None => {
+                self.journals.push(JournalChanges {
+                    files: vec![journal.to_path_buf()],
+                    actions: Vec::new(),
+                });
+                self.journals.len() - 1
+            }
+        };
+        let changes = &mut self.journals[index];
+        for file in files {
+            if !file.starts_with(journal.path()) && !changes.files.contains(&file) {
+                changes.files.push(file);
+            }
+        }
+        if let Some(action) = action.filter(|action| !action.is_empty()) {
+            changes.actions.push(action.to_string());
+        }
+        self.last_save = Some(Instant::now());
+    }
+}
+
+impl App {
+    /// Notes the journals an action just changed, and what it did, for the
+    /// next automatic commit.
+    pub(super) fn note_action_for_commit(&mut self) {
+        let step = self.executor.undo_steps().next();
+        self.note_step_for_commit(step.map(|step| step_summary(step, &step.description.past)));
+    }
+
+    /// Like `note_action_for_commit`, for an undo. The step it moved to the
+    /// redo stack is its reverse, so that step's reversed description names
+    /// the action that was undone.
+    pub(super) fn note_undo_for_commit(&mut self) {
+        let step = self.executor.redo_steps().next();
+        self.note_step_for_commit(
+            step.map(|step| {
+                step_summary(step, &format!("Undo: {}", step.description.past_reversed))
+            }),
+        );
+    }
+
+    /// Like `note_action_for_commit`, for a redo of the step it just moved
+    /// back to the undo stack.
+    pub(super) fn note_redo_for_commit(&mut self) {
+        let step = self.executor.undo_steps().next();
+        self.note_step_for_commit(
+            step.map(|step| step_summary(step, &format!("Redo: {}", step.description.past))),
+        );
+    }
+
+    fn note_step_for_commit(&mut self, summary: Option<(String, Vec<(Journal, NaiveDate)>)>) {
+        if !self.config.auto_commit {
+            return;
+        }
+        let Some((action, days)) = summary else {
+            return;
+        };
+        let mut journals: Vec<(Journal, Vec<PathBuf>)> = Vec::new();
+        for (journal, date) in days {
+            let files = day_files(&journal, date);
+            match journals.iter_mut().find(|(noted, _)| *noted == journal) {
+                Some((_, noted_files)) => noted_files.extend(files),
+                None => journals.push((journal, files)),
+            }
+        }
+        for (journal, files) in journals {
+            self.pending_commit.note(&journal, files, Some(&action));
+        }
+    }
+
+    /// Notes a save of the current day of the active journal made outside an
+    /// action.
+    pub(super) fn note_save_for_commit(&mut self) {
+        if self.config.auto_commit {
+            let journal = self.active_path().clone();
+            let files = day_files(&journal, self.current_date);
+            self.pending_commit.note(&journal, files, None);
+        }
+    }
+
+    /// Notes an archiving of the active journal, which writes the journal and
+    /// its archives.
+    pub(super) fn note_archive_for_commit(&mut self, archived: usize) -> io::Result<()> {
+        if self.config.auto_commit {
+            let journal = self.active_path().clone();
+            let archives = storage::journal_archives(&journal)?;
+            let action = format!("Archived {archived} days");
+            self.pending_commit.note(&journal, archives, Some(&action));
+        }
+        Ok(())
+    }
+
+    /// Commits changed journals once saves have settled. Called periodically
+    /// from the event loop; waits while an entry is being edited.
+    pub fn auto_commit_if_settled(&mut self) {
+        let settled = self
+            .pending_commit
+            .last_save
+            .is_some_and(|saved| saved.elapsed() >= AUTO_COMMIT_DELAY);
+        if !settled || matches!(self.input_mode, InputMode::Edit(_)) {
+            return;
+        }
+        match self.auto_commit() {
+            Ok(0) => {}
+            Ok(_) => self.set_status("Committed journal changes"),
+            Err(e) => self.set_error(format!("Auto-commit skipped: {e}")),
+        }
+    }
+
+    /// Commits every journal with pending changes, returning how many were
+    /// committed. Journals outside a git repository are let go; ones git
+    /// refuses stay pending for the next attempt.
+    pub fn auto_commit(&mut self) -> io::Result<usize> {
+        self.pending_commit.last_save = None;
+        let journals = std::mem::take(&mut self.pending_commit.journals);
+        let mut committed = 0;
+        let mut first_error = None;
+        for changes in journals {
+            if storage::repo_root(&changes.files[0]).is_none() {
+                continue;
+            }
+            match storage::commit_journal(&changes.files, &commit_message(&changes.actions)) {
+                Ok(true) => committed += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    self.pending_commit.journals.push(changes);
+                }
+            }
+        }
+        first_error.map_or(Ok(committed), Err)
+    }
+}
+
+/// `action`, describing the step, and each day the step changed.
+fn step_summary(step: &UndoStep, action: &str) -> (String, Vec<(Journal, NaiveDate)>) {
+    let days = step
+        .changes
+        .iter()
+        .map(|change| (change.journal.clone(), change.date))
+        .collect();
+    (action.to_string(), days)
+}
+
+/// The files a save of `date` may have written: the file the day is stored
+/// in, and the archives that could hold it, since a day emptied out of an
+/// archive no longer says which one it was in.
+fn day_files(journal: &Journal, date: NaiveDate) -> Vec<PathBuf> {
+    let mut files = vec![storage::journal_day_file(date, journal)];
+    files.extend(storage::archives_for_day(journal, date));
+    files
+}
+
+/// A subject naming the first action and how many followed, with every
+/// action listed in the body when there's more than one.
+fn commit_message(actions: &[String]) -> String {
+    match actions {
+        [] => "Update journal".to_string(),
+        [action] => action.clone(),
+        [first, rest @ ..] => {
+            let body: Vec<String> = actions.iter().map(|action| format!("- {action}")).collect();
+            format!("{first} and {} more\n\n{}", rest.len(), body.join("\n"))
+        }
+    }
+}
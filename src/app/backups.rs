@@ -0,0 +1,87 @@
+use std::io;
+
+use chrono::Local;
+
+use crate::storage::{self, LineDiff};
+
+use super::{App, CommandPaletteMode, ConfirmContext, InputMode};
+
+impl App {
+    /// Snapshots the active journal before an operation that rewrites it.
+    pub(super) fn backup_active_journal(&self, reason: &str) -> io::Result<()> {
+        storage::backup_journal(
+            self.active_path(),
+            &self.config.get_backup_dir(),
+            reason,
+            self.config.backup_count,
+        )
+        .map(|_| ())
+    }
+
+    /// Takes the active journal's daily snapshot if it hasn't been taken yet today.
+    pub(super) fn ensure_daily_backup(&mut self) {
+        if let Err(e) = storage::ensure_daily_backup(
+            self.active_path(),
+            &self.config.get_backup_dir(),
+            self.config.backup_count,
+            Local::now().date_naive(),
+        ) {
+            self.set_error(format!("Daily backup failed: {e}"));
+        }
+    }
+
+    pub(super) fn refresh_backup_cache(&mut self) {
+        self.cached_backups =
+            storage::list_backups(self.active_path(), &self.config.get_backup_dir())
+                .unwrap_or_default();
+        self.refresh_backup_preview();
+    }
+
+    /// Diffs the current journal against the backup selected in the palette.
+    pub(super) fn refresh_backup_preview(&mut self) {
+        self.backup_preview = match &self.input_mode {
+            InputMode::CommandPalette(state) if state.mode == CommandPaletteMode::Backups => self
+                .cached_backups
+                .get(state.selected)
+                .and_then(|backup| {
+                    let snapshot = backup.content().ok()?;
+                    let current = storage::load_journal(self.active_path()).ok()?;
+                    Some(storage::diff_lines(&current, &snapshot))
+                })
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|line| !matches!(line, LineDiff::Unchanged(_)))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    pub(super) fn execute_selected_backup(&mut self, index: usize) {
+        if let Some(backup) = self.cached_backups.get(index) {
+            let path = backup.path.clone();
+            self.close_command_palette();
+            self.input_mode = InputMode::Confirm(ConfirmContext::RestoreBackup(path));
+        }
+    }
+
+    pub fn confirm_restore_backup(&mut self, path: &std::path::Path) -> io::Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(backup) = self.cached_backups.iter().find(|b| b.path == path).cloned() else {
+            self.set_error("Backup no longer exists");
+            return Ok(());
+        };
+
+        storage::restore_backup(
+            self.active_path(),
+            &backup,
+            &self.config.get_backup_dir(),
+            self.config.backup_count,
+        )?;
+        self.refresh_view_after_tag_change()?;
+        self.set_status(format!(
+            "Restored backup from {}",
+            backup.taken_at.format("%Y/%m/%d %H:%M")
+        ));
+        Ok(())
+    }
+}
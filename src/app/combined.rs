@@ -1,7 +1,6 @@
 use std::io;
-use std::path::PathBuf;
 
-use crate::storage::{self, Entry, JournalSlot, Line, ProjectRegistry};
+use crate::storage::{self, Entry, Journal, JournalSlot, Line, ProjectRegistry};
 
 use super::{
     App, CombinedGroup, CombinedPosition, DailyState, EntryLocation, InputMode, SelectedItem,
@@ -29,7 +28,7 @@ impl App {
             self.set_status("Hub journal");
         }
 
-        self.executor.clear();
+        self.executor.detach_from_view();
         Ok(())
     }
 
@@ -44,7 +43,7 @@ impl App {
         let date = self.current_date;
         let mut groups = Vec::new();
 
-        let hub_path = self.journal_context.hub_path().to_path_buf();
+        let hub_path = self.journal_context.hub_path().clone();
         if let Some(group) = self.build_group_for_journal("Hub", &hub_path, date)? {
             groups.push(group);
         }
@@ -58,9 +57,7 @@ impl App {
             if !journal_path.exists() {
                 continue;
             }
-            if let Some(group) =
-                self.build_group_for_journal(&project.name, &journal_path, date)?
-            {
+            if let Some(group) = self.build_group_for_journal(&project.name, &journal_path, date)? {
                 groups.push(group);
             }
         }
@@ -85,16 +82,12 @@ impl App {
     fn build_group_for_journal(
         &self,
         name: &str,
-        path: &std::path::Path,
+        journal: &Journal,
         date: chrono::NaiveDate,
     ) -> io::Result<Option<CombinedGroup>> {
-        let lines = storage::load_day_lines(date, path)?;
+        let lines = storage::load_day_lines(date, journal)?;
         let entry_indices = Self::compute_entry_indices(&lines);
-        let mut projected = storage::collect_projected_entries_for_date(date, path)?;
-
-        for entry in &mut projected {
-            entry.source_journal = path.to_path_buf();
-        }
+        let projected = storage::collect_projected_entries_for_date(date, journal)?;
 
         let has_entries = !entry_indices.is_empty() || !projected.is_empty();
         if !has_entries {
@@ -103,7 +96,7 @@ impl App {
 
         Ok(Some(CombinedGroup {
             project_name: name.to_string(),
-            journal_path: path.to_path_buf(),
+            journal_path: journal.clone(),
             lines,
             entry_indices,
             projected_entries: projected,
@@ -115,16 +108,13 @@ impl App {
             return Ok(Vec::new());
         };
 
-        let (query, unknown_filters) =
-            storage::expand_saved_filters(&state.query, &self.config.filters);
-        let mut filter = storage::parse_filter_query(&query);
-        filter.invalid_tokens.extend(unknown_filters);
-
-        if !filter.invalid_tokens.is_empty() {
+        let query = storage::expand_saved_filters(&state.query, &self.config.filters);
+        let Ok(filter) = storage::parse_filter_query(&query) else {
             return Ok(Vec::new());
-        }
+        };
 
-        let mut all_entries = self.collect_entries_from_journal(&filter, self.journal_context.hub_path())?;
+        let mut all_entries =
+            self.collect_entries_from_journal(&filter, self.journal_context.hub_path())?;
 
         let registry = ProjectRegistry::load();
         for project in &registry.projects {
@@ -139,8 +129,17 @@ impl App {
 
         // Sort by journal path first (matches BTreeMap grouping in render), then date
         all_entries.sort_by(|a, b| {
+            let by_priority = || {
+                if filter.by_priority() {
+                    storage::priority_rank(&a.content).cmp(&storage::priority_rank(&b.content))
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            };
             a.source_journal
-                .cmp(&b.source_journal)
+                .path()
+                .cmp(b.source_journal.path())
+                .then_with(by_priority)
                 .then(a.source_date.cmp(&b.source_date))
         });
 
@@ -156,13 +155,9 @@ impl App {
     fn collect_entries_from_journal(
         &self,
         filter: &storage::Filter,
-        path: &std::path::Path,
+        journal: &Journal,
     ) -> io::Result<Vec<Entry>> {
-        let mut entries = storage::collect_filtered_entries(filter, path)?;
-        for entry in &mut entries {
-            entry.source_journal = path.to_path_buf();
-        }
-        Ok(entries)
+        storage::collect_filtered_entries(filter, journal)
     }
 
     #[must_use]
@@ -275,18 +270,22 @@ impl App {
     }
 
     #[must_use]
-    pub fn resolve_combined_journal_path(&self, visible_idx: usize) -> Option<PathBuf> {
+    pub fn resolve_combined_journal_path(&self, visible_idx: usize) -> Option<Journal> {
         let pos = self.resolve_combined_position(visible_idx)?;
         Some(self.combined_groups[pos.group_index].journal_path.clone())
     }
 
     #[must_use]
-    pub fn resolve_entry_path(&self, location: &EntryLocation) -> PathBuf {
+    pub fn resolve_entry_path(&self, location: &EntryLocation) -> Journal {
         if !self.combined_view {
-            if let EntryLocation::Daily { source_path: Some(path), .. } = location {
+            if let EntryLocation::Daily {
+                source_path: Some(path),
+                ..
+            } = location
+            {
                 return path.clone();
             }
-            return self.active_path().to_path_buf();
+            return self.active_path().clone();
         }
 
         match location {
@@ -295,7 +294,7 @@ impl App {
             }
             EntryLocation::Daily { source_path, .. } => source_path
                 .clone()
-                .unwrap_or_else(|| self.active_path().to_path_buf()),
+                .unwrap_or_else(|| self.active_path().clone()),
         }
     }
 
@@ -310,17 +309,14 @@ impl App {
         let hidden_projected = group
             .projected_entries
             .iter()
-            .filter(|e| matches!(e.entry_type, crate::storage::EntryType::Task { completed: true }))
+            .filter(|e| e.entry_type.is_closed_task())
             .count();
         let hidden_regular = group
             .entry_indices
             .iter()
             .filter(|&&i| {
                 if let Line::Entry(raw_entry) = &group.lines[i] {
-                    matches!(
-                        raw_entry.entry_type,
-                        crate::storage::EntryType::Task { completed: true }
-                    )
+                    raw_entry.entry_type.is_closed_task()
                 } else {
                     false
                 }
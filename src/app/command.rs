@@ -3,6 +3,7 @@ use std::io::{self, Write};
 use std::path::Path;
 use std::process::Command;
 
+use chrono::{Local, NaiveDate};
 use crossterm::execute;
 use crossterm::terminal::{
     Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -10,9 +11,13 @@ use crossterm::terminal::{
 
 use crate::config::{Config, get_config_path, get_hub_config_path};
 use crate::registry::Command as RegistryCommand;
-use crate::storage::{JournalSlot, find_git_root};
+use crate::report;
+use crate::storage::{
+    self, ArchiveOptions, DayHeaderFormat, JournalLayout, JournalProblem, JournalSlot, TaskState,
+    find_git_root,
+};
 
-use super::App;
+use super::{App, CommandPaletteMode, ConfirmContext, InputMode};
 
 impl App {
     pub fn execute_command(&mut self, command: &RegistryCommand) -> io::Result<()> {
@@ -57,12 +62,43 @@ impl App {
                 }
             }
             "edit-journal" => {
-                let path = self.journal_context.active_path().to_path_buf();
+                let path = storage::journal_day_file(self.current_date, self.active_path());
                 self.open_in_editor(&path)?;
             }
             "add-gitignore" => {
                 self.add_corner_to_gitignore();
             }
+            "restore-backup" => {
+                self.open_palette(CommandPaletteMode::Backups);
+            }
+            "undo-history" => {
+                self.open_palette(CommandPaletteMode::UndoHistory);
+            }
+            "day-commits" => {
+                self.open_palette(CommandPaletteMode::Commits);
+            }
+            "reformat-journal" => {
+                let config = &self.config;
+                if let Some(format) =
+                    DayHeaderFormat::new(config.day_header_level, &config.day_header_format)
+                {
+                    let example = format.header(self.current_date);
+                    self.input_mode = InputMode::Confirm(ConfirmContext::ReformatJournal(example));
+                } else {
+                    self.set_error(format!(
+                        "Invalid day header: level {} with \"{}\"",
+                        config.day_header_level, config.day_header_format
+                    ));
+                }
+            }
+            "report" => self.copy_report()?,
+            "archive" => self.archive_journal(),
+            "check-journal" => self.check_journal()?,
+            "mark-open" => self.set_current_task_state(TaskState::Open)?,
+            "mark-in-progress" => self.set_current_task_state(TaskState::InProgress)?,
+            "mark-blocked" => self.set_current_task_state(TaskState::Blocked)?,
+            "mark-migrated" => self.set_current_task_state(TaskState::Migrated)?,
+            "mark-cancelled" => self.set_current_task_state(TaskState::Cancelled)?,
             _ => {}
         }
         Ok(())
@@ -103,6 +139,126 @@ impl App {
         Ok(())
     }
 
+    pub fn confirm_reformat_journal(&mut self) -> io::Result<()> {
+        self.input_mode = InputMode::Normal;
+        self.save();
+        self.backup_active_journal("reformat")?;
+        let format = self.config.validated_day_header();
+        let changed = storage::reformat_journal(self.active_path(), &format)?;
+        self.refresh_view_after_tag_change()?;
+        self.set_status(format!("Reformatted {changed} day headers"));
+        Ok(())
+    }
+
+    /// Copies the report of yesterday and today across the hub and project
+    /// journals to the clipboard.
+    fn copy_report(&mut self) -> io::Result<()> {
+        self.save();
+        let today = Local::now().date_naive();
+        let start = today.pred_opt().unwrap_or(today);
+        let journals = report::report_journals(self.journal_context.hub_path());
+        let reports =
+            report::build_report(&journals, start, today, &self.config.report_blocker_tag)?;
+        if reports.is_empty() {
+            self.set_status("Nothing to report");
+            return Ok(());
+        }
+        let text = report::render_report(
+            &reports,
+            start,
+            today,
+            self.config.report_format,
+            self.config.report_template.as_deref(),
+        );
+        match Self::copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!("Copied report of {} journals", reports.len())),
+            Err(e) => self.set_error(format!("Failed to copy report: {e}")),
+        }
+        Ok(())
+    }
+
+    fn archive_journal(&mut self) {
+        if self.active_path().layout() != JournalLayout::File {
+            self.set_error("Only single-file journals can be archived");
+            return;
+        }
+        let options = self.config.archive_options(Local::now().date_naive());
+        let archive = if options.by_year {
+            "yearly archives".to_string()
+        } else {
+            storage::archive_path(self.active_path(), None)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        };
+        self.input_mode = InputMode::Confirm(ConfirmContext::ArchiveJournal {
+            before: options.before,
+            archive,
+        });
+    }
+
+    pub fn confirm_archive_journal(&mut self, before: NaiveDate) -> io::Result<()> {
+        self.input_mode = InputMode::Normal;
+        self.save();
+        self.backup_active_journal("archive")?;
+        let options = ArchiveOptions {
+            before,
+            completed_only: self.config.archive_completed_only,
+            by_year: self.config.archive_by_year,
+        };
+        let archived = storage::archive_journal(self.active_path(), &options)?;
+        self.note_archive_for_commit(archived)?;
+        self.note_save_for_ics_export();
+        self.refresh_view_after_tag_change()?;
+        self.set_status(format!("Archived {archived} days"));
+        Ok(())
+    }
+
+    /// Reports the journal's problems, offering to fix them when any can be.
+    fn check_journal(&mut self) -> io::Result<()> {
+        self.save();
+        let problems = storage::check_journal(self.active_path())?;
+        let Some(first) = problems.first() else {
+            self.set_status("No problems found");
+            return Ok(());
+        };
+        let fixable: Vec<&JournalProblem> =
+            problems.iter().filter(|p| p.kind.is_fixable()).collect();
+        match fixable.first() {
+            Some(example) => {
+                self.input_mode = InputMode::Confirm(ConfirmContext::FixJournal {
+                    fixable: fixable.len(),
+                    example: describe_problem(example),
+                });
+            }
+            None => self.set_error(format!(
+                "{} problems, like {}",
+                problems.len(),
+                describe_problem(first)
+            )),
+        }
+        Ok(())
+    }
+
+    pub fn confirm_fix_journal(&mut self) -> io::Result<()> {
+        self.input_mode = InputMode::Normal;
+        self.save();
+        self.backup_active_journal("check")?;
+        let fixed = storage::fix_journal(self.active_path())?;
+        self.refresh_view_after_tag_change()?;
+        let remaining = storage::check_journal(self.active_path())?;
+        match remaining.first() {
+            Some(first) => self.set_error(format!(
+                "Fixed {fixed} problems; {} left, like {}",
+                remaining.len(),
+                describe_problem(first)
+            )),
+            None => self.set_status(format!("Fixed {fixed} problems")),
+        }
+        Ok(())
+    }
+
     fn add_corner_to_gitignore(&mut self) {
         let Some(git_root) = find_git_root() else {
             self.set_error("Not in a git repository");
@@ -151,3 +307,13 @@ impl App {
         self.set_status("Added .corner to .gitignore");
     }
 }
+
+/// A problem as `file:line: problem`, with just the file's name.
+fn describe_problem(problem: &JournalProblem) -> String {
+    let file = problem
+        .file
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    format!("{file}:{}: {}", problem.line, problem.kind)
+}
@@ -1,6 +1,6 @@
 use std::io;
 
-use crate::storage::{self, Line, expand_favorite_tags, normalize_entry_structure};
+use crate::storage::{self, JournalSlot, Line, expand_favorite_tags, normalize_entry_structure};
 
 use super::{App, EntryLocation, ViewMode};
 
@@ -17,6 +17,16 @@ impl App {
         (content.trim_end().to_string(), warning)
     }
 
+    /// Tags a new entry with the branch checked out next to the project
+    /// journal, when `git_branch_tags` is on.
+    #[must_use]
+    pub fn stamp_git_branch(&self, content: &str) -> String {
+        if !self.config.git_branch_tags || self.active_journal() != JournalSlot::Project {
+            return content.to_string();
+        }
+        storage::stamp_branch(content, self.active_path())
+    }
+
     /// The single entry point for all content modifications.
     /// Normalizes content and persists to the appropriate location.
     pub fn save_entry_content(
@@ -37,7 +47,7 @@ impl App {
         location: &EntryLocation,
         content: &str,
     ) -> io::Result<()> {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
 
         match location {
             EntryLocation::Daily { line_idx, .. } => {
@@ -1,5 +1,8 @@
 use crate::cursor::CursorBuffer;
-use crate::storage::{self, Entry, EntryType, Line, RawEntry, SourceType, restore_done_meta};
+use crate::storage::{
+    self, Entry, EntryType, Journal, Line, RawEntry, SourceType, join_entry_text,
+    restore_done_meta, split_entry_text,
+};
 
 use super::actions::{CreateEntry, CreateTarget, EditEntry, EditTarget};
 use super::{App, EditContext, EntryLocation, InputMode, InsertPosition, ViewMode};
@@ -50,14 +53,22 @@ impl App {
 
     fn save_current_edit(&mut self) -> Option<(EditContext, bool)> {
         let buffer = self.edit_buffer.take()?;
-        let (new_content, warning) = self.normalize_content(&buffer.into_content());
+        // Only the first line is entry content; the rest is its body, kept as typed
+        let (content, body) = split_entry_text(&buffer.into_content());
+        let (content, warning) = self.normalize_content(&content);
+        let original_content = self.original_edit_content.take().unwrap_or_default();
+        let is_new_entry = original_content.is_empty();
+        let content = if is_new_entry {
+            self.stamp_git_branch(&content)
+        } else {
+            content
+        };
+        let new_content = join_entry_text(&content, &body);
         let had_content = !new_content.trim().is_empty();
 
         if let Some(warning) = warning {
             self.set_status(warning);
         }
-        let original_content = self.original_edit_content.take().unwrap_or_default();
-        let is_new_entry = original_content.is_empty();
 
         let context = match std::mem::replace(&mut self.input_mode, InputMode::Normal) {
             InputMode::Edit(ctx) => ctx,
@@ -134,7 +145,7 @@ impl App {
         let new_content = restore_done_meta(&new_content, &original_content);
 
         if let Some(entry) = self.get_daily_entry_mut(entry_index) {
-            entry.content = new_content.clone();
+            entry.set_text(&new_content);
             self.save();
         }
 
@@ -145,7 +156,7 @@ impl App {
                     self.current_date,
                     line_idx,
                     SourceType::Local,
-                    self.active_path().to_path_buf(),
+                    self.active_path().clone(),
                 );
                 let target = CreateTarget {
                     date: self.current_date,
@@ -179,7 +190,7 @@ impl App {
         new_content: String,
         original_content: String,
     ) {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
 
         let new_content = restore_done_meta(&new_content, &original_content);
 
@@ -188,13 +199,17 @@ impl App {
         } else if let Some((entry_type, new_content)) =
             self.update_remote_entry(date, line_index, new_content, &original_content)
         {
+            let (content, body) = split_entry_text(&original_content);
             let entry = Entry {
                 entry_type: entry_type.clone(),
-                content: original_content.clone(),
+                content,
                 source_date: date,
                 line_index,
                 source_type: SourceType::Local,
                 source_journal: path.clone(),
+                depth: 0,
+                indent: String::new(),
+                body,
             };
             let target = EditTarget {
                 location: EntryLocation::Filter {
@@ -218,15 +233,19 @@ impl App {
         entry_type: EntryType,
         content: String,
     ) {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
 
         if !content.trim().is_empty()
             && let Ok(mut lines) = storage::load_day_lines(date, &path)
         {
             let line_index = lines.len();
+            let (content, body) = split_entry_text(&content);
             let raw_entry = RawEntry {
                 entry_type: entry_type.clone(),
                 content: content.clone(),
+                depth: 0,
+                indent: String::new(),
+                body: body.clone(),
             };
             lines.push(Line::Entry(raw_entry));
             let _ = storage::save_day_lines(date, &path, &lines);
@@ -238,6 +257,9 @@ impl App {
                 line_index,
                 source_type: SourceType::Local,
                 source_journal: path.clone(),
+                depth: 0,
+                indent: String::new(),
+                body,
             };
             let target = CreateTarget {
                 date,
@@ -262,10 +284,10 @@ impl App {
         new_content: String,
         original_content: &str,
     ) -> Option<(EntryType, String)> {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let entry_type = storage::get_entry_type(date, &path, line_index);
 
-        match storage::update_entry_content(date, &path, line_index, new_content.clone()) {
+        match storage::update_entry_text(date, &path, line_index, &new_content) {
             Ok(false) => {
                 self.set_error(format!(
                     "Failed to update: no entry at index {line_index} for {date}"
@@ -302,20 +324,26 @@ impl App {
         let Some((context, had_content)) = self.save_current_edit() else {
             return;
         };
+        if self.pending_conflict.is_some() {
+            return;
+        }
 
         match context {
             EditContext::Daily { entry_index } if had_content => {
                 let entry_type = self
                     .get_daily_entry(entry_index)
                     .map(|e| e.entry_type.clone())
-                    .unwrap_or(EntryType::Task { completed: false });
+                    .unwrap_or(EntryType::OPEN_TASK);
 
                 let new_raw_entry = RawEntry {
                     entry_type: match entry_type {
-                        EntryType::Task { .. } => EntryType::Task { completed: false },
+                        EntryType::Task { .. } => EntryType::OPEN_TASK,
                         other => other,
                     },
                     content: String::new(),
+                    depth: 0,
+                    indent: String::new(),
+                    body: Vec::new(),
                 };
                 self.add_entry_internal(new_raw_entry, InsertPosition::Below);
             }
@@ -325,7 +353,7 @@ impl App {
                 self.input_mode = InputMode::Edit(EditContext::FilterQuickAdd {
                     date,
                     entry_type: match entry_type {
-                        EntryType::Task { .. } => EntryType::Task { completed: false },
+                        EntryType::Task { .. } => EntryType::OPEN_TASK,
                         other => other,
                     },
                 });
@@ -334,22 +362,32 @@ impl App {
         }
     }
 
-    pub(super) fn add_entry_internal(&mut self, entry: RawEntry, position: InsertPosition) {
+    pub(super) fn add_entry_internal(&mut self, mut entry: RawEntry, position: InsertPosition) {
         use super::SelectedItem;
 
-        let insert_pos =
-            if matches!(position, InsertPosition::Bottom) || self.entry_indices.is_empty() {
-                self.lines.len()
-            } else {
-                match self.get_selected_item() {
-                    SelectedItem::Daily { index, .. } => match position {
-                        InsertPosition::Below => self.entry_indices[index] + 1,
-                        InsertPosition::Above => self.entry_indices[index],
+        // New entries become siblings of the selected entry, below its nested entries
+        let insert_pos = if matches!(position, InsertPosition::Bottom)
+            || self.entry_indices.is_empty()
+        {
+            self.lines.len()
+        } else {
+            match self.get_selected_item() {
+                SelectedItem::Daily {
+                    line_idx,
+                    entry: selected,
+                    ..
+                } => {
+                    entry.depth = selected.depth;
+                    entry.indent.clone_from(&selected.indent);
+                    match position {
+                        InsertPosition::Below => storage::subtree_range(&self.lines, line_idx).end,
+                        InsertPosition::Above => line_idx,
                         InsertPosition::Bottom => unreachable!(),
-                    },
-                    _ => self.lines.len(),
+                    }
                 }
-            };
+                _ => self.lines.len(),
+            }
+        };
 
         self.lines.insert(insert_pos, Line::Entry(entry));
         self.entry_indices = Self::compute_entry_indices(&self.lines);
@@ -380,7 +418,7 @@ impl App {
 
     fn save_combined_edit(
         &mut self,
-        path: std::path::PathBuf,
+        path: Journal,
         line_idx: usize,
         new_content: String,
         original_content: String,
@@ -391,12 +429,7 @@ impl App {
             let _ = storage::delete_entry(self.current_date, &path, line_idx);
         } else {
             let entry_type = storage::get_entry_type(self.current_date, &path, line_idx);
-            let _ = storage::update_entry_content(
-                self.current_date,
-                &path,
-                line_idx,
-                new_content.clone(),
-            );
+            let _ = storage::update_entry_text(self.current_date, &path, line_idx, &new_content);
 
             if original_content != new_content {
                 let target = EditTarget {
@@ -1,12 +1,11 @@
 use std::io;
-use std::path::PathBuf;
 
-use chrono::{Datelike, Days, NaiveDate, Weekday};
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
 
 use crate::cursor::CursorBuffer;
 use crate::storage::{
-    self, Entry, EntryType, RawEntry, SourceType, add_done_date, is_done_on_date,
-    parse_to_raw_entry, remove_done_date, strip_done_meta,
+    self, Entry, EntryType, Journal, RawEntry, SourceType, TaskState, add_done_date,
+    is_done_on_date, join_entry_text, parse_to_raw_entry, remove_done_date, strip_done_meta,
 };
 
 use super::{App, EditContext, InputMode, Line, SelectedItem, ViewMode};
@@ -31,7 +30,7 @@ pub enum EntryLocation {
     /// source_path is Some in combined mode to route writes to the correct journal.
     Daily {
         line_idx: usize,
-        source_path: Option<PathBuf>,
+        source_path: Option<Journal>,
     },
     /// Entry in filter view - index is position in filter results
     Filter { index: usize, entry: Entry },
@@ -64,7 +63,7 @@ impl App {
         entries: Vec<RawEntry>,
         target_date: NaiveDate,
     ) -> io::Result<()> {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let mut target_lines = storage::load_day_lines(target_date, &path)?;
         for entry in entries {
             target_lines.push(Line::Entry(entry));
@@ -105,7 +104,7 @@ impl App {
             } => {
                 let source_journal = self
                     .resolve_combined_journal_path(self.view.selected())
-                    .unwrap_or_else(|| self.active_path().to_path_buf());
+                    .unwrap_or_else(|| self.active_path().clone());
                 Some(DeleteTarget::Daily {
                     line_idx,
                     entry: Entry::from_raw(
@@ -125,21 +124,72 @@ impl App {
         }
     }
 
+    /// Delete targets for the current entry and, in the daily view, its nested entries.
+    fn extract_subtree_delete_targets_from_current(&self) -> Vec<DeleteTarget> {
+        match self.extract_delete_target_from_current() {
+            Some(DeleteTarget::Daily { line_idx, entry }) if !self.combined_view => {
+                storage::subtree_range(&self.lines, line_idx)
+                    .filter_map(|idx| match &self.lines[idx] {
+                        Line::Entry(raw) => Some(DeleteTarget::Daily {
+                            line_idx: idx,
+                            entry: Entry::from_raw(
+                                raw,
+                                self.current_date,
+                                idx,
+                                SourceType::Local,
+                                entry.source_journal.clone(),
+                            ),
+                        }),
+                        Line::Raw(_) => None,
+                    })
+                    .collect()
+            }
+            target => target.into_iter().collect(),
+        }
+    }
+
+    /// The current daily entry and its nested entries, re-rooted at depth 0.
+    fn current_subtree_raw_entries(&self, line_idx: usize) -> Vec<RawEntry> {
+        let range = storage::subtree_range(&self.lines, line_idx);
+        let mut entries: Vec<RawEntry> = self.lines[range]
+            .iter()
+            .filter_map(|line| match line {
+                Line::Entry(raw) => Some(raw.clone()),
+                Line::Raw(_) => None,
+            })
+            .collect();
+        let root_depth = entries.first().map_or(0, |root| root.depth);
+        for entry in &mut entries {
+            entry.depth -= root_depth;
+        }
+        entries
+    }
+
     pub fn delete_current_entry(&mut self) -> io::Result<()> {
         if let SelectedItem::Projected { .. } = self.get_selected_item() {
             self.set_status("Press o to go to source");
             return Ok(());
         }
 
-        // Yank before deleting (like Vim)
-        if let Some(yank_target) = self.extract_yank_target_from_current() {
+        let targets = self.extract_subtree_delete_targets_from_current();
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        // Yank before deleting (like Vim), nested entries included
+        if let [DeleteTarget::Daily { line_idx, .. }, _, ..] = targets.as_slice() {
+            let content = self
+                .current_subtree_raw_entries(*line_idx)
+                .iter()
+                .map(storage::serialize_entry)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let _ = Self::copy_to_clipboard(&content);
+        } else if let Some(yank_target) = self.extract_yank_target_from_current() {
             let _ = Self::copy_to_clipboard(Self::yank_target_content(&yank_target));
         }
 
-        let Some(target) = self.extract_delete_target_from_current() else {
-            return Ok(());
-        };
-        let action = super::actions::DeleteEntries::single(target);
+        let action = super::actions::DeleteEntries::new(targets);
         self.execute_action(Box::new(action))
     }
 
@@ -181,18 +231,30 @@ impl App {
 
     fn toggle_projected_entry(&mut self, entry: &Entry) -> io::Result<()> {
         let path = self.resolve_entry_path(&EntryLocation::Projected(entry.clone()));
-        let Some(content) = storage::get_entry_content(entry.source_date, &path, entry.line_index)
-        else {
-            return Ok(());
-        };
 
-        let new_content = if is_done_on_date(&content, self.current_date) {
-            remove_done_date(&content, self.current_date)
-        } else {
-            add_done_date(&content, self.current_date)
-        };
+        // Scheduled entries are one-offs: completing them completes the source entry
+        if entry.source_type == SourceType::Recurring {
+            let Some(content) =
+                storage::get_entry_content(entry.source_date, &path, entry.line_index)
+            else {
+                return Ok(());
+            };
 
-        storage::update_entry_content(entry.source_date, &path, entry.line_index, new_content)?;
+            let new_content = if is_done_on_date(&content, self.current_date) {
+                remove_done_date(&content, self.current_date)
+            } else {
+                add_done_date(&content, self.current_date)
+            };
+
+            storage::update_entry_content(entry.source_date, &path, entry.line_index, new_content)?;
+        } else {
+            storage::toggle_entry_complete(
+                entry.source_date,
+                &path,
+                entry.line_index,
+                self.completion_date(),
+            )?;
+        }
 
         if self.combined_view {
             let _ = self.load_combined_data();
@@ -202,12 +264,17 @@ impl App {
         Ok(())
     }
 
-    fn toggle_daily_entry(&mut self, line_idx: usize, source_path: Option<&PathBuf>) -> io::Result<()> {
+    fn toggle_daily_entry(
+        &mut self,
+        line_idx: usize,
+        source_path: Option<&Journal>,
+    ) -> io::Result<()> {
+        let completed_on = self.completion_date();
         if let Some(path) = source_path {
-            storage::toggle_entry_complete(self.current_date, path, line_idx)?;
+            storage::toggle_entry_complete(self.current_date, path, line_idx, completed_on)?;
             let _ = self.load_combined_data();
-        } else if let Line::Entry(raw_entry) = &mut self.lines[line_idx] {
-            raw_entry.toggle_complete();
+        } else if let Line::Entry(_) = &self.lines[line_idx] {
+            storage::toggle_with_subtasks(&mut self.lines, line_idx, completed_on);
             self.save();
         }
         if self.hide_completed {
@@ -221,13 +288,23 @@ impl App {
             index,
             entry: entry.clone(),
         });
-        storage::toggle_entry_complete(entry.source_date, &path, entry.line_index)?;
-
+        storage::toggle_entry_complete(
+            entry.source_date,
+            &path,
+            entry.line_index,
+            self.completion_date(),
+        )?;
+
+        // Reread the entry so the view picks up its completion stamp
+        let toggled = storage::load_day_lines(entry.source_date, &path)?
+            .into_iter()
+            .nth(entry.line_index);
         if let ViewMode::Filter(state) = &mut self.view
             && let Some(filter_entry) = state.entries.get_mut(index)
-            && let EntryType::Task { completed } = &mut filter_entry.entry_type
+            && let Some(Line::Entry(raw_entry)) = toggled
         {
-            *completed = !*completed;
+            filter_entry.entry_type = raw_entry.entry_type;
+            filter_entry.content = raw_entry.content;
         }
 
         if entry.source_date == self.current_date {
@@ -236,6 +313,13 @@ impl App {
         Ok(())
     }
 
+    /// Today's date when completed tasks are stamped, per `record_completion_dates`.
+    fn completion_date(&self) -> Option<NaiveDate> {
+        self.config
+            .record_completion_dates
+            .then(|| Local::now().date_naive())
+    }
+
     pub fn toggle_current_entry(&mut self) -> io::Result<()> {
         let Some(target) = self.extract_toggle_target_from_current() else {
             return Ok(());
@@ -256,7 +340,7 @@ impl App {
                 entry,
             } => Some((
                 EditContext::Daily { entry_index: index },
-                entry.content.clone(),
+                entry.text(),
                 Some(line_idx),
             )),
             SelectedItem::Filter { index, entry } => Some((
@@ -265,7 +349,7 @@ impl App {
                     line_index: entry.line_index,
                     filter_index: index,
                 },
-                entry.content.clone(),
+                join_entry_text(&entry.content, &entry.body),
                 None,
             )),
             SelectedItem::None => None,
@@ -280,7 +364,7 @@ impl App {
         {
             let source_path = self
                 .resolve_combined_journal_path(self.view.selected())
-                .unwrap_or_else(|| self.active_path().to_path_buf());
+                .unwrap_or_else(|| self.active_path().clone());
             self.combined_edit_source = Some((source_path, line_idx));
         }
 
@@ -295,20 +379,19 @@ impl App {
 
     pub fn extract_yank_target_from_current(&self) -> Option<YankTarget> {
         let content = match self.get_selected_item() {
-            SelectedItem::Projected { entry, .. } => {
-                format!("{}{}", entry.entry_type.prefix(), entry.content)
-            }
-            SelectedItem::Daily { entry, .. } => {
-                format!("{}{}", entry.prefix(), entry.content)
-            }
-            SelectedItem::Filter { entry, .. } => {
-                format!("{}{}", entry.entry_type.prefix(), entry.content)
-            }
+            SelectedItem::Projected { entry, .. } => Self::yank_text(entry.to_raw()),
+            SelectedItem::Daily { entry, .. } => Self::yank_text(entry.clone()),
+            SelectedItem::Filter { entry, .. } => Self::yank_text(entry.to_raw()),
             SelectedItem::None => return None,
         };
         Some(YankTarget { content })
     }
 
+    /// The entry as top-level markdown, body included, so it pastes back whole.
+    pub(super) fn yank_text(entry: RawEntry) -> String {
+        storage::serialize_entry(&RawEntry { depth: 0, ..entry })
+    }
+
     pub fn yank_target_content(target: &YankTarget) -> &str {
         &target.content
     }
@@ -401,6 +484,33 @@ impl App {
         self.execute_action(Box::new(action))
     }
 
+    /// Moves the current task on to its next state: in progress, blocked,
+    /// migrated, cancelled, then open again.
+    pub fn advance_current_task_state(&mut self) -> io::Result<()> {
+        let Some(target) = self.extract_cycle_target_from_current() else {
+            return Ok(());
+        };
+        self.execute_task_state_action(super::actions::SetTaskState::advance(vec![target]))
+    }
+
+    pub fn set_current_task_state(&mut self, state: TaskState) -> io::Result<()> {
+        let Some(target) = self.extract_cycle_target_from_current() else {
+            return Ok(());
+        };
+        self.execute_task_state_action(super::actions::SetTaskState::new(vec![target], state))
+    }
+
+    pub(super) fn execute_task_state_action(
+        &mut self,
+        action: super::actions::SetTaskState,
+    ) -> io::Result<()> {
+        if action.is_empty() {
+            self.set_error("Only tasks have a state");
+            return Ok(());
+        }
+        self.execute_action(Box::new(action))
+    }
+
     fn is_test_environment() -> bool {
         // cfg(test) only works for unit tests in this crate.
         // For integration tests, we check CORNER_SKIP_CLIPBOARD which TestContext sets.
@@ -461,7 +571,7 @@ impl App {
             SelectedItem::None => (self.current_date, 0),
         };
 
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let mut lines = storage::load_day_lines(date, &path)?;
         let insert_pos = if lines.is_empty() {
             0
@@ -508,10 +618,15 @@ impl App {
         self.execute_action(Box::new(action))
     }
 
+    /// Pasted lines become entries; indented lines under an entry become its body.
     fn parse_paste_raw(text: &str) -> Vec<RawEntry> {
-        text.lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(parse_to_raw_entry)
+        storage::parse_lines(text)
+            .into_iter()
+            .filter_map(|line| match line {
+                Line::Entry(raw) => Some(raw),
+                Line::Raw(raw) if raw.trim().is_empty() => None,
+                Line::Raw(raw) => Some(parse_to_raw_entry(&raw)),
+            })
             .collect()
     }
 
@@ -535,8 +650,11 @@ impl App {
             return Ok(());
         }
 
-        let (source_date, raw_entry) = match self.get_selected_item() {
-            SelectedItem::Daily { entry, .. } => (self.current_date, entry.clone()),
+        let (source_date, raw_entries) = match self.get_selected_item() {
+            SelectedItem::Daily { line_idx, .. } => (
+                self.current_date,
+                self.current_subtree_raw_entries(line_idx),
+            ),
             SelectedItem::Filter { entry, .. } => {
                 let content = storage::get_entry_content(
                     entry.source_date,
@@ -546,10 +664,13 @@ impl App {
                 .unwrap_or_default();
                 (
                     entry.source_date,
-                    RawEntry {
+                    vec![RawEntry {
                         entry_type: entry.entry_type.clone(),
                         content,
-                    },
+                        depth: 0,
+                        indent: String::new(),
+                        body: entry.body.clone(),
+                    }],
                 )
             }
             SelectedItem::Projected { .. } | SelectedItem::None => return Ok(()),
@@ -560,13 +681,14 @@ impl App {
             return Ok(());
         }
 
-        let Some(delete_target) = self.extract_delete_target_from_current() else {
+        let delete_targets = self.extract_subtree_delete_targets_from_current();
+        if delete_targets.is_empty() {
             return Ok(());
-        };
-        let delete_action = super::actions::DeleteEntries::single(delete_target);
+        }
+        let delete_action = super::actions::DeleteEntries::new(delete_targets);
         self.execute_action(Box::new(delete_action))?;
 
-        self.add_entries_to_date(vec![raw_entry], target_date)?;
+        self.add_entries_to_date(raw_entries, target_date)?;
         self.set_status(format!("Moved to {}", target_date.format("%m/%d")));
         Ok(())
     }
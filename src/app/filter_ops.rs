@@ -10,18 +10,14 @@ use super::{App, EditContext, FilterState, InputMode, ViewMode};
 impl App {
     /// Switch to filter view with the given query.
     fn reset_filter_view(&mut self, query: String) -> io::Result<()> {
-        let (query, unknown_filters) = storage::expand_saved_filters(&query, &self.config.filters);
-        let mut filter = storage::parse_filter_query(&query);
-        filter.invalid_tokens.extend(unknown_filters);
-
-        if !filter.invalid_tokens.is_empty() {
-            self.set_status(format!(
-                "Unknown filter: {}",
-                filter.invalid_tokens.join(", ")
-            ));
-        }
-
-        let entries = storage::collect_filtered_entries(&filter, self.active_path())?;
+        let query = storage::expand_saved_filters(&query, &self.config.filters);
+        let entries = match storage::parse_filter_query(&query) {
+            Ok(filter) => storage::collect_filtered_entries(&filter, self.active_path())?,
+            Err(e) => {
+                self.set_status(format!("Invalid filter: {e}"));
+                Vec::new()
+            }
+        };
         let selected = entries.len().saturating_sub(1);
 
         self.view = ViewMode::Filter(FilterState {
@@ -78,22 +74,17 @@ impl App {
             return Ok(());
         }
 
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let ViewMode::Filter(state) = &mut self.view else {
             return Ok(());
         };
 
-        let filter = storage::parse_filter_query(&state.query);
-        let error_msg = if filter.invalid_tokens.is_empty() {
-            None
-        } else {
-            Some(format!(
-                "Unknown filter: {}",
-                filter.invalid_tokens.join(", ")
-            ))
+        let (entries, error_msg) = match storage::parse_filter_query(&state.query) {
+            Ok(filter) => (storage::collect_filtered_entries(&filter, &path)?, None),
+            Err(e) => (Vec::new(), Some(format!("Invalid filter: {e}"))),
         };
 
-        state.entries = storage::collect_filtered_entries(&filter, &path)?;
+        state.entries = entries;
         state.selected = state.selected.min(state.entries.len().saturating_sub(1));
         state.scroll_offset = 0;
 
@@ -113,7 +104,7 @@ impl App {
         self.edit_buffer = Some(CursorBuffer::empty());
         self.input_mode = InputMode::Edit(EditContext::FilterQuickAdd {
             date: today,
-            entry_type: EntryType::Task { completed: false },
+            entry_type: EntryType::OPEN_TASK,
         });
     }
 
@@ -168,16 +159,9 @@ impl App {
 
         let new_query = state.query_buffer.content().trim().to_string();
 
-        let (expanded, unknown_filters) =
-            storage::expand_saved_filters(&new_query, &self.config.filters);
-        let mut filter = storage::parse_filter_query(&expanded);
-        filter.invalid_tokens.extend(unknown_filters);
-
-        if !filter.invalid_tokens.is_empty() {
-            self.set_error(format!(
-                "Unknown filter: {}",
-                filter.invalid_tokens.join(", ")
-            ));
+        let expanded = storage::expand_saved_filters(&new_query, &self.config.filters);
+        if let Err(e) = storage::parse_filter_query(&expanded) {
+            self.set_error(format!("Invalid filter: {e}"));
             return Ok(());
         }
 
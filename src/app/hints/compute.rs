@@ -148,22 +148,59 @@ impl HintContext {
     ) -> Self {
         if input.is_empty() {
             return Self::GuidanceMessage {
-                message: "Type to search, or use ! @ # $ - for filters",
+                message: "Type to search, or use ! @ # $ - OR ( ) for filters",
             };
         }
 
-        if input.ends_with(' ') {
-            return Self::Inactive;
+        // Find the token being typed: it starts after the last space, group
+        // bracket or `|` that isn't inside a quoted phrase
+        let mut in_phrase = false;
+        let mut open_groups = 0usize;
+        let mut token_start = 0;
+        for (i, c) in input.char_indices() {
+            match c {
+                '"' => {
+                    in_phrase = !in_phrase;
+                    token_start = i + 1;
+                }
+                _ if in_phrase => {}
+                '(' => {
+                    open_groups += 1;
+                    token_start = i + 1;
+                }
+                ')' => {
+                    open_groups = open_groups.saturating_sub(1);
+                    token_start = i + 1;
+                }
+                '|' => token_start = i + 1,
+                c if c.is_whitespace() => token_start = i + c.len_utf8(),
+                _ => {}
+            }
         }
 
-        let current_token = input.split_whitespace().last().unwrap_or("");
+        if in_phrase {
+            return Self::GuidanceMessage {
+                message: "Close the phrase with \"",
+            };
+        }
+
+        if input.ends_with(' ') && open_groups > 0 {
+            return Self::GuidanceMessage {
+                message: "Close the group with )",
+            };
+        }
+
+        let current_token = &input[token_start..];
+        if current_token.is_empty() {
+            return Self::Inactive;
+        }
 
         if let Some(neg_suffix) = current_token.strip_prefix('-') {
             let inner = Self::compute_filter_token(neg_suffix, journal_tags, saved_filters);
             if matches!(inner, Self::Inactive) && neg_suffix.is_empty() {
                 return Self::Negation {
                     inner: Box::new(Self::GuidanceMessage {
-                        message: "! # or text to negate",
+                        message: "! # ( or text to negate",
                     }),
                 };
             }
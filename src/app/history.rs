@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+
+use super::{App, EntryLocation};
+use crate::storage::Journal;
+
+impl App {
+    /// The journal and day an entry at `location` is stored in, which is all
+    /// an action on it can change.
+    pub(crate) fn entry_day(&self, location: &EntryLocation) -> (Journal, NaiveDate) {
+        let date = match location {
+            EntryLocation::Daily { .. } => self.current_date,
+            EntryLocation::Projected(entry) | EntryLocation::Filter { entry, .. } => {
+                entry.source_date
+            }
+        };
+        (self.resolve_entry_path(location), date)
+    }
+
+    pub(super) fn save_undo_history(&mut self) {
+        if let Err(e) = self.executor.save(self.journal_context.hub_path()) {
+            self.set_error(format!("Failed to save undo history: {e}"));
+        }
+    }
+
+    /// Undoes every step up to and including the one selected in the palette.
+    pub(super) fn execute_selected_undo_step(&mut self, index: usize) {
+        self.close_command_palette();
+        for _ in 0..=index {
+            let remaining = self.executor.undo_steps().count();
+            self.undo();
+            // Stop at a step that couldn't be undone rather than skipping past it
+            if self.executor.undo_steps().count() == remaining {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::storage::{self, Journal};
+
+use super::actions::UndoStep;
+use super::{App, InputMode};
+
+/// How long saves have to stop for before changed journals' feeds are rewritten
+const ICS_EXPORT_DELAY: Duration = Duration::from_secs(2);
+
+/// Journals whose `.ics` feed is waiting for `export_ics_feeds`.
+#[derive(Default)]
+pub(super) struct PendingIcsExport {
+    /// Each changed journal, oldest first
+    journals: Vec<Journal>,
+    /// When a change was last saved
+    last_save: Option<Instant>,
+}
+
+impl PendingIcsExport {
+    fn note(&mut self, journals: impl IntoIterator<Item = Journal>) {
+        for journal in journals {
+            if !self.journals.contains(&journal) {
+                self.journals.push(journal);
+            }
+        }
+        self.last_save = Some(Instant::now());
+    }
+}
+
+impl App {
+    /// Notes the journals the newest undo step changed for the next feed export.
+    pub(super) fn note_undo_step_for_ics_export(&mut self) {
+        let journals = self.executor.undo_steps().next().map(step_journals);
+        self.note_journals_for_ics_export(journals.unwrap_or_default());
+    }
+
+    /// Like `note_undo_step_for_ics_export`, for the step an undo just moved
+    /// to the redo stack.
+    pub(super) fn note_redo_step_for_ics_export(&mut self) {
+        let journals = self.executor.redo_steps().next().map(step_journals);
+        self.note_journals_for_ics_export(journals.unwrap_or_default());
+    }
+
+    /// Notes a save of the active journal made outside an action.
+    pub(super) fn note_save_for_ics_export(&mut self) {
+        let journal = self.active_path().clone();
+        self.note_journals_for_ics_export(vec![journal]);
+    }
+
+    fn note_journals_for_ics_export(&mut self, journals: Vec<Journal>) {
+        if self.config.ics_export && !journals.is_empty() {
+            self.pending_ics_export.note(journals);
+        }
+    }
+
+    /// Rewrites the feeds of changed journals once saves have settled. Called
+    /// periodically from the event loop; waits while an entry is being edited.
+    pub fn export_ics_if_settled(&mut self) {
+        let settled = self
+            .pending_ics_export
+            .last_save
+            .is_some_and(|saved| saved.elapsed() >= ICS_EXPORT_DELAY);
+        if !settled || matches!(self.input_mode, InputMode::Edit(_)) {
+            return;
+        }
+        if let Err(e) = self.export_ics_feeds() {
+            self.set_error(format!("ICS export failed: {e}"));
+        }
+    }
+
+    /// Rewrites the feed of every journal with pending changes. Journals whose
+    /// feed couldn't be written stay pending for the next attempt.
+    pub fn export_ics_feeds(&mut self) -> io::Result<()> {
+        self.pending_ics_export.last_save = None;
+        let journals = std::mem::take(&mut self.pending_ics_export.journals);
+        let mut first_error = None;
+        for journal in journals {
+            if let Err(e) = storage::update_ics_export(&journal) {
+                first_error.get_or_insert(e);
+                self.pending_ics_export.journals.push(journal);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+/// The journals a step changed.
+fn step_journals(step: &UndoStep) -> Vec<Journal> {
+    let mut journals = Vec::new();
+    for change in &step.changes {
+        if !journals.contains(&change.journal) {
+            journals.push(change.journal.clone());
+        }
+    }
+    journals
+}
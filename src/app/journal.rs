@@ -14,12 +14,15 @@ impl App {
         self.refresh_calendar_cache();
         self.invalidate_agenda_cache();
         self.trigger_calendar_fetch();
+        self.ensure_daily_backup();
         Ok(())
     }
 
     pub(crate) fn apply_config(&mut self, config: Config) {
         self.keymap = Keymap::new(&config.keys).unwrap_or_default();
         self.hide_completed = config.hide_completed;
+        let journal = config.journal(self.active_path().to_path_buf());
+        self.journal_context.set_active_path(journal);
         self.config = config;
     }
 
@@ -28,7 +31,8 @@ impl App {
         self.deactivate_combined();
 
         let path = resolve_path(path);
-        self.journal_context.set_project_path(path.clone());
+        let journal = self.config.journal(path.clone());
+        self.journal_context.set_project_path(journal);
         self.journal_context.set_active_slot(JournalSlot::Project);
         self.reset_journal_view()?;
         self.set_status(format!("Opened: {}", path.display()));
@@ -1,4 +1,6 @@
 pub mod actions;
+mod auto_commit;
+mod backups;
 mod calendar;
 mod combined;
 mod command;
@@ -8,6 +10,8 @@ mod edit_mode;
 mod entry_ops;
 mod filter_ops;
 pub mod hints;
+mod history;
+mod ics_export;
 mod journal;
 mod navigation;
 mod palette;
@@ -18,9 +22,9 @@ mod tag_ops;
 pub use entry_ops::{DeleteTarget, EntryLocation, TagRemovalTarget, ToggleTarget, YankTarget};
 pub use hints::{HintContext, HintItem, HintMode};
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use chrono::{Local, NaiveDate};
 
@@ -36,7 +40,8 @@ use crate::config::{Config, SidebarDefault};
 use crate::cursor::CursorBuffer;
 use crate::dispatch::Keymap;
 use crate::storage::{
-    self, Entry, EntryType, JournalContext, JournalSlot, Line, ProjectRegistry, RawEntry,
+    self, Backup, Commit, Entry, EntryType, Journal, JournalContext, JournalSlot, Line, LineDiff,
+    ProjectRegistry, RawEntry, TaskState,
 };
 
 pub const DATE_SUFFIX_WIDTH: usize = " (MM/DD)".len();
@@ -86,6 +91,10 @@ pub enum CommandPaletteMode {
     Commands,
     Projects,
     Tags,
+    Backups,
+    UndoHistory,
+    /// Commits made on the current day in the journal's repository
+    Commits,
 }
 
 /// State for command palette input and selection
@@ -281,6 +290,29 @@ pub enum ConfirmContext {
     CreateProjectJournal,
     DeleteTag(String),
     DeleteTagFromCompleted(String),
+    /// The day being saved changed on disk since it was loaded
+    SaveConflict(NaiveDate),
+    RestoreBackup(PathBuf),
+    /// Rewrite every day header in the configured format, shown by example
+    ReformatJournal(String),
+    /// Repair the fixable problems found by checking the journal
+    FixJournal {
+        fixable: usize,
+        example: String,
+    },
+    /// Move days before the date into the named archive
+    ArchiveJournal {
+        before: NaiveDate,
+        archive: String,
+    },
+}
+
+/// Unsaved lines held back because the day changed on disk, awaiting the
+/// user's choice between their version and the one on disk.
+pub struct SaveConflict {
+    pub date: NaiveDate,
+    pub path: Journal,
+    pub lines: Vec<Line>,
 }
 
 /// State for the quick date picker overlay
@@ -320,7 +352,7 @@ pub enum SidebarType {
 #[derive(Clone)]
 pub struct CombinedGroup {
     pub project_name: String,
-    pub journal_path: PathBuf,
+    pub journal_path: Journal,
     pub lines: Vec<Line>,
     pub entry_indices: Vec<usize>,
     pub projected_entries: Vec<Entry>,
@@ -374,13 +406,23 @@ pub struct App {
     pub journal_context: JournalContext,
     pub in_git_repo: bool,
     pub hide_completed: bool,
+    /// Entries whose body is shown, by (journal, date, content). Keyed by what
+    /// the entry says rather than its line, so the right entries stay expanded
+    /// as lines are inserted, moved or deleted around them.
+    pub expanded_bodies: HashSet<(PathBuf, NaiveDate, String)>,
     pub hint_state: HintContext,
     pub cached_journal_tags: Vec<TagInfo>,
+    /// Backups of the active journal, newest first, listed in the palette
+    pub cached_backups: Vec<Backup>,
+    /// Changed lines between the journal and the backup selected in the palette
+    pub backup_preview: Vec<LineDiff>,
+    /// Commits made on the current day, listed in the palette
+    pub cached_commits: Vec<Commit>,
     pub executor: actions::ActionExecutor,
     pub keymap: Keymap,
     pub original_edit_content: Option<String>,
     /// In combined mode: (journal_path, line_idx) for the Daily entry being edited
-    pub(super) combined_edit_source: Option<(PathBuf, usize)>,
+    pub(super) combined_edit_source: Option<(Journal, usize)>,
     pub calendar_store: CalendarStore,
     pub calendar_state: CalendarState,
     pub active_sidebar: Option<SidebarType>,
@@ -391,10 +433,18 @@ pub struct App {
     pub(crate) surface: crate::ui::surface::Surface,
     /// Last known modification time of the journal file (for external change detection)
     last_file_mtime: Option<std::time::SystemTime>,
+    /// The current day's content as last read from or written to disk
+    loaded_day: Option<(NaiveDate, String)>,
+    /// Local changes that weren't saved because of a conflicting external edit
+    pub pending_conflict: Option<SaveConflict>,
     /// Whether the combined view (all journals) is active
     pub combined_view: bool,
     /// Groups of entries from each journal, populated when combined_view is true
     pub combined_groups: Vec<CombinedGroup>,
+    /// Journal changes not yet committed by `auto_commit`
+    pending_commit: auto_commit::PendingCommit,
+    /// Journals whose `.ics` feed `export_ics_feeds` hasn't rewritten yet
+    pending_ics_export: ics_export::PendingIcsExport,
 }
 
 impl App {
@@ -404,11 +454,13 @@ impl App {
 
     /// Creates a new App with a specific date, detecting paths from config.
     pub fn new_with_date(config: Config, date: NaiveDate) -> io::Result<Self> {
-        let hub_path = config
-            .hub_file
-            .as_ref()
-            .map(std::path::PathBuf::from)
-            .unwrap_or_else(crate::config::get_default_journal_path);
+        let hub_path = config.journal(
+            config
+                .hub_file
+                .as_ref()
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(crate::config::get_default_journal_path),
+        );
         let project_path = storage::detect_project_journal();
         let context = JournalContext::new(hub_path, project_path, JournalSlot::Hub);
         let surface = crate::ui::surface::Surface::default();
@@ -423,7 +475,7 @@ impl App {
         runtime_handle: Option<Handle>,
         surface: crate::ui::surface::Surface,
     ) -> io::Result<Self> {
-        let path = journal_context.active_path().to_path_buf();
+        let path = journal_context.active_path().clone();
         let lines = storage::load_day_lines(date, &path)?;
         let entry_indices = Self::compute_entry_indices(&lines);
         let projected_entries = storage::collect_projected_entries_for_date(date, &path)?;
@@ -443,7 +495,8 @@ impl App {
             (None, None)
         };
 
-        let last_file_mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let last_file_mtime = storage::journal_modified(&path);
+        let executor = actions::ActionExecutor::load(journal_context.hub_path());
 
         let mut app = Self {
             current_date: date,
@@ -461,9 +514,13 @@ impl App {
             journal_context,
             in_git_repo,
             hide_completed,
+            expanded_bodies: HashSet::new(),
             hint_state: HintContext::Inactive,
             cached_journal_tags,
-            executor: actions::ActionExecutor::new(),
+            cached_backups: Vec::new(),
+            backup_preview: Vec::new(),
+            cached_commits: Vec::new(),
+            executor,
             keymap,
             original_edit_content: None,
             combined_edit_source: None,
@@ -480,9 +537,14 @@ impl App {
             calendar_tx,
             surface,
             last_file_mtime,
+            loaded_day: None,
+            pending_conflict: None,
             combined_view: false,
             combined_groups: Vec::new(),
+            pending_commit: auto_commit::PendingCommit::default(),
+            pending_ics_export: ics_export::PendingIcsExport::default(),
         };
+        app.mark_day_loaded();
 
         if hide_completed {
             app.clamp_selection_to_visible();
@@ -490,6 +552,7 @@ impl App {
 
         app.refresh_calendar_cache();
         app.trigger_calendar_fetch();
+        app.ensure_daily_backup();
 
         Ok(app)
     }
@@ -500,9 +563,9 @@ impl App {
         matches!(self.view, ViewMode::Daily(_))
     }
 
-    /// Returns the active journal path
+    /// Returns the active journal
     #[must_use]
-    pub fn active_path(&self) -> &Path {
+    pub fn active_path(&self) -> &Journal {
         self.journal_context.active_path()
     }
 
@@ -671,6 +734,11 @@ impl App {
         let mut executor = std::mem::take(&mut self.executor);
         let result = executor.execute(action, self);
         self.executor = executor;
+        self.save_undo_history();
+        if result.is_ok() {
+            self.note_action_for_commit();
+            self.note_undo_step_for_ics_export();
+        }
 
         match result {
             Ok(Some(msg)) => {
@@ -687,34 +755,92 @@ impl App {
     }
 
     /// Saves current day's lines to storage, displaying any error as a status message.
+    /// Changes made to the day on disk since it was loaded are merged in; if both
+    /// sides changed the same entry, nothing is written and the user is asked
+    /// which version to keep.
     pub fn save(&mut self) {
-        if let Err(e) = storage::save_day_lines(self.current_date, self.active_path(), &self.lines)
-        {
-            self.set_status(format!("Failed to save: {e}"));
+        let result = match &self.loaded_day {
+            Some((date, base)) if *date == self.current_date => storage::save_day_lines_merged(
+                self.current_date,
+                self.active_path(),
+                base,
+                &self.lines,
+            ),
+            _ => storage::save_day_lines(self.current_date, self.active_path(), &self.lines)
+                .map(|()| Some(self.lines.clone())),
+        };
+        match result {
+            Ok(Some(saved)) => {
+                if saved != self.lines {
+                    self.lines = saved;
+                    self.entry_indices = Self::compute_entry_indices(&self.lines);
+                    self.refresh_projected_entries();
+                    self.clamp_selection_to_visible();
+                }
+                self.mark_day_loaded();
+                self.note_save_for_commit();
+                self.note_save_for_ics_export();
+            }
+            Ok(None) => {
+                self.pending_conflict = Some(SaveConflict {
+                    date: self.current_date,
+                    path: self.active_path().clone(),
+                    lines: self.lines.clone(),
+                });
+                self.input_mode =
+                    InputMode::Confirm(ConfirmContext::SaveConflict(self.current_date));
+            }
+            Err(e) => self.set_status(format!("Failed to save: {e}")),
         }
         self.update_file_mtime();
         self.invalidate_agenda_cache();
         self.refresh_calendar_cache();
     }
 
+    /// Records the current lines as the base snapshot of the day on disk.
+    pub(super) fn mark_day_loaded(&mut self) {
+        self.loaded_day = Some((self.current_date, storage::serialize_lines(&self.lines)));
+    }
+
+    /// Settles a save conflict by writing the held-back lines (`keep_mine`) or
+    /// discarding them in favor of the version on disk.
+    pub fn resolve_save_conflict(&mut self, keep_mine: bool) -> io::Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(conflict) = self.pending_conflict.take() else {
+            return Ok(());
+        };
+
+        if keep_mine {
+            storage::save_day_lines(conflict.date, &conflict.path, &conflict.lines)?;
+            self.set_status("Saved your version");
+        } else {
+            self.set_status("Reloaded from disk");
+        }
+
+        if conflict.date == self.current_date && conflict.path == *self.active_path() {
+            self.reload_current_day()?;
+            self.clamp_selection_to_visible();
+        }
+        self.update_file_mtime();
+        self.invalidate_agenda_cache();
+        self.refresh_calendar_cache();
+        Ok(())
+    }
+
     /// Updates the tracked file modification time to the current value.
     fn update_file_mtime(&mut self) {
-        self.last_file_mtime = std::fs::metadata(self.active_path())
-            .and_then(|m| m.modified())
-            .ok();
+        self.last_file_mtime = storage::journal_modified(self.active_path());
     }
 
     /// Checks if the journal file was modified externally and reloads if so.
     /// Returns true if a reload occurred.
     pub fn check_external_changes(&mut self) -> bool {
-        // Don't reload while editing - user might lose work
+        // Don't reload while editing - the save merges in external changes instead
         if matches!(self.input_mode, InputMode::Edit(_)) {
             return false;
         }
 
-        let current_mtime = std::fs::metadata(self.active_path())
-            .and_then(|m| m.modified())
-            .ok();
+        let current_mtime = storage::journal_modified(self.active_path());
 
         // Check if file was modified externally
         let was_modified = match (self.last_file_mtime, current_mtime) {
@@ -745,6 +871,7 @@ impl App {
             ViewMode::Daily(_) => {
                 if let Ok(lines) = storage::load_day_lines(self.current_date, self.active_path()) {
                     self.lines = lines;
+                    self.mark_day_loaded();
                     self.entry_indices = Self::compute_entry_indices(&self.lines);
                     if let Ok(projected) = storage::collect_projected_entries_for_date(
                         self.current_date,
@@ -766,9 +893,15 @@ impl App {
     }
 
     pub fn undo(&mut self) {
+        let had_step = self.executor.can_undo();
         let mut executor = std::mem::take(&mut self.executor);
         let result = executor.undo(self);
         self.executor = executor;
+        self.save_undo_history();
+        if had_step && result.is_ok() {
+            self.note_undo_for_commit();
+            self.note_redo_step_for_ics_export();
+        }
 
         match result {
             Ok(Some(msg)) => {
@@ -781,9 +914,15 @@ impl App {
     }
 
     pub fn redo(&mut self) -> io::Result<()> {
+        let had_step = self.executor.can_redo();
         let mut executor = std::mem::take(&mut self.executor);
         let result = executor.redo(self);
         self.executor = executor;
+        self.save_undo_history();
+        if had_step && result.is_ok() {
+            self.note_redo_for_commit();
+            self.note_undo_step_for_ics_export();
+        }
 
         match result {
             Ok(Some(msg)) => {
@@ -813,21 +952,41 @@ impl App {
             return;
         }
 
+        if let Err(e) = self.backup_active_journal("tidy") {
+            self.set_error(format!("Backup failed, tidy skipped: {e}"));
+            return;
+        }
+
         let tidy_order = self.config.validated_tidy_order();
-        let get_priority = |line: &Line| -> usize {
+        let position = |name: &str| tidy_order.iter().position(|n| n == name);
+        let get_priority = |line: &Line| -> (usize, u8) {
             let Line::Entry(entry) = line else {
-                return tidy_order.len();
+                return (tidy_order.len(), 0);
             };
-            for (i, type_name) in tidy_order.iter().enumerate() {
-                match (type_name.as_str(), &entry.entry_type) {
-                    ("completed", EntryType::Task { completed: true }) => return i,
-                    ("uncompleted", EntryType::Task { completed: false }) => return i,
-                    ("notes", EntryType::Note) => return i,
-                    ("events", EntryType::Event) => return i,
-                    _ => {}
-                }
+            // The prio bucket takes every entry with a priority except closed tasks
+            if let Some(prio) = position("prio")
+                && !entry.entry_type.is_closed_task()
+                && let Some(priority) = storage::extract_priority(&entry.content)
+            {
+                return (prio, priority);
             }
-            tidy_order.len()
+            // Task states not listed in tidy_order sort with open or completed tasks
+            let (group, fallback) = match entry.entry_type {
+                EntryType::Task { state } => match state {
+                    TaskState::Open => ("uncompleted", None),
+                    TaskState::Done => ("completed", None),
+                    TaskState::InProgress => ("in-progress", Some("uncompleted")),
+                    TaskState::Blocked => ("blocked", Some("uncompleted")),
+                    TaskState::Migrated => ("migrated", Some("completed")),
+                    TaskState::Cancelled => ("cancelled", Some("completed")),
+                },
+                EntryType::Note => ("notes", None),
+                EntryType::Event => ("events", None),
+            };
+            let bucket = position(group)
+                .or_else(|| fallback.and_then(position))
+                .unwrap_or(tidy_order.len());
+            (bucket, 0)
         };
 
         let mut entries: Vec<Line> = entry_positions
@@ -837,7 +996,7 @@ impl App {
 
         entries.sort_by_key(|line| get_priority(line));
 
-        for (pos, entry) in entry_positions.iter().zip(entries.into_iter()) {
+        for (pos, entry) in entry_positions.iter().zip(entries) {
             self.lines[*pos] = entry;
         }
 
@@ -847,6 +1006,7 @@ impl App {
 
     pub(crate) fn reload_current_day(&mut self) -> io::Result<()> {
         self.lines = storage::load_day_lines(self.current_date, self.active_path())?;
+        self.mark_day_loaded();
         self.entry_indices = Self::compute_entry_indices(&self.lines);
         Ok(())
     }
@@ -1,20 +1,130 @@
 use std::io;
+use std::path::Path;
 
 use chrono::{Days, Local, Months, NaiveDate};
 
-use crate::storage::{self, Entry, EntryType, Line, RawEntry};
+use crate::storage::{self, Entry, Line, RawEntry};
 
-use super::{App, DailyState, InputMode, SelectedItem, ViewMode};
+use super::{App, DailyState, EditContext, InputMode, SelectedItem, ViewMode};
 
 impl App {
     #[must_use]
     pub fn should_show_raw_entry(&self, entry: &RawEntry) -> bool {
-        !self.hide_completed || !matches!(entry.entry_type, EntryType::Task { completed: true })
+        !self.hide_completed || !entry.entry_type.is_closed_task()
     }
 
     #[must_use]
     pub fn should_show_entry(&self, entry: &Entry) -> bool {
-        !self.hide_completed || !matches!(entry.entry_type, EntryType::Task { completed: true })
+        !self.hide_completed || !entry.entry_type.is_closed_task()
+    }
+
+    #[must_use]
+    pub fn is_body_expanded(&self, journal: &Path, date: NaiveDate, content: &str) -> bool {
+        self.expanded_bodies
+            .contains(&(journal.to_path_buf(), date, content.to_string()))
+    }
+
+    /// Rows a body adds below its entry: all of its lines when expanded, else none.
+    fn expanded_body_rows(
+        &self,
+        journal: &Path,
+        date: NaiveDate,
+        content: &str,
+        body: &[String],
+    ) -> usize {
+        if self.is_body_expanded(journal, date, content) {
+            body.len()
+        } else {
+            0
+        }
+    }
+
+    /// Extra rows each visible entry's expanded body adds below it, in display
+    /// order. The entry being edited shows its body in the edit rows instead.
+    #[must_use]
+    pub fn body_rows(&self) -> Vec<usize> {
+        let rows: Vec<usize> = match &self.view {
+            ViewMode::Daily(_) if self.combined_view => return Vec::new(),
+            ViewMode::Daily(state) => {
+                let projected = state
+                    .projected_entries
+                    .iter()
+                    .filter(|e| self.should_show_entry(e))
+                    .map(|e| {
+                        self.expanded_body_rows(
+                            &e.source_journal,
+                            e.source_date,
+                            &e.content,
+                            &e.body,
+                        )
+                    });
+                let path = self.active_path();
+                let local = self
+                    .entry_indices
+                    .iter()
+                    .filter_map(|&i| match &self.lines[i] {
+                        Line::Entry(raw) if self.should_show_raw_entry(raw) => {
+                            Some(self.expanded_body_rows(
+                                path,
+                                self.current_date,
+                                &raw.content,
+                                &raw.body,
+                            ))
+                        }
+                        _ => None,
+                    });
+                projected.chain(local).collect()
+            }
+            ViewMode::Filter(state) => state
+                .entries
+                .iter()
+                .map(|e| {
+                    self.expanded_body_rows(&e.source_journal, e.source_date, &e.content, &e.body)
+                })
+                .collect(),
+        };
+
+        let editing = matches!(
+            self.input_mode,
+            InputMode::Edit(EditContext::Daily { .. } | EditContext::FilterEdit { .. })
+        )
+        .then(|| self.view.selected());
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, n)| if editing == Some(i) { 0 } else { n })
+            .collect()
+    }
+
+    /// Shows or hides the body of the selected entry.
+    pub fn toggle_current_body(&mut self) {
+        if self.combined_view {
+            self.set_error("Details can't be expanded in combined view");
+            return;
+        }
+        let key = match self.get_selected_item() {
+            SelectedItem::Daily { entry, .. } if !entry.body.is_empty() => (
+                self.active_path().to_path_buf(),
+                self.current_date,
+                entry.content.clone(),
+            ),
+            SelectedItem::Projected { entry, .. } | SelectedItem::Filter { entry, .. }
+                if !entry.body.is_empty() =>
+            {
+                (
+                    entry.source_journal.to_path_buf(),
+                    entry.source_date,
+                    entry.content.clone(),
+                )
+            }
+            SelectedItem::None => return,
+            _ => {
+                self.set_status("Entry has no details");
+                return;
+            }
+        };
+        if !self.expanded_bodies.remove(&key) {
+            self.expanded_bodies.insert(key);
+        }
     }
 
     #[must_use]
@@ -131,7 +241,7 @@ impl App {
 
         // Check projected entries
         for entry in &state.projected_entries {
-            let is_completed = matches!(entry.entry_type, EntryType::Task { completed: true });
+            let is_completed = entry.entry_type.is_closed_task();
             if !is_completed {
                 if visible_idx == old_visible_idx {
                     return actual_idx;
@@ -144,8 +254,7 @@ impl App {
         // Check regular entries
         for &line_idx in &self.entry_indices {
             if let Line::Entry(raw_entry) = &self.lines[line_idx] {
-                let is_completed =
-                    matches!(raw_entry.entry_type, EntryType::Task { completed: true });
+                let is_completed = raw_entry.entry_type.is_closed_task();
                 if !is_completed {
                     if visible_idx == old_visible_idx {
                         return actual_idx;
@@ -335,14 +444,14 @@ impl App {
         let hidden_projected = state
             .projected_entries
             .iter()
-            .filter(|e| matches!(e.entry_type, EntryType::Task { completed: true }))
+            .filter(|e| e.entry_type.is_closed_task())
             .count();
         let hidden_regular = self
             .entry_indices
             .iter()
             .filter(|&&i| {
                 if let Line::Entry(raw_entry) = &self.lines[i] {
-                    matches!(raw_entry.entry_type, EntryType::Task { completed: true })
+                    raw_entry.entry_type.is_closed_task()
                 } else {
                     false
                 }
@@ -387,8 +496,9 @@ impl App {
 
     pub(super) fn load_day(&mut self, date: NaiveDate) -> io::Result<Vec<Entry>> {
         self.current_date = date;
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         self.lines = storage::load_day_lines(date, &path)?;
+        self.mark_day_loaded();
         self.entry_indices = Self::compute_entry_indices(&self.lines);
         storage::collect_projected_entries_for_date(date, &path)
     }
@@ -405,7 +515,7 @@ impl App {
 
     pub(super) fn finalize_view_switch(&mut self) {
         self.input_mode = InputMode::Normal;
-        self.executor.clear();
+        self.executor.detach_from_view();
     }
 
     pub(super) fn reset_daily_view(&mut self, date: NaiveDate) -> io::Result<()> {
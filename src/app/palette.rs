@@ -1,7 +1,7 @@
 use std::io;
 
 use crate::registry::COMMANDS;
-use crate::storage::{ProjectInfo, ProjectRegistry, set_hide_from_registry};
+use crate::storage::{self, ProjectInfo, ProjectRegistry, set_hide_from_registry};
 
 use super::{App, CommandPaletteMode, CommandPaletteState, ConfirmContext, InputMode};
 
@@ -34,9 +34,12 @@ impl CommandPaletteState {
 
     pub fn select_prev_tab(&mut self) {
         self.mode = match self.mode {
-            CommandPaletteMode::Commands => CommandPaletteMode::Tags,
+            CommandPaletteMode::Commands => CommandPaletteMode::Commits,
             CommandPaletteMode::Projects => CommandPaletteMode::Commands,
             CommandPaletteMode::Tags => CommandPaletteMode::Projects,
+            CommandPaletteMode::Backups => CommandPaletteMode::Tags,
+            CommandPaletteMode::UndoHistory => CommandPaletteMode::Backups,
+            CommandPaletteMode::Commits => CommandPaletteMode::UndoHistory,
         };
         self.reset_selection();
     }
@@ -45,7 +48,10 @@ impl CommandPaletteState {
         self.mode = match self.mode {
             CommandPaletteMode::Commands => CommandPaletteMode::Projects,
             CommandPaletteMode::Projects => CommandPaletteMode::Tags,
-            CommandPaletteMode::Tags => CommandPaletteMode::Commands,
+            CommandPaletteMode::Tags => CommandPaletteMode::Backups,
+            CommandPaletteMode::Backups => CommandPaletteMode::UndoHistory,
+            CommandPaletteMode::UndoHistory => CommandPaletteMode::Commits,
+            CommandPaletteMode::Commits => CommandPaletteMode::Commands,
         };
         self.reset_selection();
     }
@@ -68,7 +74,10 @@ impl CommandPaletteState {
 impl App {
     /// Returns visible projects sorted with current project first (matching palette display order)
     fn visible_projects_sorted(&self) -> Vec<ProjectInfo> {
-        let current_path = self.journal_context.project_path();
+        let current_path = self
+            .journal_context
+            .project_path()
+            .map(|journal| journal.path());
         let mut projects: Vec<_> = ProjectRegistry::load()
             .projects
             .into_iter()
@@ -87,6 +96,8 @@ impl App {
     pub fn open_palette(&mut self, mode: super::CommandPaletteMode) {
         self.refresh_tag_cache();
         self.input_mode = InputMode::CommandPalette(CommandPaletteState::new_with_mode(mode));
+        self.refresh_backup_cache();
+        self.refresh_commit_cache();
     }
 
     pub fn toggle_command_palette(&mut self) {
@@ -105,12 +116,16 @@ impl App {
         if let InputMode::CommandPalette(state) = &mut self.input_mode {
             state.select_prev_tab();
         }
+        self.refresh_backup_preview();
+        self.refresh_commit_cache();
     }
 
     pub fn command_palette_next_tab(&mut self) {
         if let InputMode::CommandPalette(state) = &mut self.input_mode {
             state.select_next_tab();
         }
+        self.refresh_backup_preview();
+        self.refresh_commit_cache();
     }
 
     pub fn command_palette_select_next(&mut self) {
@@ -122,6 +137,7 @@ impl App {
         if let InputMode::CommandPalette(state) = &mut self.input_mode {
             state.select_next(count);
         }
+        self.refresh_backup_preview();
     }
 
     fn palette_item_count(&self, mode: CommandPaletteMode) -> usize {
@@ -129,6 +145,9 @@ impl App {
             CommandPaletteMode::Commands => COMMANDS.len(),
             CommandPaletteMode::Projects => self.visible_projects_sorted().len(),
             CommandPaletteMode::Tags => self.cached_journal_tags.len(),
+            CommandPaletteMode::Backups => self.cached_backups.len(),
+            CommandPaletteMode::UndoHistory => self.executor.undo_steps().count(),
+            CommandPaletteMode::Commits => self.cached_commits.len(),
         }
     }
 
@@ -136,6 +155,7 @@ impl App {
         if let InputMode::CommandPalette(state) = &mut self.input_mode {
             state.select_prev();
         }
+        self.refresh_backup_preview();
     }
 
     pub fn execute_selected_palette_item(&mut self) -> io::Result<()> {
@@ -156,6 +176,15 @@ impl App {
             CommandPaletteMode::Tags => {
                 self.execute_selected_tag(selected)?;
             }
+            CommandPaletteMode::Backups => {
+                self.execute_selected_backup(selected);
+            }
+            CommandPaletteMode::UndoHistory => {
+                self.execute_selected_undo_step(selected);
+            }
+            CommandPaletteMode::Commits => {
+                self.execute_selected_commit(selected);
+            }
         }
         Ok(())
     }
@@ -169,6 +198,40 @@ impl App {
         Ok(())
     }
 
+    /// Lists the commits made on the current day in the repository holding the
+    /// active journal, when the Commits tab is showing. Git is only run then.
+    fn refresh_commit_cache(&mut self) {
+        let showing = matches!(
+            &self.input_mode,
+            InputMode::CommandPalette(state) if state.mode == CommandPaletteMode::Commits
+        );
+        if !showing {
+            return;
+        }
+        self.cached_commits = match storage::repo_root(self.active_path()) {
+            Some(root) => match storage::commits_on(&root, self.current_date) {
+                Ok(commits) => commits,
+                Err(e) => {
+                    self.set_error(format!("Failed to list commits: {e}"));
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+    }
+
+    fn execute_selected_commit(&mut self, index: usize) {
+        let Some(commit) = self.cached_commits.get(index) else {
+            return;
+        };
+        let hash = commit.hash.clone();
+        self.close_command_palette();
+        match Self::copy_to_clipboard(&hash) {
+            Ok(()) => self.set_status(format!("Copied {hash}")),
+            Err(e) => self.set_error(format!("Failed to copy: {e}")),
+        }
+    }
+
     fn execute_selected_tag(&mut self, index: usize) -> io::Result<()> {
         if let Some(tag) = self.cached_journal_tags.get(index) {
             let query = format!("#{}", tag.name);
@@ -184,8 +247,11 @@ impl App {
         };
 
         match mode {
-            CommandPaletteMode::Commands => {
-                // Commands cannot be deleted
+            CommandPaletteMode::Commands
+            | CommandPaletteMode::Backups
+            | CommandPaletteMode::UndoHistory
+            | CommandPaletteMode::Commits => {
+                // Commands, backups, undo steps and commits cannot be deleted
             }
             CommandPaletteMode::Projects => {
                 self.palette_delete_project(selected)?;
@@ -200,7 +200,7 @@ impl App {
 
     fn collect_delete_targets_from_selected(&self) -> Vec<DeleteTarget> {
         let current_date = self.current_date;
-        let active_path = self.active_path().to_path_buf();
+        let active_path = self.active_path().clone();
         self.collect_targets_from_selected(|entry| match entry {
             SelectedEntry::Projected(projected) => {
                 if matches!(projected.source_type, SourceType::Recurring) {
@@ -256,15 +256,9 @@ impl App {
     fn collect_yank_targets_from_selected(&self) -> Vec<YankTarget> {
         self.collect_targets_from_selected(|entry| {
             let content = match entry {
-                SelectedEntry::Projected(projected) => {
-                    format!("{}{}", projected.entry_type.prefix(), projected.content)
-                }
-                SelectedEntry::Daily { entry, .. } => {
-                    format!("{}{}", entry.prefix(), entry.content)
-                }
-                SelectedEntry::Filter { entry, .. } => {
-                    format!("{}{}", entry.entry_type.prefix(), entry.content)
-                }
+                SelectedEntry::Projected(projected) => Self::yank_text(projected.to_raw()),
+                SelectedEntry::Daily { entry, .. } => Self::yank_text(entry.clone()),
+                SelectedEntry::Filter { entry, .. } => Self::yank_text(entry.to_raw()),
             };
             Some(YankTarget { content })
         })
@@ -278,7 +272,10 @@ impl App {
                 original_type: projected.entry_type.clone(),
             }),
             SelectedEntry::Daily { line_idx, entry } => Some(super::actions::CycleTarget {
-                location: TagRemovalTarget::Daily { line_idx, source_path: None },
+                location: TagRemovalTarget::Daily {
+                    line_idx,
+                    source_path: None,
+                },
                 original_type: entry.entry_type.clone(),
             }),
             SelectedEntry::Filter { index, entry } => Some(super::actions::CycleTarget {
@@ -300,7 +297,10 @@ impl App {
                 projected.content.clone(),
             )),
             SelectedEntry::Daily { line_idx, entry } => Some(super::actions::ContentTarget::new(
-                TagRemovalTarget::Daily { line_idx, source_path: None },
+                TagRemovalTarget::Daily {
+                    line_idx,
+                    source_path: None,
+                },
                 entry.content.clone(),
             )),
             SelectedEntry::Filter { index, entry } => Some(super::actions::ContentTarget::new(
@@ -407,6 +407,16 @@ impl App {
         self.execute_action(Box::new(action))
     }
 
+    /// Move every selected task on to its next state
+    pub fn advance_selected_task_states(&mut self) -> io::Result<()> {
+        let targets = self.collect_cycle_targets_from_selected();
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        self.execute_task_state_action(super::actions::SetTaskState::advance(targets))
+    }
+
     /// Check if in selection mode and get selection state
     pub fn get_selection_state(&self) -> Option<&SelectionState> {
         if let InputMode::Selection(ref state) = self.input_mode {
@@ -427,6 +437,9 @@ impl App {
                 Some(RawEntry {
                     entry_type: projected.entry_type.clone(),
                     content: projected.content.clone(),
+                    depth: projected.depth,
+                    indent: projected.indent.clone(),
+                    body: projected.body.clone(),
                 })
             }
             SelectedEntry::Daily { entry, .. } => Some(entry.clone()),
@@ -440,6 +453,9 @@ impl App {
                 Some(RawEntry {
                     entry_type: entry.entry_type.clone(),
                     content,
+                    depth: entry.depth,
+                    indent: entry.indent.clone(),
+                    body: entry.body.clone(),
                 })
             }
         })
@@ -29,7 +29,8 @@ fn count_tag_occurrences(journal: &str, tag: &str, line_filter: Option<fn(&str)
 }
 
 fn is_completed_task(line: &str) -> bool {
-    line.trim_start().starts_with("- [x] ")
+    let trimmed = line.trim_start();
+    trimmed.starts_with("- [x] ") || trimmed.starts_with("- [X] ")
 }
 
 fn replace_tag_matches(journal: &str, regex: &Regex, replacement: Option<&str>) -> String {
@@ -65,7 +66,7 @@ impl App {
     }
 
     pub(super) fn delete_all_tag_occurrences(&mut self, tag: &str) -> io::Result<usize> {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let journal = storage::load_journal(&path)?;
         let count = count_tag_occurrences(&journal, tag, None);
 
@@ -73,6 +74,7 @@ impl App {
         let new_journal = replace_tag_matches(&journal, &tag_regex, None);
         let cleaned = Self::clean_empty_entries(&new_journal);
 
+        self.backup_active_journal("tag-delete")?;
         storage::save_journal(&path, &cleaned)?;
         Ok(count)
     }
@@ -86,7 +88,7 @@ impl App {
     }
 
     pub(super) fn delete_tag_from_completed(&mut self, tag: &str) -> io::Result<usize> {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let journal = storage::load_journal(&path)?;
         let count = count_tag_occurrences(&journal, tag, Some(is_completed_task));
 
@@ -104,6 +106,7 @@ impl App {
             .join("\n");
         let cleaned = Self::clean_empty_entries(&new_journal);
 
+        self.backup_active_journal("tag-delete-completed")?;
         storage::save_journal(&path, &cleaned)?;
         Ok(count)
     }
@@ -122,7 +125,7 @@ impl App {
         old_tag: &str,
         new_tag: &str,
     ) -> io::Result<usize> {
-        let path = self.active_path().to_path_buf();
+        let path = self.active_path().clone();
         let journal = storage::load_journal(&path)?;
         let count = count_tag_occurrences(&journal, old_tag, None);
 
@@ -131,6 +134,7 @@ impl App {
         let new_journal = replace_tag_matches(&journal, &tag_regex, Some(&replacement));
         let cleaned = Self::clean_empty_entries(&new_journal);
 
+        self.backup_active_journal("tag-rename")?;
         storage::save_journal(&path, &cleaned)?;
         Ok(count)
     }
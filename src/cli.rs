@@ -0,0 +1,539 @@
+use std::io::{self, Write};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::import::{self, ImportFormat};
+use crate::report::{self, ReportFormat};
+use crate::storage::{
+    self, Entry, EntryType, Filter, Journal, JournalContext, JournalProblem, JournalSlot, Line,
+    RawEntry, ScheduleKind, TaskState,
+};
+
+/// Headless subcommands that operate on the journal without launching the TUI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Append an entry to today's page.
+    Add(String),
+    /// Print entries matching a filter query.
+    List(String),
+    /// Serialize entries matching a filter query for other tools.
+    Query { query: String, format: QueryFormat },
+    /// Report problems in the journal, repairing what can be with `--fix`.
+    Check { fix: bool },
+    /// Add the tasks in a todo.txt file or Taskwarrior export to the journal.
+    Import {
+        file: String,
+        /// `--format`, detected from the file's content when not given
+        format: Option<String>,
+        dry_run: bool,
+        /// `--hub` imports into the hub journal even inside a project
+        hub: bool,
+    },
+    /// Print the journal's events and open tasks as an iCalendar feed.
+    ExportIcs {
+        /// `--output`, a file to write the feed to instead of stdout, and
+        /// empty when given without a value
+        output: Option<String>,
+    },
+    /// Print what was done, what's open and what's blocked across journals.
+    Report {
+        /// `--from`, a filter date; yesterday when not given
+        from: Option<String>,
+        /// `--to`, a filter date; today when not given
+        to: Option<String>,
+        /// `--markdown` or `--text`, overriding `report_format`
+        format: Option<ReportFormat>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryFormat {
+    /// A single JSON array (`--json`, the default).
+    Json,
+    /// One JSON object per line (`--jsonl`).
+    JsonLines,
+}
+
+/// Parses headless subcommands from the remaining CLI arguments.
+/// Returns None when the arguments should launch the TUI instead, and an
+/// `InvalidInput` error for a subcommand given arguments it can't take.
+#[must_use]
+pub fn parse_command(args: &[String]) -> Option<io::Result<CliCommand>> {
+    let (name, rest) = args.split_first()?;
+    let joined = rest.join(" ");
+    let command = match name.as_str() {
+        "add" => Ok(CliCommand::Add(joined)),
+        "list" => Ok(CliCommand::List(joined)),
+        "query" => {
+            let mut format = QueryFormat::Json;
+            let mut terms = Vec::new();
+            for arg in rest {
+                match arg.as_str() {
+                    "--json" => format = QueryFormat::Json,
+                    "--jsonl" => format = QueryFormat::JsonLines,
+                    _ => terms.push(arg.as_str()),
+                }
+            }
+            Ok(CliCommand::Query {
+                query: terms.join(" "),
+                format,
+            })
+        }
+        "check" => Ok(CliCommand::Check {
+            fix: rest.iter().any(|arg| arg == "--fix"),
+        }),
+        "import" => parse_import(rest),
+        "export-ics" => {
+            let output = rest
+                .iter()
+                .position(|arg| arg == "--output" || arg == "-o")
+                .map(|i| {
+                    rest.get(i + 1)
+                        .filter(|value| !value.starts_with("--"))
+                        .cloned()
+                        .unwrap_or_default()
+                });
+            Ok(CliCommand::ExportIcs { output })
+        }
+        "report" => parse_report(rest),
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// `import <file> [--format todotxt|taskwarrior] [--dry-run] [--hub]`
+fn parse_import(args: &[String]) -> io::Result<CliCommand> {
+    let mut file: Option<&String> = None;
+    let mut format = None;
+    let mut dry_run = false;
+    let mut hub = false;
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = args
+                    .next_if(|value| !value.starts_with('-'))
+                    .ok_or_else(|| {
+                        invalid_input("--format needs a value: todotxt or taskwarrior")
+                    })?;
+                format = Some(value.clone());
+            }
+            "--dry-run" => dry_run = true,
+            "--hub" => hub = true,
+            flag if flag.starts_with('-') => {
+                return Err(invalid_input(format!("Unknown import option: {flag}")));
+            }
+            _ => {
+                if let Some(first) = file {
+                    return Err(invalid_input(format!(
+                        "Only one file can be imported at a time, got {first} and {arg}"
+                    )));
+                }
+                file = Some(arg);
+            }
+        }
+    }
+    Ok(CliCommand::Import {
+        file: file.cloned().unwrap_or_default(),
+        format,
+        dry_run,
+        hub,
+    })
+}
+
+/// `report [--from <date>] [--to <date>] [--markdown|--text]`
+fn parse_report(args: &[String]) -> io::Result<CliCommand> {
+    let mut from = None;
+    let mut to = None;
+    let mut format = None;
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            flag @ ("--from" | "--to") => {
+                let value = args
+                    .next_if(|value| !value.starts_with("--"))
+                    .ok_or_else(|| invalid_input(format!("{flag} needs a date")))?;
+                if flag == "--from" {
+                    from = Some(value.clone());
+                } else {
+                    to = Some(value.clone());
+                }
+            }
+            "--markdown" => format = Some(ReportFormat::Markdown),
+            "--text" => format = Some(ReportFormat::Text),
+            _ => return Err(invalid_input(format!("Unknown report option: {arg}"))),
+        }
+    }
+    Ok(CliCommand::Report { from, to, format })
+}
+
+fn invalid_input(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
+
+pub fn run(
+    command: &CliCommand,
+    config: &Config,
+    context: &JournalContext,
+    today: NaiveDate,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    match command {
+        CliCommand::Add(text) => add_entry(text, config, context, today),
+        CliCommand::List(query) => list_entries(query, config, context, out),
+        CliCommand::Query { query, format } => query_entries(query, *format, config, context, out),
+        CliCommand::Check { fix } => check_journal(*fix, config, context, out),
+        CliCommand::Import {
+            file,
+            format,
+            dry_run,
+            hub,
+        } => {
+            let path = if *hub {
+                context.hub_path()
+            } else {
+                context.active_path()
+            };
+            import_tasks(file, format.as_deref(), *dry_run, path, config, today, out)
+        }
+        CliCommand::ExportIcs { output } => export_ics(output.as_deref(), context, out),
+        CliCommand::Report { from, to, format } => print_report(
+            from.as_deref(),
+            to.as_deref(),
+            *format,
+            config,
+            context,
+            today,
+            out,
+        ),
+    }
+}
+
+/// Appends an entry to today's page. Plain text becomes a task; text starting
+/// with a journal prefix (`- [ ] `, `- `, `* `) keeps that entry type.
+fn add_entry(
+    text: &str,
+    config: &Config,
+    context: &JournalContext,
+    today: NaiveDate,
+) -> io::Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Nothing to add",
+        ));
+    }
+
+    let mut entry = match storage::parse_lines(text).into_iter().next() {
+        Some(Line::Entry(entry)) => entry,
+        _ => RawEntry {
+            entry_type: EntryType::OPEN_TASK,
+            content: text.to_string(),
+            depth: 0,
+            indent: String::new(),
+            body: Vec::new(),
+        },
+    };
+
+    let content = storage::expand_favorite_tags(&entry.content, &config.favorite_tags);
+    let (content, _) = storage::normalize_entry_structure(&content);
+    entry.content = content.trim_end().to_string();
+
+    let path = context.active_path();
+    if config.git_branch_tags && context.active_slot() == JournalSlot::Project {
+        entry.content = storage::stamp_branch(&entry.content, path);
+    }
+    storage::update_day_lines(today, path, |lines| lines.push(Line::Entry(entry)))?;
+    update_ics_export(path, config)
+}
+
+/// Rewrites the journal's `.ics` feed after a command changed it, if
+/// configured to.
+fn update_ics_export(path: &Journal, config: &Config) -> io::Result<()> {
+    if config.ics_export {
+        storage::update_ics_export(path)?;
+    }
+    Ok(())
+}
+
+/// Prints entries matching the query, one per line, prefixed with their source date.
+fn list_entries(
+    query: &str,
+    config: &Config,
+    context: &JournalContext,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let entries = collect_matching(query, config, context)?;
+    for entry in entries {
+        writeln!(
+            out,
+            "{} {}{}",
+            entry.source_date.format("%Y/%m/%d"),
+            entry.prefix(),
+            storage::strip_done_meta(&entry.content)
+        )?;
+    }
+    Ok(())
+}
+
+/// A filter result as exposed to external tools. `source_date` and `line_index`
+/// together address the entry within `source_journal`.
+#[derive(Serialize)]
+struct EntryRecord {
+    entry_type: &'static str,
+    completed: Option<bool>,
+    state: Option<&'static str>,
+    priority: Option<u8>,
+    content: String,
+    body: Vec<String>,
+    tags: Vec<String>,
+    source_date: NaiveDate,
+    line_index: usize,
+    source_journal: String,
+    recurring: Option<String>,
+    due: Option<NaiveDate>,
+    scheduled: Option<NaiveDate>,
+}
+
+impl From<&Entry> for EntryRecord {
+    fn from(entry: &Entry) -> Self {
+        let (entry_type, state) = match entry.entry_type {
+            EntryType::Task { state } => ("task", Some(state)),
+            EntryType::Note => ("note", None),
+            EntryType::Event => ("event", None),
+        };
+        let content = storage::strip_done_meta(&entry.content);
+        let scheduled = storage::extract_scheduled_date(&content, entry.source_date);
+        let scheduled_on = |kind| scheduled.filter(|s| s.kind == kind).map(|s| s.date);
+        Self {
+            entry_type,
+            completed: state.map(|state| state == TaskState::Done),
+            state: state.map(TaskState::label),
+            priority: storage::extract_priority(&content),
+            tags: storage::extract_tags(&content),
+            recurring: storage::extract_recurrence(&content, entry.source_date)
+                .map(|r| r.to_string()),
+            due: scheduled_on(ScheduleKind::Due),
+            scheduled: scheduled_on(ScheduleKind::On),
+            content,
+            body: entry.body.clone(),
+            source_date: entry.source_date,
+            line_index: entry.line_index,
+            source_journal: entry.source_journal.display().to_string(),
+        }
+    }
+}
+
+fn query_entries(
+    query: &str,
+    format: QueryFormat,
+    config: &Config,
+    context: &JournalContext,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let entries = collect_matching(query, config, context)?;
+    let records: Vec<EntryRecord> = entries.iter().map(EntryRecord::from).collect();
+
+    match format {
+        QueryFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, &records)?;
+            writeln!(out)?;
+        }
+        QueryFormat::JsonLines => {
+            for record in &records {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints each problem in the journal as `file:line: problem`. With `fix`, the
+/// journal is backed up and repaired first, and only what's left is printed.
+/// Fails if any problems remain, so it can gate commits.
+fn check_journal(
+    fix: bool,
+    config: &Config,
+    context: &JournalContext,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let path = context.active_path();
+    let is_fixable = |problem: &JournalProblem| problem.kind.is_fixable();
+    if fix && storage::check_journal(path)?.iter().any(is_fixable) {
+        storage::backup_journal(path, &config.get_backup_dir(), "check", config.backup_count)?;
+        let fixed = storage::fix_journal(path)?;
+        writeln!(out, "Fixed {fixed} problems")?;
+    }
+
+    let problems = storage::check_journal(path)?;
+    for problem in &problems {
+        writeln!(out, "{problem}")?;
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let hint = if !fix && problems.iter().any(is_fixable) {
+        " (run with --fix to repair some)"
+    } else {
+        ""
+    };
+    Err(io::Error::other(format!(
+        "{} problems found{hint}",
+        problems.len()
+    )))
+}
+
+/// Imports a todo.txt file or Taskwarrior export into the journal at `path`,
+/// backing the journal up first. A dry run prints the entries each day would
+/// get instead.
+fn import_tasks(
+    file: &str,
+    format: Option<&str>,
+    dry_run: bool,
+    path: &Journal,
+    config: &Config,
+    today: NaiveDate,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    if file.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Usage: corner import <file> [--format todotxt|taskwarrior] [--dry-run] [--hub]",
+        ));
+    }
+    let content = std::fs::read_to_string(file)?;
+    let format = match format {
+        Some(name) => ImportFormat::from_name(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unknown import format: {name}"),
+            )
+        })?,
+        None => ImportFormat::detect(&content),
+    };
+    let parsed = import::parse_import(&content, format, today)?;
+
+    if dry_run {
+        for task in &parsed.tasks {
+            writeln!(
+                out,
+                "{} {}",
+                task.date.format("%Y/%m/%d"),
+                storage::serialize_entry(&task.entry)
+            )?;
+        }
+    } else {
+        storage::backup_journal(
+            path,
+            &config.get_backup_dir(),
+            "import",
+            config.backup_count,
+        )?;
+    }
+    let summary = import::write_import(&parsed.tasks, path, dry_run)?;
+    if !dry_run {
+        update_ics_export(path, config)?;
+    }
+
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    write!(
+        out,
+        "{verb} {} tasks into {} days of {}",
+        summary.added,
+        summary.days,
+        path.display()
+    )?;
+    if summary.duplicates > 0 {
+        write!(out, " ({} already there)", summary.duplicates)?;
+    }
+    writeln!(out)?;
+    for skipped in &parsed.skipped {
+        writeln!(out, "Skipped {skipped}")?;
+    }
+    Ok(())
+}
+
+/// Writes the active journal's ICS feed to `output`, or prints it.
+fn export_ics(
+    output: Option<&str>,
+    context: &JournalContext,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    if output == Some("") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--output needs a file to write the feed to",
+        ));
+    }
+    let feed = storage::journal_ics(context.active_path())?;
+    match output {
+        Some(file) => std::fs::write(file, feed),
+        None => out.write_all(feed.as_bytes()),
+    }
+}
+
+/// Prints the report of the hub and registered project journals from `from`
+/// through `to`, in `format` or the configured one.
+fn print_report(
+    from: Option<&str>,
+    to: Option<&str>,
+    format: Option<ReportFormat>,
+    config: &Config,
+    context: &JournalContext,
+    today: NaiveDate,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let parse = |input: Option<&str>, default: NaiveDate| match input {
+        Some(input) => storage::parse_filter_date(input, today).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unknown date: {input}"),
+            )
+        }),
+        None => Ok(default),
+    };
+    let start = parse(from, today.pred_opt().unwrap_or(today))?;
+    let end = parse(to, today)?;
+    if start > end {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Report starts after it ends: {start} is after {end}"),
+        ));
+    }
+
+    let journals = report::report_journals(context.hub_path());
+    let reports = report::build_report(&journals, start, end, &config.report_blocker_tag)?;
+    if reports.is_empty() {
+        return writeln!(out, "Nothing to report");
+    }
+    let format = format.unwrap_or(config.report_format);
+    let template = config.report_template.as_deref();
+    out.write_all(report::render_report(&reports, start, end, format, template).as_bytes())
+}
+
+/// Builds a filter the same way the filter view does and collects its matches.
+/// An empty query falls back to `default_filter`.
+fn collect_matching(
+    query: &str,
+    config: &Config,
+    context: &JournalContext,
+) -> io::Result<Vec<Entry>> {
+    let filter = build_filter(query, config)?;
+    storage::collect_filtered_entries(&filter, context.active_path())
+}
+
+fn build_filter(query: &str, config: &Config) -> io::Result<Filter> {
+    let query = if query.trim().is_empty() {
+        config.default_filter.as_str()
+    } else {
+        query
+    };
+
+    let query = storage::expand_saved_filters(query, &config.filters);
+    storage::parse_filter_query(&query)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid filter: {e}")))
+}
@@ -4,12 +4,26 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
+use chrono::{Days, NaiveDate};
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
-use crate::storage::find_git_root;
-
-const VALID_TIDY_TYPES: &[&str] = &["completed", "uncompleted", "notes", "events"];
+use crate::report::{DEFAULT_BLOCKER_TAG, ReportFormat};
+use crate::storage::{
+    self, ArchiveOptions, DayHeaderFormat, Journal, JournalLayout, find_git_root,
+};
+
+const VALID_TIDY_TYPES: &[&str] = &[
+    "completed",
+    "uncompleted",
+    "notes",
+    "events",
+    "in-progress",
+    "blocked",
+    "migrated",
+    "cancelled",
+    "prio",
+];
 
 // Global profile context, initialized at startup
 static PROFILE: OnceLock<ProfileContext> = OnceLock::new();
@@ -222,6 +236,18 @@ fn default_header_date_format() -> String {
     "%A, %b %-d".to_string()
 }
 
+fn default_day_header_level() -> usize {
+    1
+}
+
+fn default_day_header_format() -> String {
+    "%Y/%m/%d".to_string()
+}
+
+fn default_daily_notes_format() -> String {
+    storage::DEFAULT_DAILY_NOTES_FORMAT.to_string()
+}
+
 fn default_scratchpad_file() -> PathBuf {
     get_config_dir().join("scratchpad.md")
 }
@@ -230,12 +256,40 @@ fn default_auto_init_project() -> bool {
     true
 }
 
+fn default_backup_count() -> usize {
+    10
+}
+
+fn default_archive_after_days() -> u64 {
+    90
+}
+
+fn default_report_blocker_tag() -> String {
+    DEFAULT_BLOCKER_TAG.to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
     pub hub_file: Option<String>,
     #[serde(default)]
     pub journal_file: Option<String>,
+    /// Whether journals are one file, a directory of daily or monthly files,
+    /// or a folder of daily notes
+    #[serde(default)]
+    pub journal_layout: JournalLayout,
+    /// strftime pattern naming the notes of a daily-notes journal, without `.md`
+    #[serde(default = "default_daily_notes_format")]
+    pub daily_notes_format: String,
+    /// Heading level of the day headers written to journals
+    #[serde(default = "default_day_header_level")]
+    pub day_header_level: usize,
+    /// strftime pattern for the date in day headers written to journals
+    #[serde(default = "default_day_header_format")]
+    pub day_header_format: String,
+    /// Further strftime patterns accepted as day headers when reading
+    #[serde(default)]
+    pub day_header_read_formats: Vec<String>,
     #[serde(default)]
     pub scratchpad_file: Option<String>,
     #[serde(default = "default_tidy_order")]
@@ -269,6 +323,46 @@ pub struct Config {
     /// Whether to hide footer help hints
     #[serde(default)]
     pub hide_footer_help: bool,
+    /// Number of rolling journal backups to keep (0 disables backups)
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+    /// Directory for journal backups
+    #[serde(default)]
+    pub backup_dir: Option<String>,
+    /// Whether completing a task records the date as `<!-- done: YYYY-MM-DD -->`
+    #[serde(default)]
+    pub record_completion_dates: bool,
+    /// Days older than this many days are moved by the archive command
+    #[serde(default = "default_archive_after_days")]
+    pub archive_after_days: u64,
+    /// Whether the archive command keeps days that still have open tasks
+    #[serde(default = "default_true")]
+    pub archive_completed_only: bool,
+    /// Whether to archive into one file per year instead of a single archive
+    #[serde(default)]
+    pub archive_by_year: bool,
+    /// Whether journals keep their events and open tasks in an `.ics` file
+    /// beside them, rewritten once saves settle
+    #[serde(default)]
+    pub ics_export: bool,
+    /// Whether the report command renders markdown or plain text
+    #[serde(default)]
+    pub report_format: ReportFormat,
+    /// Template each journal's report is rendered through, in place of the
+    /// format's default
+    #[serde(default)]
+    pub report_template: Option<String>,
+    /// Tag marking notes and tasks as blockers in reports
+    #[serde(default = "default_report_blocker_tag")]
+    pub report_blocker_tag: String,
+    /// Whether new entries in project journals are tagged with the current
+    /// git branch
+    #[serde(default)]
+    pub git_branch_tags: bool,
+    /// Whether journal changes are committed to git once saves settle and on
+    /// quit
+    #[serde(default)]
+    pub auto_commit: bool,
 }
 
 /// Raw config for deserialization - all fields are Option to distinguish "not set" from "set to default".
@@ -278,6 +372,15 @@ pub struct Config {
 struct RawConfig {
     pub hub_file: Option<String>,
     pub journal_file: Option<String>,
+    /// Whether journals are one file, or a directory of daily or monthly files
+    pub journal_layout: Option<JournalLayout>,
+    pub daily_notes_format: Option<String>,
+    /// Heading level of day headers
+    pub day_header_level: Option<usize>,
+    /// strftime pattern for the date in day headers
+    pub day_header_format: Option<String>,
+    /// Further patterns accepted as day headers when reading
+    pub day_header_read_formats: Option<Vec<String>>,
     pub scratchpad_file: Option<String>,
     pub tidy_order: Option<Vec<String>>,
     pub favorite_tags: Option<HashMap<String, String>>,
@@ -297,6 +400,30 @@ struct RawConfig {
     pub defer_skip_weekends: Option<bool>,
     /// Whether to hide footer help hints
     pub hide_footer_help: Option<bool>,
+    /// Number of rolling journal backups to keep
+    pub backup_count: Option<usize>,
+    /// Directory for journal backups (base config only)
+    pub backup_dir: Option<String>,
+    /// Whether completing a task records the date
+    pub record_completion_dates: Option<bool>,
+    /// Age in days after which days are archived
+    pub archive_after_days: Option<u64>,
+    /// Whether only days without open tasks are archived
+    pub archive_completed_only: Option<bool>,
+    /// Whether archives are split by year
+    pub archive_by_year: Option<bool>,
+    /// Whether journals keep an `.ics` file up to date
+    pub ics_export: Option<bool>,
+    /// Report output format
+    pub report_format: Option<ReportFormat>,
+    /// Per-journal report template
+    pub report_template: Option<String>,
+    /// Tag that marks blockers in reports
+    pub report_blocker_tag: Option<String>,
+    /// Whether project entries get a branch tag
+    pub git_branch_tags: Option<bool>,
+    /// Whether journal changes are committed automatically
+    pub auto_commit: Option<bool>,
 }
 
 impl RawConfig {
@@ -304,6 +431,17 @@ impl RawConfig {
         Config {
             hub_file: self.hub_file,
             journal_file: self.journal_file,
+            journal_layout: self.journal_layout.unwrap_or_default(),
+            daily_notes_format: self
+                .daily_notes_format
+                .unwrap_or_else(default_daily_notes_format),
+            day_header_level: self
+                .day_header_level
+                .unwrap_or_else(default_day_header_level),
+            day_header_format: self
+                .day_header_format
+                .unwrap_or_else(default_day_header_format),
+            day_header_read_formats: self.day_header_read_formats.unwrap_or_default(),
             scratchpad_file: self.scratchpad_file,
             tidy_order: self.tidy_order.unwrap_or_else(default_tidy_order),
             favorite_tags: self.favorite_tags.unwrap_or_else(default_favorite_tags),
@@ -322,6 +460,22 @@ impl RawConfig {
             sidebar_default: self.sidebar_default.unwrap_or_default(),
             defer_skip_weekends: self.defer_skip_weekends.unwrap_or(false),
             hide_footer_help: self.hide_footer_help.unwrap_or(false),
+            backup_count: self.backup_count.unwrap_or_else(default_backup_count),
+            backup_dir: self.backup_dir,
+            record_completion_dates: self.record_completion_dates.unwrap_or(false),
+            archive_after_days: self
+                .archive_after_days
+                .unwrap_or_else(default_archive_after_days),
+            archive_completed_only: self.archive_completed_only.unwrap_or(true),
+            archive_by_year: self.archive_by_year.unwrap_or(false),
+            ics_export: self.ics_export.unwrap_or(false),
+            report_format: self.report_format.unwrap_or_default(),
+            report_template: self.report_template,
+            report_blocker_tag: self
+                .report_blocker_tag
+                .unwrap_or_else(default_report_blocker_tag),
+            git_branch_tags: self.git_branch_tags.unwrap_or(false),
+            auto_commit: self.auto_commit.unwrap_or(false),
         }
     }
 
@@ -332,10 +486,18 @@ impl RawConfig {
     /// - auto_init_project: base only (global setting)
     /// - calendars: base only (security - URLs shouldn't be in repos)
     /// - calendar_visibility: base only (global setting)
+    /// - backup_dir: base only (global setting)
     fn merge_over(self, base: RawConfig) -> RawConfig {
         RawConfig {
             hub_file: base.hub_file,
             journal_file: self.journal_file,
+            journal_layout: self.journal_layout.or(base.journal_layout),
+            daily_notes_format: self.daily_notes_format.or(base.daily_notes_format),
+            day_header_level: self.day_header_level.or(base.day_header_level),
+            day_header_format: self.day_header_format.or(base.day_header_format),
+            day_header_read_formats: self
+                .day_header_read_formats
+                .or(base.day_header_read_formats),
             scratchpad_file: self.scratchpad_file.or(base.scratchpad_file),
             tidy_order: self.tidy_order.or(base.tidy_order),
             default_filter: self.default_filter.or(base.default_filter),
@@ -350,6 +512,20 @@ impl RawConfig {
             sidebar_default: base.sidebar_default,
             defer_skip_weekends: self.defer_skip_weekends.or(base.defer_skip_weekends),
             hide_footer_help: self.hide_footer_help.or(base.hide_footer_help),
+            backup_count: self.backup_count.or(base.backup_count),
+            backup_dir: base.backup_dir,
+            record_completion_dates: self
+                .record_completion_dates
+                .or(base.record_completion_dates),
+            archive_after_days: self.archive_after_days.or(base.archive_after_days),
+            archive_completed_only: self.archive_completed_only.or(base.archive_completed_only),
+            archive_by_year: self.archive_by_year.or(base.archive_by_year),
+            ics_export: self.ics_export.or(base.ics_export),
+            report_format: self.report_format.or(base.report_format),
+            report_template: self.report_template.or(base.report_template),
+            report_blocker_tag: self.report_blocker_tag.or(base.report_blocker_tag),
+            git_branch_tags: self.git_branch_tags.or(base.git_branch_tags),
+            auto_commit: self.auto_commit.or(base.auto_commit),
         }
     }
 }
@@ -505,12 +681,15 @@ impl Config {
         Ok(true)
     }
 
-    pub fn get_hub_journal_path(&self) -> PathBuf {
-        if let Some(ref file) = self.hub_file {
+    /// Get hub journal path, defaulting to hub_journal.md (or a hub_journal/
+    /// directory for directory layouts) in the config directory.
+    pub fn get_hub_journal_path(&self) -> Journal {
+        let path = if let Some(ref file) = self.hub_file {
             resolve_path(file)
         } else {
-            get_default_journal_path()
-        }
+            self.default_journal_name(get_default_journal_path())
+        };
+        self.journal(path)
     }
 
     pub fn get_scratchpad_path(&self) -> PathBuf {
@@ -521,13 +700,63 @@ impl Config {
         }
     }
 
-    /// Get project journal path, defaulting to .corner/journal.md if not configured.
+    /// Get the backup directory, defaulting to backups/ in the config directory.
     #[must_use]
-    pub fn get_project_journal_path(&self, project_root: &Path) -> PathBuf {
-        if let Some(ref file) = self.journal_file {
+    pub fn get_backup_dir(&self) -> PathBuf {
+        if let Some(ref dir) = self.backup_dir {
+            expand_tilde(dir)
+        } else {
+            get_config_dir().join("backups")
+        }
+    }
+
+    /// Get project journal path, defaulting to .corner/journal.md (or a
+    /// .corner/journal/ directory for directory layouts) if not configured.
+    #[must_use]
+    pub fn get_project_journal_path(&self, project_root: &Path) -> Journal {
+        let path = if let Some(ref file) = self.journal_file {
             expand_tilde(file)
         } else {
-            project_root.join(".corner").join("journal.md")
+            self.default_journal_name(project_root.join(".corner").join("journal.md"))
+        };
+        self.journal(path)
+    }
+
+    /// The journal at `path`, stored with this config's layout and day headers.
+    #[must_use]
+    pub fn journal(&self, path: PathBuf) -> Journal {
+        Journal::new(path)
+            .with_layout(self.journal_layout)
+            .with_daily_notes_format(&self.daily_notes_format)
+            .with_day_header(self.validated_day_header())
+    }
+
+    /// The configured day header format, or the default if the level isn't
+    /// 1-6 or the pattern can't be read back as a date.
+    #[must_use]
+    pub fn validated_day_header(&self) -> DayHeaderFormat {
+        DayHeaderFormat::new(self.day_header_level, &self.day_header_format)
+            .unwrap_or_default()
+            .with_read_patterns(&self.day_header_read_formats)
+    }
+
+    /// What the archive command moves when run on `today`.
+    #[must_use]
+    pub fn archive_options(&self, today: NaiveDate) -> ArchiveOptions {
+        ArchiveOptions {
+            before: today - Days::new(self.archive_after_days),
+            completed_only: self.archive_completed_only,
+            by_year: self.archive_by_year,
+        }
+    }
+
+    /// Directory journals drop the `.md` from the default file name.
+    fn default_journal_name(&self, path: PathBuf) -> PathBuf {
+        match self.journal_layout {
+            JournalLayout::File => path,
+            JournalLayout::Daily | JournalLayout::Monthly | JournalLayout::DailyNotes => {
+                path.with_extension("")
+            }
         }
     }
 }
@@ -207,6 +207,32 @@ impl CursorBuffer {
     }
 }
 
+/// Calculates the (row, column) position of a cursor within multi-line text
+/// where each line is word-wrapped on its own.
+#[must_use]
+pub fn cursor_position_in_lines(
+    text: &str,
+    cursor_char_pos: usize,
+    max_width: usize,
+) -> (usize, usize) {
+    let mut row = 0;
+    let mut remaining = cursor_char_pos;
+    let mut lines = text.split('\n').peekable();
+
+    while let Some(line) = lines.next() {
+        let line_chars = line.chars().count();
+        if remaining <= line_chars || lines.peek().is_none() {
+            let before: String = line.chars().take(remaining).collect();
+            let (line_row, col) = cursor_position_in_wrap(line, before.width(), max_width);
+            return (row + line_row, col);
+        }
+        // Skip past this line and its newline
+        remaining -= line_chars + 1;
+        row += cursor_position_in_wrap(line, line.width(), max_width).0 + 1;
+    }
+    (row, 0)
+}
+
 /// Calculates the (row, column) position of a cursor within word-wrapped text.
 /// Used to position the terminal cursor correctly when editing wrapped content.
 #[must_use]
@@ -490,6 +490,8 @@ pub fn parse_action_id(s: &str) -> Option<KeyActionId> {
         "remove_last_tag" => Some(KeyActionId::RemoveLastTag),
         "remove_all_tags" => Some(KeyActionId::RemoveAllTags),
         "cycle_entry_type" => Some(KeyActionId::CycleEntryType),
+        "mark_task_state" => Some(KeyActionId::MarkTaskState),
+        "toggle_body" => Some(KeyActionId::ToggleBody),
         "selection" => Some(KeyActionId::Selection),
         "selection_extend_range" => Some(KeyActionId::SelectionExtendRange),
         "toggle_filter_view" => Some(KeyActionId::ToggleFilterView),
@@ -502,6 +504,7 @@ pub fn parse_action_id(s: &str) -> Option<KeyActionId> {
         "tidy_entries" => Some(KeyActionId::TidyEntries),
         "hide" => Some(KeyActionId::Hide),
         "autocomplete" => Some(KeyActionId::Autocomplete),
+        "insert_line_break" => Some(KeyActionId::InsertLineBreak),
         "toggle_calendar_sidebar" => Some(KeyActionId::ToggleCalendarSidebar),
         "toggle_agenda" => Some(KeyActionId::ToggleAgenda),
         "date_picker" => Some(KeyActionId::DatePicker),
@@ -127,6 +127,13 @@ fn dispatch_entry_operation(app: &mut App, action: KeyActionId) -> io::Result<()
                 app.cycle_current_entry_type()?;
             }
         },
+        MarkTaskState => {
+            dispatch_entry_op(
+                app,
+                App::advance_selected_task_states,
+                App::advance_current_task_state,
+            )?;
+        }
         _ => {}
     }
     Ok(())
@@ -164,6 +171,7 @@ fn dispatch_action(app: &mut App, action: KeyActionId) -> io::Result<bool> {
             | RemoveLastTag
             | RemoveAllTags
             | CycleEntryType
+            | MarkTaskState
     ) {
         dispatch_entry_operation(app, action)?;
         return Ok(true);
@@ -254,6 +262,13 @@ fn dispatch_action(app: &mut App, action: KeyActionId) -> io::Result<bool> {
         TidyEntries => app.tidy_entries(),
         ToggleCombinedView => app.toggle_combined_view()?,
         Hide => app.toggle_hide_completed(),
+        ToggleBody => app.toggle_current_body(),
+        InsertLineBreak => {
+            app.clear_hints();
+            if let Some(ref mut buffer) = app.edit_buffer {
+                buffer.insert_char('\n');
+            }
+        }
         Autocomplete => {
             app.accept_hint();
             if let Some(ref mut buffer) = app.edit_buffer {
@@ -342,8 +357,12 @@ pub fn handle_command_palette_key(app: &mut App, key: KeyEvent) -> io::Result<()
             KeyActionId::MoveLeft => app.command_palette_prev_tab(),
             KeyActionId::MoveRight => app.command_palette_next_tab(),
             KeyActionId::Submit => {
+                let mode = palette_mode(app);
                 app.execute_selected_palette_item()?;
-                app.close_command_palette();
+                // Items that open a dialog or another palette tab keep it
+                if palette_mode(app) == mode {
+                    app.close_command_palette();
+                }
             }
             KeyActionId::Delete => {
                 app.palette_delete_selected()?;
@@ -360,6 +379,13 @@ pub fn handle_command_palette_key(app: &mut App, key: KeyEvent) -> io::Result<()
     Ok(())
 }
 
+fn palette_mode(app: &App) -> Option<CommandPaletteMode> {
+    match &app.input_mode {
+        InputMode::CommandPalette(state) => Some(state.mode),
+        _ => None,
+    }
+}
+
 pub fn handle_reorder_key(app: &mut App, key: KeyEvent) {
     let spec = KeySpec::from_event(&key);
     if let Some(action) = app.keymap.get(KeyContext::Reorder, &spec) {
@@ -441,20 +467,10 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
                 let config_load = Config::load_merged_from(&root).unwrap_or_default();
                 let journal_path = config_load.config.get_project_journal_path(&root);
 
-                if !journal_path.exists() {
-                    // Create parent directories if journal is at custom location
-                    if let Some(parent) = journal_path.parent()
-                        && let Err(e) = std::fs::create_dir_all(parent)
-                    {
-                        app.set_error(format!("Failed to create journal directory: {e}"));
-                        app.input_mode = InputMode::Normal;
-                        return Ok(());
-                    }
-                    if let Err(e) = std::fs::write(&journal_path, "") {
-                        app.set_error(format!("Failed to create journal: {e}"));
-                        app.input_mode = InputMode::Normal;
-                        return Ok(());
-                    }
+                if let Err(e) = storage::create_journal(&journal_path) {
+                    app.set_error(format!("Failed to create journal: {e}"));
+                    app.input_mode = InputMode::Normal;
+                    return Ok(());
                 }
 
                 if std::env::var("CORNER_SKIP_REGISTRY").is_err() {
@@ -476,6 +492,11 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
             ConfirmContext::DeleteTagFromCompleted(tag) => {
                 app.confirm_delete_tag_from_completed(&tag)?;
             }
+            ConfirmContext::SaveConflict(_) => app.resolve_save_conflict(true)?,
+            ConfirmContext::RestoreBackup(path) => app.confirm_restore_backup(&path)?,
+            ConfirmContext::ReformatJournal(_) => app.confirm_reformat_journal()?,
+            ConfirmContext::FixJournal { .. } => app.confirm_fix_journal()?,
+            ConfirmContext::ArchiveJournal { before, .. } => app.confirm_archive_journal(before)?,
         },
         KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => match context {
             ConfirmContext::CreateProjectJournal => {
@@ -485,6 +506,13 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
             ConfirmContext::DeleteTag(_) | ConfirmContext::DeleteTagFromCompleted(_) => {
                 app.open_palette(CommandPaletteMode::Tags);
             }
+            ConfirmContext::SaveConflict(_) => app.resolve_save_conflict(false)?,
+            ConfirmContext::RestoreBackup(_) => app.open_palette(CommandPaletteMode::Backups),
+            ConfirmContext::ReformatJournal(_)
+            | ConfirmContext::FixJournal { .. }
+            | ConfirmContext::ArchiveJournal { .. } => {
+                app.input_mode = InputMode::Normal;
+            }
         },
         _ => {}
     }
@@ -556,10 +584,10 @@ pub fn handle_date_picker_key(app: &mut App, key: KeyEvent) -> io::Result<()> {
                 return Ok(());
             };
             match key.code {
-                KeyCode::Char(c) if c.is_ascii_digit() || c == '/' => {
-                    if state.buffer.content().len() < 10 {
-                        state.buffer.insert_char(c);
-                    }
+                KeyCode::Char(c)
+                    if (c.is_ascii_digit() || c == '/') && state.buffer.content().len() < 10 =>
+                {
+                    state.buffer.insert_char(c);
                 }
                 KeyCode::Backspace => {
                     state.buffer.delete_char_before();
@@ -0,0 +1,348 @@
+//! Importing tasks from todo.txt and Taskwarrior.
+//!
+//! Each task becomes an entry on the day it was created, or completed when
+//! that's all that's known. Projects and contexts become tags, and priorities
+//! and due dates are written the way Corner writes them (`!1`, `@due-…`).
+//! Importing the same file twice adds nothing the second time.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+
+use crate::storage::{self, EntryType, Journal, Line, RawEntry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// One task per line, as described at todotxt.org
+    TodoTxt,
+    /// The JSON written by `task export`
+    Taskwarrior,
+}
+
+impl ImportFormat {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "todotxt" | "todo.txt" | "todo" => Some(Self::TodoTxt),
+            "taskwarrior" | "task" | "tw" => Some(Self::Taskwarrior),
+            _ => None,
+        }
+    }
+
+    /// Taskwarrior exports are JSON; anything else is read as todo.txt.
+    #[must_use]
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with(['[', '{']) {
+            Self::Taskwarrior
+        } else {
+            Self::TodoTxt
+        }
+    }
+}
+
+/// A task read from another tool, placed on its day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTask {
+    pub date: NaiveDate,
+    pub entry: RawEntry,
+}
+
+/// The tasks read from a file, and why any others were left out.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedImport {
+    pub tasks: Vec<ImportedTask>,
+    pub skipped: Vec<String>,
+}
+
+/// What writing an import did, or would do on a dry run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub days: usize,
+    /// Tasks already on their day, which aren't added again
+    pub duplicates: usize,
+}
+
+/// Reads the tasks in `content`. Tasks with no dates go on `today`.
+pub fn parse_import(
+    content: &str,
+    format: ImportFormat,
+    today: NaiveDate,
+) -> io::Result<ParsedImport> {
+    match format {
+        ImportFormat::TodoTxt => Ok(parse_todo_txt(content, today)),
+        ImportFormat::Taskwarrior => parse_taskwarrior(content, today),
+    }
+}
+
+/// Adds each task to its day in the journal at `path`, after the day's
+/// existing entries. Nothing is written on a dry run.
+pub fn write_import(
+    tasks: &[ImportedTask],
+    path: &Journal,
+    dry_run: bool,
+) -> io::Result<ImportSummary> {
+    let mut by_day: BTreeMap<NaiveDate, Vec<&RawEntry>> = BTreeMap::new();
+    for task in tasks {
+        by_day.entry(task.date).or_default().push(&task.entry);
+    }
+
+    let mut summary = ImportSummary::default();
+    for (date, entries) in by_day {
+        let mut lines = storage::load_day_lines(date, path)?;
+        let mut existing: HashSet<String> = lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Entry(entry) => Some(storage::serialize_entry(entry)),
+                Line::Raw(_) => None,
+            })
+            .collect();
+
+        let before = lines.len();
+        for entry in entries {
+            if existing.insert(storage::serialize_entry(entry)) {
+                lines.push(Line::Entry(entry.clone()));
+            } else {
+                summary.duplicates += 1;
+            }
+        }
+        let added = lines.len() - before;
+        if added == 0 {
+            continue;
+        }
+        summary.added += added;
+        summary.days += 1;
+        if !dry_run {
+            storage::save_day_lines(date, path, &lines)?;
+        }
+    }
+    Ok(summary)
+}
+
+/// An entry's content from a task's parts, in the order Corner writes them.
+fn entry_content(
+    text: &str,
+    priority: Option<u8>,
+    due: Option<NaiveDate>,
+    scheduled: Option<NaiveDate>,
+    tags: &[String],
+) -> String {
+    let mut parts = vec![text.split_whitespace().collect::<Vec<_>>().join(" ")];
+    parts.extend(priority.map(|priority| format!("!{priority}")));
+    parts.extend(due.map(|date| format!("@due-{}", date.format("%Y/%m/%d"))));
+    parts.extend(scheduled.map(|date| format!("@on-{}", date.format("%Y/%m/%d"))));
+
+    let mut seen = HashSet::new();
+    for tag in tags {
+        let tag: String = tag
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let tag = tag.trim_matches('-');
+        if !tag.is_empty() && seen.insert(tag.to_lowercase()) {
+            parts.push(format!("#{tag}"));
+        }
+    }
+
+    let (content, _) = storage::normalize_entry_structure(&parts.join(" "));
+    content
+}
+
+fn task_entry(
+    content: String,
+    completed: Option<Option<NaiveDate>>,
+    body: Vec<String>,
+) -> RawEntry {
+    let (entry_type, content) = match completed {
+        Some(Some(date)) => (EntryType::DONE_TASK, storage::add_done_date(&content, date)),
+        Some(None) => (EntryType::DONE_TASK, content),
+        None => (EntryType::OPEN_TASK, content),
+    };
+    RawEntry {
+        entry_type,
+        content,
+        depth: 0,
+        indent: String::new(),
+        body,
+    }
+}
+
+/// `(A)` is the highest todo.txt priority and `!1` Corner's. Corner has three
+/// levels, so everything below `(B)` becomes `!3`.
+fn letter_priority(letter: char) -> Option<u8> {
+    match letter {
+        'A' => Some(1),
+        'B' => Some(2),
+        'C'..='Z' => Some(3),
+        _ => None,
+    }
+}
+
+/// Splits a `YYYY-MM-DD` date off the start of `text`.
+fn leading_date(text: &str) -> Option<(NaiveDate, &str)> {
+    let (date, rest) = NaiveDate::parse_and_remainder(text, "%Y-%m-%d").ok()?;
+    (rest.is_empty() || rest.starts_with(' ')).then(|| (date, rest.trim_start()))
+}
+
+fn parse_todo_txt(content: &str, today: NaiveDate) -> ParsedImport {
+    let mut parsed = ParsedImport::default();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_todo_line(line.trim(), today) {
+            Ok(task) => parsed.tasks.push(task),
+            Err(reason) => parsed.skipped.push(format!("line {}: {reason}", i + 1)),
+        }
+    }
+    parsed
+}
+
+fn parse_todo_line(line: &str, today: NaiveDate) -> Result<ImportedTask, &'static str> {
+    let mut rest = line;
+    let mut completed = None;
+    let mut created = None;
+    let mut priority = None;
+
+    if let Some(after) = rest.strip_prefix("x ") {
+        rest = after.trim_start();
+        completed = Some(None);
+        if let Some((date, after)) = leading_date(rest) {
+            completed = Some(Some(date));
+            rest = after;
+        }
+    } else if let Some(after) = rest.strip_prefix('(')
+        && let Some((letter, after)) = after.split_once(") ")
+        && let [letter] = letter.as_bytes()
+    {
+        priority = letter_priority(char::from(*letter));
+        rest = after.trim_start();
+    }
+    if let Some((date, after)) = leading_date(rest) {
+        created = Some(date);
+        rest = after;
+    }
+
+    let mut words = Vec::new();
+    let mut tags = Vec::new();
+    let mut due = None;
+    for word in rest.split_whitespace() {
+        if let Some(tag) = word.strip_prefix(['+', '@']).filter(|tag| !tag.is_empty()) {
+            tags.push(tag.to_string());
+        } else if let Some(date) = word.strip_prefix("due:") {
+            due = Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| "bad due date")?);
+        } else if let Some(letter) = word.strip_prefix("pri:") {
+            priority = letter.chars().next().and_then(letter_priority);
+        } else if word == "h:1" {
+            return Err("hidden task");
+        } else {
+            words.push(word);
+        }
+    }
+    if words.is_empty() {
+        return Err("no description");
+    }
+
+    let date = created.or(completed.flatten()).unwrap_or(today);
+    let content = entry_content(&words.join(" "), priority, due, None, &tags);
+    Ok(ImportedTask {
+        date,
+        entry: task_entry(content, completed, Vec::new()),
+    })
+}
+
+/// A task as `task export` writes it. Fields Corner has no use for are ignored.
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    description: String,
+    status: String,
+    entry: Option<String>,
+    end: Option<String>,
+    due: Option<String>,
+    scheduled: Option<String>,
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    priority: Option<String>,
+    #[serde(default)]
+    annotations: Vec<TaskwarriorAnnotation>,
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorAnnotation {
+    description: String,
+}
+
+/// Reads a JSON array of tasks, or one task per line as older versions of
+/// Taskwarrior export them.
+fn parse_taskwarrior(content: &str, today: NaiveDate) -> io::Result<ParsedImport> {
+    let tasks: Vec<TaskwarriorTask> = if content.trim_start().starts_with('[') {
+        serde_json::from_str(content)?
+    } else {
+        content
+            .lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut parsed = ParsedImport::default();
+    for (i, task) in tasks.into_iter().enumerate() {
+        let completed = match task.status.as_str() {
+            "pending" | "waiting" => None,
+            "completed" => Some(task.end.as_deref().and_then(taskwarrior_date)),
+            // Recurring templates only spawn tasks, which are exported too
+            status => {
+                parsed.skipped.push(format!("task {}: {status}", i + 1));
+                continue;
+            }
+        };
+
+        let priority = match task.priority.as_deref() {
+            Some("H") => Some(1),
+            Some("M") => Some(2),
+            Some("L") => Some(3),
+            _ => None,
+        };
+        let tags: Vec<String> = task.project.into_iter().chain(task.tags).collect();
+        let content = entry_content(
+            &task.description,
+            priority,
+            task.due.as_deref().and_then(taskwarrior_date),
+            task.scheduled.as_deref().and_then(taskwarrior_date),
+            &tags,
+        );
+        let body = task
+            .annotations
+            .into_iter()
+            .map(|annotation| annotation.description)
+            .collect();
+
+        let date = task
+            .entry
+            .as_deref()
+            .and_then(taskwarrior_date)
+            .or(completed.flatten())
+            .unwrap_or(today);
+        parsed.tasks.push(ImportedTask {
+            date,
+            entry: task_entry(content, completed, body),
+        });
+    }
+    Ok(parsed)
+}
+
+/// The local date of a Taskwarrior timestamp (`20260110T153000Z`, in UTC).
+fn taskwarrior_date(timestamp: &str) -> Option<NaiveDate> {
+    let utc = NaiveDateTime::parse_from_str(timestamp, "%Y%m%dT%H%M%SZ").ok()?;
+    Some(utc.and_utc().with_timezone(&Local).date_naive())
+}
@@ -1,11 +1,14 @@
 pub mod app;
 pub mod calendar;
+pub mod cli;
 pub mod config;
 pub mod cursor;
 pub mod dispatch;
 pub mod handlers;
+pub mod import;
 pub mod recorder;
 pub mod registry;
+pub mod report;
 pub mod storage;
 pub mod testrun;
 pub mod ui;
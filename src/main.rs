@@ -1,6 +1,5 @@
 use std::fs;
 use std::io;
-use std::path::PathBuf;
 
 use crossterm::{
     event::{self, DisableBracketedPaste, EnableBracketedPaste, Event},
@@ -11,9 +10,9 @@ use ratatui::{Terminal, backend::CrosstermBackend};
 
 use corner::app::{App, InputMode};
 use corner::config::{self, Config, get_profile_project_root, has_custom_profile, init_profile};
-use corner::storage::{JournalContext, JournalSlot};
+use corner::storage::{Journal, JournalContext, JournalSlot};
 use corner::ui::surface::Surface;
-use corner::{handlers, storage, testrun, ui};
+use corner::{cli, handlers, storage, testrun, ui};
 
 fn main() -> Result<(), io::Error> {
     let args: Vec<String> = std::env::args().collect();
@@ -48,7 +47,32 @@ fn main() -> Result<(), io::Error> {
 
     let hub_path = config_load.config.get_hub_journal_path();
 
-    let journal_context = JournalContext::new(hub_path, project_path.clone(), active_slot);
+    let project_path =
+        project_path.map(|journal| config_load.config.journal(journal.path().to_path_buf()));
+    let journal_context = JournalContext::new(hub_path, project_path, active_slot);
+
+    if let Some(command) = cli::parse_command(&remaining_args) {
+        let today = chrono::Local::now().date_naive();
+        let res = command.and_then(|command| {
+            cli::run(
+                &command,
+                &config_load.config,
+                &journal_context,
+                today,
+                &mut io::stdout(),
+            )
+        });
+
+        if let Some(temp) = temp_dir {
+            testrun::cleanup(temp);
+        }
+
+        if let Err(err) = res {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     let surface = Surface::from_terminal();
 
@@ -82,12 +106,13 @@ fn main() -> Result<(), io::Error> {
     )?;
     terminal.show_cursor()?;
 
-    if let Some(temp) = temp_dir {
-        testrun::cleanup(temp);
+    match res {
+        Ok(mut app) => finish_pending_writes(&mut app),
+        Err(err) => eprintln!("Error: {err}"),
     }
 
-    if let Err(err) = res {
-        eprintln!("Error: {err}");
+    if let Some(temp) = temp_dir {
+        testrun::cleanup(temp);
     }
 
     Ok(())
@@ -123,7 +148,7 @@ fn run_app<B: ratatui::backend::Backend>(
     surface: Surface,
     config_warning: Option<String>,
     mut recorder: Option<corner::recorder::Recorder>,
-) -> io::Result<()> {
+) -> io::Result<App> {
     let date = chrono::Local::now().date_naive();
 
     let runtime = tokio::runtime::Runtime::new()
@@ -158,12 +183,7 @@ fn run_app<B: ratatui::backend::Backend>(
             fs::create_dir_all(&corner_dir)?;
 
             let journal_path = app.config.get_project_journal_path(&git_root);
-            if !journal_path.exists() {
-                if let Some(parent) = journal_path.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                fs::write(&journal_path, "")?;
-            }
+            storage::create_journal(&journal_path)?;
 
             let mut registry = storage::ProjectRegistry::load();
             if registry.find_by_path(&corner_dir).is_none() {
@@ -194,6 +214,8 @@ fn run_app<B: ratatui::backend::Backend>(
         tick_counter = tick_counter.wrapping_add(1);
         if tick_counter.is_multiple_of(60) {
             app.check_external_changes();
+            app.auto_commit_if_settled();
+            app.export_ics_if_settled();
         }
 
         if event::poll(std::time::Duration::from_millis(16))? {
@@ -224,9 +246,10 @@ fn run_app<B: ratatui::backend::Backend>(
                 }
                 Event::Paste(text) => {
                     if matches!(app.input_mode, InputMode::Edit(_)) {
-                        let first_line = text.lines().next().unwrap_or(&text);
+                        // Lines after the first become the entry's body
+                        let text = text.replace("\r\n", "\n").replace('\r', "\n");
                         if let Some(ref mut buffer) = app.edit_buffer {
-                            buffer.insert_str(first_line);
+                            buffer.insert_str(text.trim_end_matches('\n'));
                         }
                         app.update_hints();
                     }
@@ -244,10 +267,21 @@ fn run_app<B: ratatui::backend::Backend>(
         rec.save()?;
     }
 
-    Ok(())
+    Ok(app)
+}
+
+/// Writes the feeds and commits still waiting for saves to settle. Runs after
+/// the terminal is restored, so anything refused is shown as a warning.
+fn finish_pending_writes(app: &mut App) {
+    if let Err(e) = app.export_ics_feeds() {
+        eprintln!("Warning: ICS export failed: {e}");
+    }
+    if let Err(e) = app.auto_commit() {
+        eprintln!("Warning: Auto-commit skipped: {e}");
+    }
 }
 
-fn detect_project_with_profile() -> Option<PathBuf> {
+fn detect_project_with_profile() -> Option<Journal> {
     if let Some(project_root) = get_profile_project_root() {
         let config_load = Config::load_merged_from(project_root).ok()?;
         let journal_path = config_load.config.get_project_journal_path(project_root);
@@ -0,0 +1,205 @@
+//! Standup and weekly reports.
+//!
+//! A report walks the hub and every registered project journal over a range
+//! of days and collects, per journal, the tasks completed in it, the tasks
+//! still open, and blockers: blocked tasks and entries tagged `#blocker`.
+//! Each journal is rendered through a template, as markdown or plain text.
+
+use std::io;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{self, EntryType, Journal, Line, ProjectRegistry, RawEntry, TaskState};
+
+/// Tag marking an entry as a blocker when none is configured.
+pub const DEFAULT_BLOCKER_TAG: &str = "blocker";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Text,
+}
+
+impl ReportFormat {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "text" | "plain" | "txt" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// The template used when none is configured.
+    #[must_use]
+    pub fn default_template(self) -> &'static str {
+        match self {
+            Self::Markdown => {
+                "## {journal}\n\n**Done**\n{done}\n\n**Open**\n{open}\n\n**Blockers**\n{blockers}"
+            }
+            Self::Text => "{journal}\nDone:\n{done}\nOpen:\n{open}\nBlockers:\n{blockers}",
+        }
+    }
+}
+
+/// What one journal contributes to a report. Items are entry text without
+/// done dates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalReport {
+    pub name: String,
+    pub path: PathBuf,
+    pub done: Vec<String>,
+    pub open: Vec<String>,
+    pub blockers: Vec<String>,
+}
+
+impl JournalReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty() && self.open.is_empty() && self.blockers.is_empty()
+    }
+}
+
+/// The hub followed by each available registered project journal, by name,
+/// the journals the combined view shows.
+#[must_use]
+pub fn report_journals(hub_path: &Journal) -> Vec<(String, Journal)> {
+    let mut journals = vec![("Hub".to_string(), hub_path.clone())];
+    let registry = ProjectRegistry::load();
+    for project in &registry.projects {
+        if !project.available || project.hide_from_registry {
+            continue;
+        }
+        let journal_path = project.journal_path();
+        if journal_path.exists() {
+            journals.push((project.name.clone(), journal_path));
+        }
+    }
+    journals
+}
+
+/// Collects each journal's report for `start` through `end`, leaving out
+/// journals with nothing to report.
+///
+/// Tasks count as done when one of their done dates is in the range, or, with
+/// none recorded, when they're written in it. Open tasks and blockers are
+/// those written in the range or scheduled into it.
+pub fn build_report(
+    journals: &[(String, Journal)],
+    start: NaiveDate,
+    end: NaiveDate,
+    blocker_tag: &str,
+) -> io::Result<Vec<JournalReport>> {
+    let blocker_tag = if blocker_tag.is_empty() {
+        DEFAULT_BLOCKER_TAG
+    } else {
+        blocker_tag
+    };
+    let in_range = |date: NaiveDate| date >= start && date <= end;
+
+    let mut reports = Vec::new();
+    for (name, path) in journals {
+        let mut report = JournalReport {
+            name: name.clone(),
+            path: path.to_path_buf(),
+            ..JournalReport::default()
+        };
+        for (date, content) in storage::load_days(path)? {
+            for line in storage::parse_lines(&content) {
+                let Line::Entry(entry) = line else {
+                    continue;
+                };
+                let text = storage::strip_done_meta(&entry.content);
+                let EntryType::Task { state } = entry.entry_type else {
+                    if in_range(date) && has_tag(&entry, blocker_tag) {
+                        report.blockers.push(text);
+                    }
+                    continue;
+                };
+                if state == TaskState::Done {
+                    let done_dates = storage::extract_done_dates(&entry.content);
+                    let done = if done_dates.is_empty() {
+                        in_range(date)
+                    } else {
+                        done_dates.into_iter().any(in_range)
+                    };
+                    if done {
+                        report.done.push(text);
+                    }
+                    continue;
+                }
+                if state.is_closed() {
+                    continue;
+                }
+                let scheduled = storage::extract_scheduled_date(&entry.content, date)
+                    .is_some_and(|scheduled| in_range(scheduled.date));
+                if !in_range(date) && !scheduled {
+                    continue;
+                }
+                if state == TaskState::Blocked || has_tag(&entry, blocker_tag) {
+                    report.blockers.push(text);
+                } else {
+                    report.open.push(text);
+                }
+            }
+        }
+        if !report.is_empty() {
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
+
+/// Renders each journal's report through `template`, or the format's default.
+/// `{journal}`, `{done}`, `{open}`, `{blockers}`, `{start}` and `{end}` are
+/// replaced; lists with nothing in them read "- none".
+#[must_use]
+pub fn render_report(
+    reports: &[JournalReport],
+    start: NaiveDate,
+    end: NaiveDate,
+    format: ReportFormat,
+    template: Option<&str>,
+) -> String {
+    let template = template
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or_else(|| format.default_template());
+    let sections: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            template
+                .replace("{journal}", &report.name)
+                .replace("{start}", &start.format("%Y/%m/%d").to_string())
+                .replace("{end}", &end.format("%Y/%m/%d").to_string())
+                .replace("{done}", &render_items(&report.done))
+                .replace("{open}", &render_items(&report.open))
+                .replace("{blockers}", &render_items(&report.blockers))
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    let mut report = sections.join("\n\n");
+    report.push('\n');
+    report
+}
+
+fn render_items(items: &[String]) -> String {
+    if items.is_empty() {
+        return "- none".to_string();
+    }
+    items
+        .iter()
+        .map(|item| format!("- {item}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn has_tag(entry: &RawEntry, tag: &str) -> bool {
+    storage::extract_tags(&entry.content)
+        .iter()
+        .any(|entry_tag| entry_tag.eq_ignore_ascii_case(tag))
+}
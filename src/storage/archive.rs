@@ -0,0 +1,213 @@
+//! Archives of old days.
+//!
+//! Archiving moves old days out of a single-file journal into an archive beside
+//! it, `journal.archive.md` or one file per year (`journal.archive.2025.md`), so
+//! everyday scans only read recent days. Archives are ordinary journals. A day
+//! the journal doesn't have is loaded from and saved to the archive holding it,
+//! and filters whose date range reaches back far enough search the archives too.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+
+use super::check::sort_and_merge_days;
+use super::entries::{Line, parse_lines};
+use super::filter::RECURRING_REGEX;
+use super::headers::DayHeaderFormat;
+use super::index;
+use super::lock::with_journal_lock;
+use super::store::{Journal, JournalLayout};
+
+/// Which days `archive_journal` moves, and where to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveOptions {
+    /// Days before this date are archived
+    pub before: NaiveDate,
+    /// Keep days that still have open tasks
+    pub completed_only: bool,
+    /// One archive per year instead of a single archive
+    pub by_year: bool,
+}
+
+/// The archive of `journal`, or its archive for one year.
+#[must_use]
+pub fn archive_path(journal: &Path, year: Option<i32>) -> PathBuf {
+    let stem = journal
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match year {
+        Some(year) => format!("{stem}.archive.{year}.md"),
+        None => format!("{stem}.archive.md"),
+    };
+    journal.with_file_name(name)
+}
+
+/// Whether `path` is itself an archive, which has no archives of its own.
+fn is_archive(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().contains(".archive."))
+}
+
+/// Archives of `journal` that exist and may hold `date`: its year's archive,
+/// then the single archive.
+#[must_use]
+pub fn archives_for_day(journal: &Path, date: NaiveDate) -> Vec<PathBuf> {
+    if is_archive(journal) {
+        return Vec::new();
+    }
+    [Some(date.year()), None]
+        .into_iter()
+        .map(|year| archive_path(journal, year))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Archives of `journal` that exist and may hold days from `start` through
+/// `end`, oldest first. Each bound is open when None.
+pub(super) fn archives_in_range(
+    journal: &Journal,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+) -> io::Result<Vec<PathBuf>> {
+    if is_archive(journal) || journal.layout() != JournalLayout::File {
+        return Ok(Vec::new());
+    }
+    let Some(dir) = journal.parent().filter(|dir| dir.is_dir()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut years = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let year = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".md"))
+            .and_then(|name| name.rsplit_once('.'))
+            .and_then(|(_, year)| year.parse::<i32>().ok());
+        if let Some(year) = year
+            && path == archive_path(journal, Some(year))
+            && start.is_none_or(|start| year >= start.year())
+            && end.is_none_or(|end| year <= end.year())
+        {
+            years.push((year, path));
+        }
+    }
+    years.sort();
+
+    let mut archives = Vec::new();
+    let single = archive_path(journal, None);
+    if single.exists() {
+        let (first, last) = index::with_index(&single, journal.day_header(), |index| {
+            (index.dates().min(), index.dates().max())
+        })?;
+        if start.is_none_or(|start| last.is_some_and(|last| last >= start))
+            && end.is_none_or(|end| first.is_some_and(|first| first <= end))
+        {
+            archives.push(single);
+        }
+    }
+    archives.extend(years.into_iter().map(|(_, path)| path));
+    Ok(archives)
+}
+
+/// Every archive of `journal` that exists, oldest first.
+pub fn journal_archives(journal: &Journal) -> io::Result<Vec<PathBuf>> {
+    archives_in_range(journal, None, None)
+}
+
+/// Moves days before `options.before` from the journal into its archive and
+/// returns how many were moved. Days with recurring entries stay, since they
+/// still project onto new days. The archive is written before the journal, so
+/// an interrupted archive leaves days in both rather than in neither.
+pub fn archive_journal(journal: &Journal, options: &ArchiveOptions) -> io::Result<usize> {
+    if journal.layout() != JournalLayout::File || is_archive(journal) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Only single-file journals can be archived",
+        ));
+    }
+
+    let format = journal.day_header();
+    with_journal_lock(journal, || {
+        let content = index::with_index(journal, format, |index| index.content().to_string())?;
+        let mut kept = String::new();
+        let mut archived: BTreeMap<PathBuf, String> = BTreeMap::new();
+        let mut count = 0;
+
+        for (date, section) in sections(&content, format) {
+            match date.filter(|date| should_archive(*date, section, options)) {
+                Some(date) => {
+                    let year = options.by_year.then(|| date.year());
+                    let target = archived.entry(archive_path(journal, year)).or_default();
+                    target.push_str(section.trim_end());
+                    target.push_str("\n\n");
+                    count += 1;
+                }
+                None => kept.push_str(section),
+            }
+        }
+        if count == 0 {
+            return Ok(0);
+        }
+
+        for (path, days) in archived {
+            let existing = if path.exists() {
+                index::with_index(&path, format, |index| index.content().to_string())?
+            } else {
+                String::new()
+            };
+            let (merged, _) = sort_and_merge_days(&format!("{existing}\n{days}"), format);
+            index::save_journal(&path, format, &merged)?;
+        }
+
+        let kept = kept.trim_end();
+        let kept = if kept.is_empty() {
+            String::new()
+        } else {
+            format!("{kept}\n")
+        };
+        index::save_journal(journal, format, &kept)?;
+        Ok(count)
+    })
+}
+
+fn should_archive(date: NaiveDate, section: &str, options: &ArchiveOptions) -> bool {
+    if date >= options.before {
+        return false;
+    }
+    !parse_lines(section).iter().any(|line| match line {
+        Line::Entry(entry) => {
+            RECURRING_REGEX.is_match(&entry.content)
+                || (options.completed_only && entry.entry_type.is_open_task())
+        }
+        Line::Raw(_) => false,
+    })
+}
+
+/// The journal split at its day headers: any text above the first header,
+/// then each day's section from its header to the next, verbatim.
+fn sections<'a>(journal: &'a str, format: &DayHeaderFormat) -> Vec<(Option<NaiveDate>, &'a str)> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut date = None;
+    let mut offset = 0;
+    for line in journal.split_inclusive('\n') {
+        if let Some(next) = format.parse(line.trim_end_matches(['\n', '\r'])) {
+            if offset > start {
+                sections.push((date, &journal[start..offset]));
+            }
+            start = offset;
+            date = Some(next);
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        sections.push((date, &journal[start..offset]));
+    }
+    sections
+}
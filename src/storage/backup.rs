@@ -0,0 +1,138 @@
+//! Rolling snapshots of journal files.
+//!
+//! Each journal gets its own folder under the backup directory, named after the
+//! journal's path. Snapshots are named by the time they were taken and why,
+//! e.g. `20260115-140312.123-tag-delete.md`, so they sort oldest first.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+
+use super::persistence::{load_journal, save_journal};
+use super::store::Journal;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+const TIMESTAMP_LEN: usize = "20260115-140312.123".len();
+
+/// A snapshot of a journal taken before a destructive operation or once a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub taken_at: NaiveDateTime,
+    pub reason: String,
+}
+
+impl Backup {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        let stem = path.file_stem()?.to_str()?;
+        let timestamp = stem.get(..TIMESTAMP_LEN)?;
+        let taken_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+        let reason = stem
+            .get(TIMESTAMP_LEN + 1..)
+            .unwrap_or_default()
+            .to_string();
+        Some(Self {
+            path,
+            taken_at,
+            reason,
+        })
+    }
+
+    pub fn content(&self) -> io::Result<String> {
+        fs::read_to_string(&self.path)
+    }
+}
+
+/// Folder holding the snapshots of one journal.
+fn journal_backup_dir(journal: &Path, backup_dir: &Path) -> PathBuf {
+    let journal = fs::canonicalize(journal).unwrap_or_else(|_| journal.to_path_buf());
+    let key = journal
+        .to_string_lossy()
+        .trim_start_matches(['/', '\\'])
+        .replace(['/', '\\', ':'], "%");
+    backup_dir.join(key)
+}
+
+/// Lists a journal's snapshots, newest first.
+pub fn list_backups(journal: &Path, backup_dir: &Path) -> io::Result<Vec<Backup>> {
+    let dir = journal_backup_dir(journal, backup_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<Backup> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Backup::from_path(entry.path()))
+        .collect();
+    backups.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(backups)
+}
+
+/// Snapshots the journal, keeping at most `keep` snapshots. Skipped when
+/// `keep` is 0, the journal is empty, or it matches the newest snapshot.
+/// Returns the new snapshot's path.
+pub fn backup_journal(
+    journal: &Journal,
+    backup_dir: &Path,
+    reason: &str,
+    keep: usize,
+) -> io::Result<Option<PathBuf>> {
+    if keep == 0 {
+        return Ok(None);
+    }
+    let content = load_journal(journal)?;
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let existing = list_backups(journal, backup_dir)?;
+    if let Some(newest) = existing.first()
+        && newest.content().is_ok_and(|newest| newest == content)
+    {
+        return Ok(None);
+    }
+
+    let dir = journal_backup_dir(journal, backup_dir);
+    fs::create_dir_all(&dir)?;
+    let name = format!(
+        "{}-{}.md",
+        Local::now().naive_local().format(TIMESTAMP_FORMAT),
+        reason
+    );
+    let path = dir.join(name);
+    fs::write(&path, &content)?;
+
+    for stale in list_backups(journal, backup_dir)?.iter().skip(keep) {
+        fs::remove_file(&stale.path)?;
+    }
+    Ok(Some(path))
+}
+
+/// Takes the day's first snapshot if none was taken on `today` yet.
+pub fn ensure_daily_backup(
+    journal: &Journal,
+    backup_dir: &Path,
+    keep: usize,
+    today: NaiveDate,
+) -> io::Result<Option<PathBuf>> {
+    let backups = list_backups(journal, backup_dir)?;
+    if backups.iter().any(|b| b.taken_at.date() == today) {
+        return Ok(None);
+    }
+    backup_journal(journal, backup_dir, "daily", keep)
+}
+
+/// Replaces the journal with a snapshot, first snapshotting the current
+/// journal so the restore itself can be undone.
+pub fn restore_backup(
+    journal: &Journal,
+    backup: &Backup,
+    backup_dir: &Path,
+    keep: usize,
+) -> io::Result<()> {
+    let content = backup.content()?;
+    backup_journal(journal, backup_dir, "before-restore", keep)?;
+    save_journal(journal, &content)
+}
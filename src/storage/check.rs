@@ -0,0 +1,271 @@
+//! Journal linting and repair.
+//!
+//! Corner tolerates a lot in a journal it didn't write, but some things quietly
+//! go wrong: only the first of two headers for the same date is ever edited,
+//! new days are inserted by date so days out of order end up in odd places,
+//! and entries above the first day header belong to no day at all. `check`
+//! reports these with line numbers; `fix` repairs the ones that have a single
+//! right answer.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+use super::entries::{Line, parse_lines};
+use super::filter::DONE_META_REGEX;
+use super::headers::DayHeaderFormat;
+use super::history::split_days;
+use super::index;
+use super::lock::with_journal_lock;
+use super::store::{Journal, journal_store};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemKind {
+    /// A second header for a date, whose entries Corner never shows
+    DuplicateDay { date: NaiveDate, first_line: usize },
+    /// A day that comes after a later one
+    OutOfOrder { date: NaiveDate, after: NaiveDate },
+    /// An entry above the first day header
+    EntryBeforeFirstDay,
+    /// A CRLF line in a file that also has LF lines
+    MixedLineEndings,
+    /// A `<!-- done -->` comment whose dates can't be read
+    BadDoneComment,
+}
+
+impl ProblemKind {
+    /// Whether `fix_journal` repairs this problem.
+    #[must_use]
+    pub fn is_fixable(&self) -> bool {
+        matches!(
+            self,
+            Self::DuplicateDay { .. } | Self::OutOfOrder { .. } | Self::MixedLineEndings
+        )
+    }
+}
+
+/// A problem at a line of one of a journal's files. Lines count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalProblem {
+    pub file: PathBuf,
+    pub line: usize,
+    pub kind: ProblemKind,
+}
+
+impl fmt::Display for ProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateDay { date, first_line } => write!(
+                f,
+                "{} already has a header on line {first_line}",
+                date.format("%Y/%m/%d")
+            ),
+            Self::OutOfOrder { date, after } => write!(
+                f,
+                "{} comes after {}",
+                date.format("%Y/%m/%d"),
+                after.format("%Y/%m/%d")
+            ),
+            Self::EntryBeforeFirstDay => write!(f, "entry before the first day header"),
+            Self::MixedLineEndings => write!(f, "CRLF line ending among LF lines"),
+            Self::BadDoneComment => write!(f, "unreadable done comment"),
+        }
+    }
+}
+
+impl fmt::Display for JournalProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.kind)
+    }
+}
+
+/// Every problem in the journal, file by file in line order.
+pub fn check_journal(journal: &Journal) -> io::Result<Vec<JournalProblem>> {
+    let store = journal_store(journal);
+    let headers = store.has_day_headers().then(|| journal.day_header());
+    let mut problems = Vec::new();
+    for file in store.files()? {
+        let text = fs::read_to_string(&file)?;
+        problems.extend(check_text(&text, headers).into_iter().map(|(line, kind)| {
+            JournalProblem {
+                file: file.clone(),
+                line,
+                kind,
+            }
+        }));
+    }
+    Ok(problems)
+}
+
+/// Problems in one file's text, by line number. `headers` is None for files
+/// without day headers.
+fn check_text(text: &str, headers: Option<&DayHeaderFormat>) -> Vec<(usize, ProblemKind)> {
+    let mut problems = Vec::new();
+    let has_lf = text
+        .split_inclusive('\n')
+        .any(|line| line.ends_with('\n') && !line.ends_with("\r\n"));
+    let mut first_lines: HashMap<NaiveDate, usize> = HashMap::new();
+    let mut latest: Option<NaiveDate> = None;
+    let mut in_day = headers.is_none();
+
+    for (i, raw) in text.split_inclusive('\n').enumerate() {
+        let number = i + 1;
+        if has_lf && raw.ends_with("\r\n") {
+            problems.push((number, ProblemKind::MixedLineEndings));
+        }
+        let line = raw.trim_end_matches(['\n', '\r']);
+
+        if let Some(date) = headers.and_then(|format| format.parse(line)) {
+            in_day = true;
+            if let Some(&first_line) = first_lines.get(&date) {
+                problems.push((number, ProblemKind::DuplicateDay { date, first_line }));
+                continue;
+            }
+            first_lines.insert(date, number);
+            match latest {
+                Some(after) if date < after => {
+                    problems.push((number, ProblemKind::OutOfOrder { date, after }));
+                }
+                _ => latest = Some(date),
+            }
+            continue;
+        }
+
+        if !in_day && matches!(parse_lines(line).first(), Some(Line::Entry(_))) {
+            problems.push((number, ProblemKind::EntryBeforeFirstDay));
+        }
+        if !done_comment_is_valid(line) {
+            problems.push((number, ProblemKind::BadDoneComment));
+        }
+    }
+    problems
+}
+
+/// False for a `<!-- done: -->` comment that isn't a list of `YYYY-MM-DD`
+/// dates, which Corner would silently ignore. Other comments, including ones
+/// that merely start with the word "done", are fine.
+fn done_comment_is_valid(line: &str) -> bool {
+    let Some(start) = line.find("<!--") else {
+        return true;
+    };
+    let comment = line[start + 4..].trim_start();
+    if !comment.starts_with("done:") {
+        return true;
+    }
+    let Some(caps) = DONE_META_REGEX.captures(line) else {
+        return false;
+    };
+    caps[1]
+        .split(',')
+        .all(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").is_ok())
+}
+
+/// Repairs the fixable problems `check_journal` finds: the days of each file
+/// are sorted by date, later headers for a date are merged into the first,
+/// and CRLF line endings become LF. Returns how many problems were fixed.
+/// Nothing is written unless every rebuilt file reads back as the merged days.
+pub fn fix_journal(journal: &Journal) -> io::Result<usize> {
+    with_journal_lock(journal, || {
+        let store = journal_store(journal);
+        let format = journal.day_header();
+        let headers = store.has_day_headers().then_some(format);
+        let mut rewrites = Vec::new();
+        let mut fixed = 0;
+        for file in store.files()? {
+            let before = fs::read_to_string(&file)?;
+            let fixable: Vec<ProblemKind> = check_text(&before, headers)
+                .into_iter()
+                .map(|(_, kind)| kind)
+                .filter(ProblemKind::is_fixable)
+                .collect();
+            if fixable.is_empty() {
+                continue;
+            }
+            // Files whose days are in order are only touched where needed
+            let normalized = before.replace("\r\n", "\n");
+            let reorder = fixable
+                .iter()
+                .any(|kind| *kind != ProblemKind::MixedLineEndings);
+            let after = if reorder {
+                let (after, merged) = sort_and_merge_days(&normalized, format);
+                if split_days(&after, format) != merged {
+                    return Err(io::Error::other(format!(
+                        "Fixing {} would change its days",
+                        file.display()
+                    )));
+                }
+                after
+            } else {
+                normalized
+            };
+            fixed += fixable.len();
+            rewrites.push((file, after));
+        }
+
+        for (file, content) in rewrites {
+            index::save_journal(&file, format, &content)?;
+        }
+        Ok(fixed)
+    })
+}
+
+/// Rebuilds a journal with its days in date order, each date's sections joined
+/// under its first header. Text above the first header stays on top. Returns
+/// the new journal along with the days it should read back as.
+pub(super) fn sort_and_merge_days(
+    journal: &str,
+    format: &DayHeaderFormat,
+) -> (String, Vec<(NaiveDate, String)>) {
+    let mut preamble = String::new();
+    // Each date's first header line and the content under each of its headers
+    let mut days: Vec<(NaiveDate, String, Vec<Vec<&str>>)> = Vec::new();
+    let mut positions: HashMap<NaiveDate, usize> = HashMap::new();
+    let mut current: Option<usize> = None;
+
+    for line in journal.lines() {
+        if let Some(date) = format.parse(line) {
+            let position = *positions.entry(date).or_insert_with(|| {
+                days.push((date, line.to_string(), Vec::new()));
+                days.len() - 1
+            });
+            days[position].2.push(Vec::new());
+            current = Some(position);
+        } else if let Some(position) = current {
+            if let Some(section) = days[position].2.last_mut() {
+                section.push(line);
+            }
+        } else {
+            preamble.push_str(line);
+            preamble.push('\n');
+        }
+    }
+    days.sort_by_key(|(date, _, _)| *date);
+
+    let mut merged = Vec::new();
+    let mut sections = Vec::new();
+    for (date, header, bodies) in days {
+        let content = bodies
+            .iter()
+            .map(|lines| lines.join("\n").trim_end().to_string())
+            .filter(|body| !body.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(if content.is_empty() {
+            header
+        } else {
+            format!("{header}\n{content}")
+        });
+        merged.push((date, content));
+    }
+
+    let days_text = sections.join("\n\n");
+    let parts: Vec<&str> = [preamble.trim_end(), &days_text]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect();
+    (parts.join("\n\n") + "\n", merged)
+}
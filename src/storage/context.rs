@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use super::store::Journal;
 use crate::config::Config;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -9,14 +10,14 @@ pub enum JournalSlot {
 }
 
 pub struct JournalContext {
-    hub_path: PathBuf,
-    project_path: Option<PathBuf>,
+    hub_path: Journal,
+    project_path: Option<Journal>,
     active: JournalSlot,
 }
 
 impl JournalContext {
     #[must_use]
-    pub fn new(hub_path: PathBuf, project_path: Option<PathBuf>, active: JournalSlot) -> Self {
+    pub fn new(hub_path: Journal, project_path: Option<Journal>, active: JournalSlot) -> Self {
         Self {
             hub_path,
             project_path,
@@ -25,10 +26,10 @@ impl JournalContext {
     }
 
     #[must_use]
-    pub fn active_path(&self) -> &std::path::Path {
+    pub fn active_path(&self) -> &Journal {
         match self.active {
             JournalSlot::Hub => &self.hub_path,
-            JournalSlot::Project => self.project_path.as_deref().unwrap_or(&self.hub_path),
+            JournalSlot::Project => self.project_path.as_ref().unwrap_or(&self.hub_path),
         }
     }
 
@@ -42,17 +43,25 @@ impl JournalContext {
     }
 
     #[must_use]
-    pub fn hub_path(&self) -> &std::path::Path {
+    pub fn hub_path(&self) -> &Journal {
         &self.hub_path
     }
 
     #[must_use]
-    pub fn project_path(&self) -> Option<&std::path::Path> {
-        self.project_path.as_deref()
+    pub fn project_path(&self) -> Option<&Journal> {
+        self.project_path.as_ref()
     }
 
-    pub fn set_project_path(&mut self, path: PathBuf) {
-        self.project_path = Some(path);
+    pub fn set_project_path(&mut self, journal: Journal) {
+        self.project_path = Some(journal);
+    }
+
+    /// Replaces the active journal, such as with the same path under new config.
+    pub fn set_active_path(&mut self, journal: Journal) {
+        match (self.active, &mut self.project_path) {
+            (JournalSlot::Project, Some(project)) => *project = journal,
+            _ => self.hub_path = journal,
+        }
     }
 
     pub fn reset_project_path(&mut self) {
@@ -77,7 +86,7 @@ pub fn find_git_root() -> Option<PathBuf> {
 
 /// Detect project journal path, checking config for custom location.
 #[must_use]
-pub fn detect_project_journal() -> Option<PathBuf> {
+pub fn detect_project_journal() -> Option<Journal> {
     let root = find_git_root().or_else(|| std::env::current_dir().ok())?;
     let corner_dir = root.join(".corner");
 
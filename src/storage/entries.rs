@@ -1,20 +1,30 @@
-use std::path::PathBuf;
+use std::fmt;
+use std::ops::Range;
 
 use chrono::{Datelike, NaiveDate, Weekday};
 
+use super::filter::{RECURRING_REGEX, add_done_date, strip_done_meta};
+use super::store::Journal;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum EntryType {
-    Task { completed: bool },
+    Task { state: TaskState },
     Note,
     Event,
 }
 
 impl EntryType {
+    pub const OPEN_TASK: Self = Self::Task {
+        state: TaskState::Open,
+    };
+    pub const DONE_TASK: Self = Self::Task {
+        state: TaskState::Done,
+    };
+
     #[must_use]
     pub fn prefix(&self) -> &'static str {
         match self {
-            Self::Task { completed: false } => "- [ ] ",
-            Self::Task { completed: true } => "- [x] ",
+            Self::Task { state } => state.prefix(),
             Self::Note => "- ",
             Self::Event => "* ",
         }
@@ -25,7 +35,112 @@ impl EntryType {
         match self {
             Self::Task { .. } => Self::Note,
             Self::Note => Self::Event,
-            Self::Event => Self::Task { completed: false },
+            Self::Event => Self::OPEN_TASK,
+        }
+    }
+
+    /// True for a task that needs no more work: done, migrated or cancelled.
+    #[must_use]
+    pub fn is_closed_task(&self) -> bool {
+        matches!(self, Self::Task { state } if state.is_closed())
+    }
+
+    /// True for a task that still needs work: open, in progress or blocked.
+    #[must_use]
+    pub fn is_open_task(&self) -> bool {
+        matches!(self, Self::Task { state } if !state.is_closed())
+    }
+}
+
+/// A task's bullet-journal state, written as the character in its checkbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    /// `- [ ]`
+    Open,
+    /// `- [x]`, also read from `- [X]`
+    Done,
+    /// `- [/]`
+    InProgress,
+    /// `- [!]`
+    Blocked,
+    /// `- [>]`, forwarded to another day or list
+    Migrated,
+    /// `- [-]`
+    Cancelled,
+}
+
+impl TaskState {
+    pub const ALL: [Self; 6] = [
+        Self::Open,
+        Self::Done,
+        Self::InProgress,
+        Self::Blocked,
+        Self::Migrated,
+        Self::Cancelled,
+    ];
+
+    #[must_use]
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Open => "- [ ] ",
+            Self::Done => "- [x] ",
+            Self::InProgress => "- [/] ",
+            Self::Blocked => "- [!] ",
+            Self::Migrated => "- [>] ",
+            Self::Cancelled => "- [-] ",
+        }
+    }
+
+    #[must_use]
+    pub fn from_marker(marker: char) -> Option<Self> {
+        match marker {
+            ' ' => Some(Self::Open),
+            'x' | 'X' => Some(Self::Done),
+            '/' => Some(Self::InProgress),
+            '!' => Some(Self::Blocked),
+            '>' => Some(Self::Migrated),
+            '-' => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_closed(self) -> bool {
+        matches!(self, Self::Done | Self::Migrated | Self::Cancelled)
+    }
+
+    /// The state after toggling completion: open work becomes done, closed work reopens.
+    #[must_use]
+    pub fn toggled(self) -> Self {
+        if self.is_closed() {
+            Self::Open
+        } else {
+            Self::Done
+        }
+    }
+
+    /// The next state when marking a task, skipping done, which toggling covers:
+    /// open, in progress, blocked, migrated, cancelled, then open again.
+    #[must_use]
+    pub fn next_mark(self) -> Self {
+        match self {
+            Self::Open | Self::Done => Self::InProgress,
+            Self::InProgress => Self::Blocked,
+            Self::Blocked => Self::Migrated,
+            Self::Migrated => Self::Cancelled,
+            Self::Cancelled => Self::Open,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Done => "done",
+            Self::InProgress => "in-progress",
+            Self::Blocked => "blocked",
+            Self::Migrated => "migrated",
+            Self::Cancelled => "cancelled",
         }
     }
 }
@@ -37,6 +152,10 @@ pub enum SourceType {
     Local,
     /// Projected via @every-* pattern, read-only
     Recurring,
+    /// Projected onto its @due-*/@on-* date
+    Scheduled,
+    /// Incomplete task past its @due-* date, projected onto today
+    Overdue,
     /// From external calendar (ICS), read-only
     Calendar {
         calendar_id: String,
@@ -50,14 +169,25 @@ pub enum SourceType {
 pub struct RawEntry {
     pub entry_type: EntryType,
     pub content: String,
+    /// Nesting level from the line's indentation (two spaces or one tab per level)
+    pub depth: usize,
+    /// Leading whitespace as written, kept so saves don't reformat it. Ignored
+    /// once `depth` changes.
+    pub indent: String,
+    /// Continuation lines indented under the entry (details, snippets, links),
+    /// stored without the indentation that attaches them
+    pub body: Vec<String>,
 }
 
 impl RawEntry {
     #[must_use]
     pub fn new_task(content: &str) -> Self {
         Self {
-            entry_type: EntryType::Task { completed: false },
+            entry_type: EntryType::OPEN_TASK,
             content: content.to_string(),
+            depth: 0,
+            indent: String::new(),
+            body: Vec::new(),
         }
     }
 
@@ -65,9 +195,20 @@ impl RawEntry {
         self.entry_type.prefix()
     }
 
+    /// Content and body as one newline-separated text, as shown in the edit buffer.
+    #[must_use]
+    pub fn text(&self) -> String {
+        join_entry_text(&self.content, &self.body)
+    }
+
+    /// Replaces content and body from newline-separated text.
+    pub fn set_text(&mut self, text: &str) {
+        (self.content, self.body) = split_entry_text(text);
+    }
+
     pub fn toggle_complete(&mut self) {
-        if let EntryType::Task { completed } = &mut self.entry_type {
-            *completed = !*completed;
+        if let EntryType::Task { state } = &mut self.entry_type {
+            *state = state.toggled();
         }
     }
 }
@@ -81,7 +222,10 @@ pub struct Entry {
     pub source_date: NaiveDate,
     pub line_index: usize,
     pub source_type: SourceType,
-    pub source_journal: PathBuf,
+    pub source_journal: Journal,
+    pub depth: usize,
+    pub indent: String,
+    pub body: Vec<String>,
 }
 
 impl Entry {
@@ -92,7 +236,7 @@ impl Entry {
         source_date: NaiveDate,
         line_index: usize,
         source_type: SourceType,
-        source_journal: PathBuf,
+        source_journal: Journal,
     ) -> Self {
         Self {
             entry_type: raw.entry_type.clone(),
@@ -101,6 +245,9 @@ impl Entry {
             line_index,
             source_type,
             source_journal,
+            depth: raw.depth,
+            indent: raw.indent.clone(),
+            body: raw.body.clone(),
         }
     }
 
@@ -109,15 +256,18 @@ impl Entry {
         content: &str,
         source_date: NaiveDate,
         line_index: usize,
-        source_journal: PathBuf,
+        source_journal: Journal,
     ) -> Self {
         Self {
-            entry_type: EntryType::Task { completed: false },
+            entry_type: EntryType::OPEN_TASK,
             content: content.to_string(),
             source_date,
             line_index,
             source_type: SourceType::Local,
             source_journal,
+            depth: 0,
+            indent: String::new(),
+            body: Vec::new(),
         }
     }
 
@@ -126,8 +276,8 @@ impl Entry {
     }
 
     pub fn toggle_complete(&mut self) {
-        if let EntryType::Task { completed } = &mut self.entry_type {
-            *completed = !*completed;
+        if let EntryType::Task { state } = &mut self.entry_type {
+            *state = state.toggled();
         }
     }
 
@@ -137,6 +287,9 @@ impl Entry {
         RawEntry {
             entry_type: self.entry_type.clone(),
             content: self.content.clone(),
+            depth: self.depth,
+            indent: self.indent.clone(),
+            body: self.body.clone(),
         }
     }
 
@@ -167,28 +320,170 @@ pub enum RecurringPattern {
     Weekly(Weekday),
     /// @every-1 through @every-31 (day of month)
     Monthly(u8),
+    /// @every-2w-tue - every N weeks on a day of week, counted from the entry's week
+    EveryNWeeks(u8, Weekday),
+    /// @every-last-fri - the last given day of week in each month
+    LastWeekdayOfMonth(Weekday),
+    /// @every-quarter-1 - day of month every three months, counted from the entry's month
+    Quarterly(u8),
+    /// @every-3/15 - yearly on a month and day
+    Yearly(u8, u8),
 }
 
 impl RecurringPattern {
-    /// Returns true if this pattern matches the given date.
+    /// Returns true if this pattern matches the given date. `start` is the day the
+    /// entry was written, which anchors interval patterns (@every-2w-*, @every-quarter-*).
     #[must_use]
-    pub fn matches(&self, date: NaiveDate) -> bool {
+    pub fn matches(&self, start: NaiveDate, date: NaiveDate) -> bool {
         match self {
             Self::Daily => true,
             Self::Weekday => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
             Self::Weekly(day) => date.weekday() == *day,
-            Self::Monthly(day) => {
-                let last_day = last_day_of_month(date);
-                if u32::from(*day) > last_day {
-                    date.day() == last_day
-                } else {
-                    date.day() == u32::from(*day)
-                }
+            Self::Monthly(day) => is_day_of_month(date, *day),
+            Self::EveryNWeeks(interval, day) => {
+                let weeks = (week_start(date) - week_start(start)).num_weeks();
+                date.weekday() == *day && weeks.rem_euclid(i64::from(*interval)) == 0
+            }
+            Self::LastWeekdayOfMonth(day) => {
+                date.weekday() == *day && date.day() + 7 > last_day_of_month(date)
+            }
+            Self::Quarterly(day) => {
+                let months =
+                    (date.year() - start.year()) * 12 + date.month() as i32 - start.month() as i32;
+                months.rem_euclid(3) == 0 && is_day_of_month(date, *day)
+            }
+            Self::Yearly(month, day) => {
+                date.month() == u32::from(*month) && is_day_of_month(date, *day)
+            }
+        }
+    }
+}
+
+/// Formats the pattern as its canonical `@every-*` token.
+impl fmt::Display for RecurringPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "@every-day"),
+            Self::Weekday => write!(f, "@every-weekday"),
+            Self::Weekly(day) => write!(f, "@every-{}", weekday_name(*day)),
+            Self::Monthly(day) => write!(f, "@every-{day}"),
+            Self::EveryNWeeks(interval, day) => {
+                write!(f, "@every-{interval}w-{}", &weekday_name(*day)[..3])
             }
+            Self::LastWeekdayOfMonth(day) => write!(f, "@every-last-{}", &weekday_name(*day)[..3]),
+            Self::Quarterly(day) => write!(f, "@every-quarter-{day}"),
+            Self::Yearly(month, day) => write!(f, "@every-{month:02}/{day:02}"),
+        }
+    }
+}
+
+/// When a recurring entry stops: `-until-DATE` or `-count-N` after the pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceEnd {
+    /// Last date an occurrence may fall on (inclusive)
+    Until(NaiveDate),
+    /// Total number of occurrences, counting the entry's own day if it matches
+    Count(u16),
+}
+
+/// A recurring pattern anchored to the day its entry was written, with an optional end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    pub pattern: RecurringPattern,
+    pub start: NaiveDate,
+    pub end: Option<RecurrenceEnd>,
+}
+
+impl Recurrence {
+    /// Returns the date of the final occurrence, or None if the recurrence never ends.
+    /// A count the scanned years don't reach ends at the last occurrence found.
+    #[must_use]
+    pub fn last_date(&self) -> Option<NaiveDate> {
+        match self.end? {
+            RecurrenceEnd::Until(date) => Some(date),
+            RecurrenceEnd::Count(count) => self
+                .start
+                .iter_days()
+                .take(MAX_COUNT_SCAN_DAYS)
+                .filter(|date| self.pattern.matches(self.start, *date))
+                .take(usize::from(count.max(1)))
+                .last(),
+        }
+    }
+
+    /// Returns true if the recurrence has an occurrence on the given date.
+    /// Callers checking many dates should use `last_date` once and `matches_until`.
+    #[must_use]
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        self.matches_until(date, self.last_date())
+    }
+
+    /// Like `occurs_on`, with the final occurrence already resolved.
+    #[must_use]
+    pub fn matches_until(&self, date: NaiveDate, last_date: Option<NaiveDate>) -> bool {
+        date >= self.start
+            && last_date.is_none_or(|last| date <= last)
+            && self.pattern.matches(self.start, date)
+    }
+}
+
+/// Formats the recurrence as its `@every-*` token, including any end condition.
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)?;
+        match self.end {
+            Some(RecurrenceEnd::Until(date)) => write!(f, "-until-{}", date.format("%Y/%m/%d")),
+            Some(RecurrenceEnd::Count(count)) => write!(f, "-count-{count}"),
+            None => Ok(()),
         }
     }
 }
 
+/// Upper bound when resolving `-count-N`, so a pattern that rarely matches stays cheap.
+const MAX_COUNT_SCAN_DAYS: usize = 366 * 50;
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Matches a day of month, falling back to the last day for short months.
+fn is_day_of_month(date: NaiveDate, day: u8) -> bool {
+    let last_day = last_day_of_month(date);
+    if u32::from(day) > last_day {
+        date.day() == last_day
+    } else {
+        date.day() == u32::from(day)
+    }
+}
+
+/// Which token scheduled an entry onto another day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleKind {
+    /// @due-DATE - shows on the date, then as overdue until completed
+    Due,
+    /// @on-DATE - shows on the date only
+    On,
+}
+
+/// A one-off date an entry is scheduled for via @due-* or @on-*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledDate {
+    pub kind: ScheduleKind,
+    pub date: NaiveDate,
+}
+
 /// Returns the last day of the month for the given date.
 #[must_use]
 fn last_day_of_month(date: NaiveDate) -> u32 {
@@ -208,62 +503,256 @@ fn last_day_of_month(date: NaiveDate) -> u32 {
 #[must_use]
 pub fn parse_to_raw_entry(line: &str) -> RawEntry {
     let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
 
-    if let Some(content) = trimmed.strip_prefix("- [ ] ") {
-        return RawEntry {
-            entry_type: EntryType::Task { completed: false },
-            content: content.to_string(),
-        };
-    }
-    if let Some(content) = trimmed.strip_prefix("- [x] ") {
-        return RawEntry {
-            entry_type: EntryType::Task { completed: true },
-            content: content.to_string(),
-        };
-    }
-    if let Some(content) = trimmed.strip_prefix("* ") {
-        return RawEntry {
-            entry_type: EntryType::Event,
-            content: content.to_string(),
-        };
-    }
-    if let Some(content) = trimmed.strip_prefix("- ") {
-        return RawEntry {
-            entry_type: EntryType::Note,
-            content: content.to_string(),
-        };
-    }
+    let (entry_type, content) = if let Some((state, content)) = strip_task_prefix(trimmed) {
+        (EntryType::Task { state }, content)
+    } else if let Some(content) = trimmed.strip_prefix("* ") {
+        (EntryType::Event, content)
+    } else if let Some(content) = trimmed.strip_prefix("- ") {
+        (EntryType::Note, content)
+    } else {
+        (EntryType::Note, trimmed)
+    };
     RawEntry {
-        entry_type: EntryType::Note,
-        content: trimmed.to_string(),
+        entry_type,
+        content: content.to_string(),
+        depth: indent_depth(indent),
+        indent: indent.to_string(),
+        body: Vec::new(),
     }
 }
 
+/// Converts leading whitespace to a nesting level: a tab or two spaces per level.
+fn indent_depth(indent: &str) -> usize {
+    let width: usize = indent.chars().map(|c| if c == '\t' { 2 } else { 1 }).sum();
+    width / 2
+}
+
+/// Splits a `- [?] ` checkbox prefix into its task state and the rest of the line.
+fn strip_task_prefix(line: &str) -> Option<(TaskState, &str)> {
+    let rest = line.strip_prefix("- [")?;
+    let marker = rest.chars().next()?;
+    let state = TaskState::from_marker(marker)?;
+    let content = rest[marker.len_utf8()..].strip_prefix("] ")?;
+    Some((state, content))
+}
+
 fn parse_line(line: &str) -> Line {
     let trimmed = line.trim_start();
-    if trimmed.starts_with("- [ ] ")
-        || trimmed.starts_with("- [x] ")
-        || trimmed.starts_with("* ")
-        || trimmed.starts_with("- ")
-    {
+    if trimmed.starts_with("* ") || trimmed.starts_with("- ") {
         Line::Entry(parse_to_raw_entry(line))
     } else {
         Line::Raw(line.to_string())
     }
 }
 
+/// Strips the indentation that attaches a continuation line to an entry at
+/// `depth`. Returns None if the line isn't indented deeper than the entry.
+fn strip_continuation_indent(line: &str, depth: usize) -> Option<&str> {
+    let mut remaining = (depth + 1) * 2;
+    let mut chars = line.char_indices();
+    while remaining > 0 {
+        match chars.next()? {
+            (_, ' ') => remaining -= 1,
+            (_, '\t') => remaining = remaining.saturating_sub(2),
+            _ => return None,
+        }
+    }
+    let offset = chars.next().map_or(line.len(), |(i, _)| i);
+    Some(&line[offset..])
+}
+
+/// Whether a body line, past any backslashes escaping it, starts like an entry.
+fn has_entry_bullet(line: &str) -> bool {
+    let bullet = line.trim_start().trim_start_matches('\\');
+    bullet.starts_with("- ") || bullet.starts_with("* ")
+}
+
+/// Escapes a body line that would otherwise read back as a nested entry with a
+/// `\` before its bullet, as markdown does. Lines already escaped get one more,
+/// so `unescape_body_line` always gives back the line as written.
+fn escape_body_line(line: &str) -> String {
+    if !has_entry_bullet(line) {
+        return line.to_string();
+    }
+    let text = line.trim_start();
+    format!("{}\\{text}", &line[..line.len() - text.len()])
+}
+
+fn unescape_body_line(line: &str) -> String {
+    let text = line.trim_start();
+    if !has_entry_bullet(line) || !text.starts_with('\\') {
+        return line.to_string();
+    }
+    format!("{}{}", &line[..line.len() - text.len()], &text[1..])
+}
+
+/// Parses a day's lines. Non-entry lines indented deeper than the entry above
+/// them become that entry's body, along with any blank lines between them.
 #[must_use]
 pub fn parse_lines(content: &str) -> Vec<Line> {
-    content.lines().map(parse_line).collect()
+    let mut lines: Vec<Line> = Vec::new();
+    // Blank lines after an entry belong to its body only if more body follows
+    let mut blanks: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let parsed = parse_line(line);
+        if let Line::Raw(raw) = &parsed
+            && let Some(Line::Entry(entry)) = lines.last_mut()
+        {
+            if raw.trim().is_empty() {
+                blanks.push(raw.clone());
+                continue;
+            }
+            if let Some(body_line) = strip_continuation_indent(raw, entry.depth) {
+                entry.body.extend(blanks.drain(..).map(|_| String::new()));
+                entry.body.push(unescape_body_line(body_line));
+                continue;
+            }
+        }
+        lines.extend(blanks.drain(..).map(Line::Raw));
+        lines.push(parsed);
+    }
+    lines.extend(blanks.into_iter().map(Line::Raw));
+    lines
 }
 
 fn serialize_line(line: &Line) -> String {
     match line {
-        Line::Entry(raw_entry) => format!("{}{}", raw_entry.prefix(), raw_entry.content),
+        Line::Entry(raw_entry) => serialize_entry(raw_entry),
         Line::Raw(s) => s.clone(),
     }
 }
 
+/// Serializes an entry at its nesting depth, followed by its body indented one
+/// level deeper. The entry's own indentation is written back as it was read
+/// unless its depth changed; tab-indented entries keep indenting with tabs.
+#[must_use]
+pub fn serialize_entry(entry: &RawEntry) -> String {
+    let unit = if entry.indent.contains('\t') {
+        "\t"
+    } else {
+        "  "
+    };
+    let indent = if indent_depth(&entry.indent) == entry.depth {
+        entry.indent.clone()
+    } else {
+        unit.repeat(entry.depth)
+    };
+    let mut text = format!("{indent}{}{}", entry.prefix(), entry.content);
+    let body_indent = unit.repeat(entry.depth + 1);
+    for line in &entry.body {
+        text.push('\n');
+        if !line.is_empty() {
+            text.push_str(&body_indent);
+            text.push_str(&escape_body_line(line));
+        }
+    }
+    text
+}
+
+/// Joins an entry's content and body lines into newline-separated text.
+#[must_use]
+pub fn join_entry_text(content: &str, body: &[String]) -> String {
+    std::iter::once(content)
+        .chain(body.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits newline-separated text into content (the first line) and body (the
+/// rest), dropping trailing whitespace and trailing blank lines from the body.
+#[must_use]
+pub fn split_entry_text(text: &str) -> (String, Vec<String>) {
+    let mut lines = text.lines();
+    let content = lines.next().unwrap_or_default().to_string();
+    let mut body: Vec<String> = lines.map(|line| line.trim_end().to_string()).collect();
+    while body.last().is_some_and(String::is_empty) {
+        body.pop();
+    }
+    (content, body)
+}
+
+/// Returns the line range covering the entry at `index` and its nested entries.
+/// Nesting ends at the first line that isn't an entry indented deeper than it.
+#[must_use]
+pub fn subtree_range(lines: &[Line], index: usize) -> Range<usize> {
+    let Some(Line::Entry(parent)) = lines.get(index) else {
+        return index..index;
+    };
+    let end = lines[index + 1..]
+        .iter()
+        .position(|line| !matches!(line, Line::Entry(child) if child.depth > parent.depth))
+        .map_or(lines.len(), |offset| index + 1 + offset);
+    index..end
+}
+
+/// Counts (completed, total) tasks nested under the entry at `index`.
+/// Returns None when the entry has no nested tasks.
+#[must_use]
+pub fn subtask_progress(lines: &[Line], index: usize) -> Option<(usize, usize)> {
+    let range = subtree_range(lines, index);
+    let (done, total) = lines[range.start + 1..range.end]
+        .iter()
+        .filter_map(|line| match line {
+            Line::Entry(RawEntry {
+                entry_type: EntryType::Task { state },
+                ..
+            }) => Some(state.is_closed()),
+            _ => None,
+        })
+        .fold((0, 0), |(done, total), completed| {
+            (done + usize::from(completed), total + 1)
+        });
+    (total > 0).then_some((done, total))
+}
+
+/// Toggles the task at `index` and carries its nested tasks along: completing
+/// it completes open subtasks, and reopening it reopens done ones. Migrated and
+/// cancelled subtasks keep their state.
+///
+/// Tasks that become done are stamped with `completed_on` when given; tasks
+/// that reopen lose their stamp.
+pub fn toggle_with_subtasks(lines: &mut [Line], index: usize, completed_on: Option<NaiveDate>) {
+    let range = subtree_range(lines, index);
+    let Some(Line::Entry(parent)) = lines.get_mut(index) else {
+        return;
+    };
+    parent.toggle_complete();
+    let EntryType::Task { state } = parent.entry_type else {
+        return;
+    };
+    record_completion(parent, completed_on);
+    for line in &mut lines[range.start + 1..range.end] {
+        if let Line::Entry(child) = line
+            && let EntryType::Task { state: child_state } = child.entry_type
+        {
+            let carries = match state {
+                TaskState::Done => !child_state.is_closed(),
+                _ => child_state == TaskState::Done,
+            };
+            if carries {
+                child.entry_type = EntryType::Task { state };
+                record_completion(child, completed_on);
+            }
+        }
+    }
+}
+
+/// Updates the `<!-- done: ... -->` stamp after a task's state changed.
+/// Recurring entries keep their per-day done list untouched.
+fn record_completion(entry: &mut RawEntry, completed_on: Option<NaiveDate>) {
+    if RECURRING_REGEX.is_match(&entry.content) {
+        return;
+    }
+    if entry.entry_type != EntryType::DONE_TASK {
+        entry.content = strip_done_meta(&entry.content);
+    } else if let Some(date) = completed_on {
+        entry.content = add_done_date(&strip_done_meta(&entry.content), date);
+    }
+}
+
 #[must_use]
 pub fn serialize_lines(lines: &[Line]) -> String {
     lines
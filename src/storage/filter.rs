@@ -1,36 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use std::io;
-use std::path::Path;
 use std::sync::LazyLock;
 
 use chrono::NaiveDate;
 use regex::Regex;
 
+use super::archive::archives_in_range;
 use super::date_parsing::{ParseContext, parse_date, parse_weekday};
-use super::entries::{Entry, EntryType, Line, RawEntry, RecurringPattern, SourceType, parse_lines};
-use super::persistence::{load_journal, parse_day_header};
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum FilterType {
-    Task,
-    Note,
-    Event,
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct Filter {
-    pub entry_types: Vec<FilterType>,
-    pub completed: Option<bool>,
-    pub tags: Vec<String>,
-    pub exclude_tags: Vec<String>,
-    pub search_terms: Vec<String>,
-    pub exclude_terms: Vec<String>,
-    pub exclude_types: Vec<FilterType>,
-    pub before_date: Option<NaiveDate>,
-    pub after_date: Option<NaiveDate>,
-    pub recurring: bool,
-    pub invalid_tokens: Vec<String>,
-}
+use super::entries::{
+    Entry, EntryType, Line, RawEntry, Recurrence, RecurrenceEnd, RecurringPattern, ScheduleKind,
+    ScheduledDate, SourceType, parse_lines,
+};
+use super::persistence::{load_days, load_journal};
+use super::query::Filter;
+use super::store::Journal;
 
 pub static TAG_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"#([a-zA-Z][a-zA-Z0-9_-]*)").unwrap());
@@ -65,18 +48,50 @@ pub static SAVED_FILTER_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\$(\w+)\b").unwrap());
 
 /// Matches @every-* patterns for recurring entries:
-/// @every-day, @every-weekday, @every-mon..sun (or full names), @every-1..31
+/// @every-day, @every-weekday, @every-mon..sun (or full names), @every-1..31,
+/// @every-2w-tue, @every-last-fri, @every-quarter-1, @every-03/15,
+/// optionally followed by an end condition: -until-DATE or -count-N
 pub static RECURRING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)@every-(day|weekday|monday|tuesday|wednesday|thursday|friday|saturday|sunday|mon|tue|wed|thu|fri|sat|sun|[1-9]|[12]\d|3[01])(?:\s|$)")
-        .unwrap()
+    let weekday =
+        "monday|tuesday|wednesday|thursday|friday|saturday|sunday|mon|tue|wed|thu|fri|sat|sun";
+    let day = r"[1-9]|[12]\d|3[01]";
+    Regex::new(&format!(
+        r"(?i)@every-(day|weekday|{weekday}|{day}|\d{{1,2}}w-(?:{weekday})|last-(?:{weekday})|quarter-(?:{day})|\d{{1,2}}/\d{{1,2}})(?:-until-([a-z0-9/+]+)|-count-(\d{{1,3}}))?(?:\s|$)"
+    ))
+    .unwrap()
 });
 
+/// Matches @due-* and @on-* scheduled dates. The date accepts any entry date syntax
+/// (11/03, 2026/11/03, fri, d3) and resolves relative to the entry's own day.
+/// The `@` must not follow a word character, so "foo@on-call" is left alone.
+pub static SCHEDULED_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\B@(due|on)-([a-z0-9/+]+)\b").unwrap());
+
+/// Matches a priority marker: !1 (highest) through !3. The `!` must not follow a
+/// word character, so "wow!1" is left alone.
+pub static PRIORITY_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\B!([1-3])\b").unwrap());
+
 /// Matches <!-- done: ... --> metadata comment at end of content
-static DONE_META_REGEX: LazyLock<Regex> =
+pub(super) static DONE_META_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\s*<!--\s*done:\s*([^>]*)\s*-->").unwrap());
 
+/// The entry's priority from its first `!1`..`!3` marker, 1 being highest.
+#[must_use]
+pub fn extract_priority(content: &str) -> Option<u8> {
+    PRIORITY_REGEX
+        .captures(content)
+        .and_then(|caps| caps[1].parse().ok())
+}
+
+/// Sort key placing higher priorities first and entries without one last.
+#[must_use]
+pub fn priority_rank(content: &str) -> u8 {
+    extract_priority(content).unwrap_or(u8::MAX)
+}
+
 /// Extracts completion dates from entry content's <!-- done: ... --> comment.
-fn extract_done_dates(content: &str) -> Vec<NaiveDate> {
+#[must_use]
+pub fn extract_done_dates(content: &str) -> Vec<NaiveDate> {
     DONE_META_REGEX
         .captures(content)
         .and_then(|caps| caps.get(1))
@@ -139,13 +154,17 @@ pub fn strip_done_meta(content: &str) -> String {
 #[must_use]
 pub fn restore_done_meta(new_content: &str, original: &str) -> String {
     let done_dates = extract_done_dates(original);
-    format_done_meta(new_content, &done_dates)
+    // The metadata stays on the first line, ahead of any body lines
+    match new_content.split_once('\n') {
+        Some((first, body)) => format!("{}\n{body}", format_done_meta(first, &done_dates)),
+        None => format_done_meta(new_content, &done_dates),
+    }
 }
 
 /// Checks if a token looks like spread date syntax (not plain text search).
 /// Spread syntax includes: DATE, DATE.., ..DATE, DATE..DATE
 /// Where DATE can be: mm/dd, mm/dd/yy, mm/dd/yyyy, yyyy/mm/dd, d[1-999][+], weekday[+]
-fn is_spread_syntax(token: &str) -> bool {
+pub(super) fn is_spread_syntax(token: &str) -> bool {
     // Contains ".." -> definitely spread syntax
     if token.contains("..") {
         return true;
@@ -171,7 +190,7 @@ fn is_spread_syntax(token: &str) -> bool {
 /// - ..DATE (past): all past through date
 /// - ..DATE+ (future): from today to date
 /// - DATE..DATE: between two dates
-fn parse_spread_date(
+pub(super) fn parse_spread_date(
     token: &str,
     today: NaiveDate,
 ) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
@@ -221,12 +240,12 @@ pub fn extract_tags(content: &str) -> Vec<String> {
 
 /// Collects all unique tags from the current journal.
 /// Returns tags sorted alphabetically, deduplicated (case-insensitive, first occurrence preserved).
-pub fn collect_journal_tags(path: &Path) -> io::Result<Vec<String>> {
-    let journal = load_journal(path)?;
+pub fn collect_journal_tags(journal: &Journal) -> io::Result<Vec<String>> {
+    let content = load_journal(journal)?;
     let mut seen_lower: HashSet<String> = HashSet::new();
     let mut tags: Vec<String> = Vec::new();
 
-    for cap in TAG_REGEX.captures_iter(&journal) {
+    for cap in TAG_REGEX.captures_iter(&content) {
         let tag = cap[1].to_string();
         let lower = tag.to_lowercase();
         if seen_lower.insert(lower) {
@@ -270,14 +289,24 @@ fn parse_filter_date_with_bias(
     parse_date(input, ctx, today)
 }
 
-/// Normalizes entry structure to: [content] [recurring_dates] [#tags]
+/// Normalizes entry structure to: [content] [recurring/scheduled dates] [#tags]
 ///
 /// - Trailing section = contiguous dates/tags at end (only whitespace between them)
 /// - Inline #tags (in content section) have # stripped
-/// - @every-* patterns are extracted from anywhere and moved to structure
+/// - @every-*, @due-* and @on-* patterns are extracted from anywhere and moved to structure
 #[must_use]
 pub fn normalize_entry_structure(content: &str) -> (String, Option<String>) {
-    let recurring_dates: Vec<_> = RECURRING_REGEX.find_iter(content).collect();
+    // Only scheduled dates that parse count, so text like "@on-call" stays put
+    let today = chrono::Local::now().date_naive();
+    let mut recurring_dates: Vec<_> = RECURRING_REGEX
+        .find_iter(content)
+        .chain(
+            SCHEDULED_REGEX
+                .find_iter(content)
+                .filter(|m| extract_scheduled_date(m.as_str(), today).is_some()),
+        )
+        .collect();
+    recurring_dates.sort_by_key(regex::Match::start);
     let tags: Vec<_> = TAG_REGEX.find_iter(content).collect();
 
     if recurring_dates.is_empty() && tags.is_empty() {
@@ -302,7 +331,7 @@ pub fn normalize_entry_structure(content: &str) -> (String, Option<String>) {
     }
 
     // Sort descending so we can modify from end to start without invalidating positions
-    removals.sort_by(|a, b| b.0.cmp(&a.0));
+    removals.sort_by_key(|r| std::cmp::Reverse(r.0));
 
     let mut result = content.to_string();
     for (start, end, replacement) in removals {
@@ -380,33 +409,32 @@ pub fn expand_favorite_tags(content: &str, favorite_tags: &HashMap<String, Strin
 }
 
 /// Expands saved filter shortcuts ($name) with their definitions from config.
-/// Returns the expanded query and a list of unknown filter names.
+/// Definitions using OR are parenthesized so they stay whole next to other
+/// filters. Unknown names are left for the query parser to report.
 #[must_use]
-pub fn expand_saved_filters(
-    query: &str,
-    filters: &HashMap<String, String>,
-) -> (String, Vec<String>) {
-    use std::cell::RefCell;
-
-    let unknown = RefCell::new(Vec::new());
-    let result = SAVED_FILTER_REGEX
+pub fn expand_saved_filters(query: &str, filters: &HashMap<String, String>) -> String {
+    SAVED_FILTER_REGEX
         .replace_all(query, |caps: &regex::Captures| {
             let name = &caps[1];
             filters.get(name).map_or_else(
-                || {
-                    unknown.borrow_mut().push(caps[0].to_string());
-                    caps[0].to_string()
+                || caps[0].to_string(),
+                |expansion| {
+                    let has_or = expansion
+                        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                        .any(|word| word == "OR" || word.contains('|'));
+                    if has_or {
+                        format!("({expansion})")
+                    } else {
+                        expansion.clone()
+                    }
                 },
-                |expansion| expansion.clone(),
             )
         })
-        .into_owned();
-
-    (result, unknown.into_inner())
+        .into_owned()
 }
 
-/// Parses an @every-* pattern string (without the @every- prefix) into a RecurringPattern.
-/// Reuses `parse_weekday()` for weekday names to avoid duplication.
+/// Parses an @every-* pattern string (without the @every- prefix or end condition)
+/// into a RecurringPattern. Reuses `parse_weekday()` for weekday names to avoid duplication.
 #[must_use]
 pub fn parse_recurring_pattern(pattern_str: &str) -> Option<RecurringPattern> {
     let lower = pattern_str.to_lowercase();
@@ -418,16 +446,31 @@ pub fn parse_recurring_pattern(pattern_str: &str) -> Option<RecurringPattern> {
             if let Some(weekday) = parse_weekday(&lower) {
                 return Some(RecurringPattern::Weekly(weekday));
             }
+            if let Some(weekday) = lower.strip_prefix("last-").and_then(parse_weekday) {
+                return Some(RecurringPattern::LastWeekdayOfMonth(weekday));
+            }
+            if let Some(day) = lower.strip_prefix("quarter-") {
+                return parse_day_of_month(day).map(RecurringPattern::Quarterly);
+            }
+            if let Some((interval, weekday)) = lower.split_once("w-") {
+                let interval = interval.parse::<u8>().ok().filter(|&n| n >= 1)?;
+                return parse_weekday(weekday)
+                    .map(|weekday| RecurringPattern::EveryNWeeks(interval, weekday));
+            }
+            if let Some((month, day)) = lower.split_once('/') {
+                let month = month.parse::<u8>().ok().filter(|m| (1..=12).contains(m))?;
+                return parse_day_of_month(day).map(|day| RecurringPattern::Yearly(month, day));
+            }
             // Then try as day of month (1-31)
-            lower
-                .parse::<u8>()
-                .ok()
-                .filter(|&d| (1..=31).contains(&d))
-                .map(RecurringPattern::Monthly)
+            parse_day_of_month(&lower).map(RecurringPattern::Monthly)
         }
     }
 }
 
+fn parse_day_of_month(s: &str) -> Option<u8> {
+    s.parse::<u8>().ok().filter(|d| (1..=31).contains(d))
+}
+
 /// Strips @every-* tags from content (e.g., for matching done-today entries).
 #[must_use]
 pub fn strip_recurring_tags(content: &str) -> String {
@@ -443,39 +486,87 @@ pub fn extract_recurring_pattern(content: &str) -> Option<RecurringPattern> {
         .and_then(|m| parse_recurring_pattern(m.as_str()))
 }
 
-/// Collects all recurring projected entries for the target date.
+/// Extracts the recurrence from entry content, anchored to the day the entry was
+/// written. `-until-` dates resolve relative to that day like any entry date.
+#[must_use]
+pub fn extract_recurrence(content: &str, source_date: NaiveDate) -> Option<Recurrence> {
+    let caps = RECURRING_REGEX.captures(content)?;
+    let pattern = parse_recurring_pattern(&caps[1])?;
+    let end = if let Some(until) = caps.get(2) {
+        Some(RecurrenceEnd::Until(parse_date(
+            until.as_str(),
+            ParseContext::Entry,
+            source_date,
+        )?))
+    } else {
+        caps.get(3)
+            .and_then(|count| count.as_str().parse().ok())
+            .map(RecurrenceEnd::Count)
+    };
+    Some(Recurrence {
+        pattern,
+        start: source_date,
+        end,
+    })
+}
+
+/// Extracts the @due-*/@on-* date from entry content, resolving relative dates
+/// against the day the entry was written on.
+#[must_use]
+pub fn extract_scheduled_date(content: &str, source_date: NaiveDate) -> Option<ScheduledDate> {
+    SCHEDULED_REGEX.captures_iter(content).find_map(|caps| {
+        let kind = if caps[1].eq_ignore_ascii_case("due") {
+            ScheduleKind::Due
+        } else {
+            ScheduleKind::On
+        };
+        let date = parse_date(&caps[2], ParseContext::Entry, source_date)?;
+        Some(ScheduledDate { kind, date })
+    })
+}
+
+/// Returns the projection of a scheduled entry onto the target date, if any.
+/// Overdue projection only applies when viewing today.
+fn scheduled_source_type(
+    raw_entry: &RawEntry,
+    source_date: NaiveDate,
+    target_date: NaiveDate,
+    today: NaiveDate,
+) -> Option<SourceType> {
+    let scheduled = extract_scheduled_date(&raw_entry.content, source_date)?;
+    if scheduled.date == target_date {
+        return Some(SourceType::Scheduled);
+    }
+    let is_open_task = raw_entry.entry_type.is_open_task();
+    (scheduled.kind == ScheduleKind::Due
+        && is_open_task
+        && target_date == today
+        && scheduled.date < today)
+        .then_some(SourceType::Overdue)
+}
+
+/// Collects all projected entries for the target date: recurring entries whose
+/// pattern matches, entries scheduled onto it, and (for today) overdue tasks.
 /// Entries from the target date itself are excluded (they're regular entries).
 pub fn collect_projected_entries_for_date(
     target_date: NaiveDate,
-    path: &Path,
+    journal: &Journal,
 ) -> io::Result<Vec<Entry>> {
-    let journal = load_journal(path)?;
+    let today = chrono::Local::now().date_naive();
     let mut entries = Vec::new();
-    let mut current_date: Option<NaiveDate> = None;
-    let mut line_index_in_day: usize = 0;
-
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            current_date = Some(date);
-            line_index_in_day = 0;
-            continue;
-        }
 
-        if let Some(source_date) = current_date {
-            if source_date == target_date {
-                line_index_in_day += 1;
+    for (source_date, lines) in parse_journal_days(journal, |date| date != target_date)? {
+        for (line_index, line) in lines.iter().enumerate() {
+            let Line::Entry(raw_entry) = line else {
                 continue;
-            }
+            };
 
-            let parsed = parse_lines(line);
-            if let Some(Line::Entry(raw_entry)) = parsed.first()
-                && let Some(pattern) = extract_recurring_pattern(&raw_entry.content)
-                && target_date >= source_date
-                && pattern.matches(target_date)
+            if let Some(recurrence) = extract_recurrence(&raw_entry.content, source_date)
+                && recurrence.occurs_on(target_date)
             {
                 let is_done = is_done_on_date(&raw_entry.content, target_date);
                 let entry_type = if is_done {
-                    EntryType::Task { completed: true }
+                    EntryType::DONE_TASK
                 } else {
                     raw_entry.entry_type.clone()
                 };
@@ -484,44 +575,65 @@ pub fn collect_projected_entries_for_date(
                     entry_type,
                     content: strip_done_meta(&raw_entry.content),
                     source_date,
-                    line_index: line_index_in_day,
+                    line_index,
                     source_type: SourceType::Recurring,
-                    source_journal: path.to_path_buf(),
+                    source_journal: journal.clone(),
+                    depth: raw_entry.depth,
+                    indent: raw_entry.indent.clone(),
+                    body: raw_entry.body.clone(),
                 });
+            } else if !RECURRING_REGEX.is_match(&raw_entry.content)
+                && let Some(source_type) =
+                    scheduled_source_type(raw_entry, source_date, target_date, today)
+            {
+                entries.push(Entry::from_raw(
+                    raw_entry,
+                    source_date,
+                    line_index,
+                    source_type,
+                    journal.clone(),
+                ));
             }
-            line_index_in_day += 1;
         }
     }
 
-    entries.sort_by_key(|e| e.source_date);
+    // Overdue tasks lead the list as their own section
+    entries.sort_by_key(|e| (e.source_type != SourceType::Overdue, e.source_date));
     Ok(entries)
 }
 
+/// Each day's lines in journal order, parsed the way `load_day_lines` parses
+/// them so line indices agree. Days rejected by `include` aren't parsed.
+fn parse_journal_days(
+    journal: &Journal,
+    include: impl Fn(NaiveDate) -> bool,
+) -> io::Result<Vec<(NaiveDate, Vec<Line>)>> {
+    Ok(load_days(journal)?
+        .into_iter()
+        .filter(|(date, _)| include(*date))
+        .map(|(date, content)| (date, parse_lines(&content)))
+        .collect())
+}
+
 /// Scans the journal for recurring entries and returns which dates in the range have them.
 /// More efficient than calling collect_projected_entries_for_date for each date.
 pub fn scan_recurring_in_range(
     start: NaiveDate,
     end: NaiveDate,
-    path: &Path,
+    journal: &Journal,
 ) -> io::Result<HashSet<NaiveDate>> {
-    let journal = load_journal(path)?;
     let mut result = HashSet::new();
-    let mut current_date: Option<NaiveDate> = None;
 
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            current_date = Some(date);
-            continue;
-        }
-
-        if let Some(source_date) = current_date {
+    for (source_date, content) in load_days(journal)? {
+        for line in content.lines() {
             let parsed = parse_lines(line);
             if let Some(Line::Entry(raw_entry)) = parsed.first()
-                && let Some(pattern) = extract_recurring_pattern(&raw_entry.content)
+                && let Some(recurrence) = extract_recurrence(&raw_entry.content, source_date)
             {
                 // Check each date in range to see if this pattern matches
+                let last_date = recurrence.last_date();
                 for date in start.iter_days().take_while(|d| *d <= end) {
-                    if date >= source_date && date != source_date && pattern.matches(date) {
+                    if date > source_date && recurrence.matches_until(date, last_date) {
                         result.insert(date);
                     }
                 }
@@ -532,222 +644,64 @@ pub fn scan_recurring_in_range(
     Ok(result)
 }
 
-fn parse_type_keyword(s: &str) -> Option<FilterType> {
-    match s {
-        "tasks" | "task" | "t" => Some(FilterType::Task),
-        "notes" | "note" | "n" => Some(FilterType::Note),
-        "events" | "event" | "e" => Some(FilterType::Event),
-        _ => None,
-    }
-}
-
-#[must_use]
-pub fn parse_filter_query(query: &str) -> Filter {
-    let mut filter = Filter::default();
-    let today = chrono::Local::now().date_naive();
-
-    for token in query.split_whitespace() {
-        // Spread date syntax: DATE, DATE.., ..DATE, DATE..DATE
-        // Dates default to past (d7 = 7 days ago, mon = last Monday)
-        // Append + for explicit future (d7+ = 7 days from now, mon+ = next Monday)
-        if is_spread_syntax(token) {
-            if filter.before_date.is_some() || filter.after_date.is_some() {
-                filter
-                    .invalid_tokens
-                    .push("Multiple date ranges".to_string());
-            } else if let Some((before, after)) = parse_spread_date(token, today) {
-                filter.before_date = before;
-                filter.after_date = after;
-            } else {
-                filter.invalid_tokens.push(token.to_string());
-            }
-            continue;
-        }
-
-        // Content-based filters: @recurring
-        if token == "@recurring" {
-            filter.recurring = true;
-            continue;
-        }
-        if token.starts_with('@') {
-            filter.invalid_tokens.push(token.to_string());
-            continue;
-        }
-
-        if let Some(negated) = token.strip_prefix('-') {
-            if let Some(tag) = negated.strip_prefix('#') {
-                filter.exclude_tags.push(tag.to_string());
-            } else if let Some(type_str) = negated.strip_prefix('!') {
-                if let Some(filter_type) = parse_type_keyword(type_str) {
-                    filter.exclude_types.push(filter_type);
-                } else {
-                    filter.invalid_tokens.push(token.to_string());
-                }
-            } else if !negated.is_empty() {
-                filter.exclude_terms.push(negated.to_string());
-            }
-        } else if let Some(type_str) = token.strip_prefix('!') {
-            let base_type = if let Some(idx) = type_str.find('/') {
-                &type_str[..idx]
-            } else {
-                type_str
-            };
-
-            let (new_type, completed_override) = match base_type {
-                "tasks" | "task" | "t" => (Some(FilterType::Task), Some(false)),
-                "completed" | "c" => (Some(FilterType::Task), Some(true)),
-                "notes" | "note" | "n" => (Some(FilterType::Note), None),
-                "events" | "event" | "e" => (Some(FilterType::Event), None),
-                _ => (None, None),
-            };
-
-            if let Some(new_type) = new_type {
-                if !filter.entry_types.contains(&new_type) {
-                    filter.entry_types.push(new_type);
-                }
-                // Handle completed filter: if conflicting values, show all (None)
-                if let Some(new_completed) = completed_override {
-                    filter.completed = match filter.completed {
-                        None => Some(new_completed),
-                        Some(existing) if existing != new_completed => None,
-                        other => other,
-                    };
-                }
-            } else {
-                filter.invalid_tokens.push(token.to_string());
-            }
-        } else if let Some(tag) = token.strip_prefix('#') {
-            filter.tags.push(tag.to_string());
-        } else if !token.is_empty() {
-            filter.search_terms.push(token.to_string());
-        }
-    }
-
-    filter
-}
-
 /// Collects entries matching the filter criteria.
 /// Returns entries with SourceType::Local (filter results are from their source day).
-pub fn collect_filtered_entries(filter: &Filter, path: &Path) -> io::Result<Vec<Entry>> {
-    if !filter.invalid_tokens.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let journal = load_journal(path)?;
+/// Archives are searched too when the filter's date range reaches into them;
+/// their entries are addressed through the journal, which loads archived days.
+pub fn collect_filtered_entries(filter: &Filter, journal: &Journal) -> io::Result<Vec<Entry>> {
+    let today = chrono::Local::now().date_naive();
     let mut entries = Vec::new();
-    let mut current_date: Option<NaiveDate> = None;
-    let mut line_index_in_day: usize = 0;
-
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            current_date = Some(date);
-            line_index_in_day = 0;
-            continue;
-        }
-
-        if let Some(source_date) = current_date {
-            // Date filters on day header
-            if let Some(before) = filter.before_date
-                && source_date > before
-            {
-                line_index_in_day += 1;
-                continue;
-            }
-            if let Some(after) = filter.after_date
-                && source_date < after
-            {
-                line_index_in_day += 1;
-                continue;
-            }
-
-            let parsed = parse_lines(line);
-            if let Some(Line::Entry(raw_entry)) = parsed.first() {
-                // @recurring shows only recurring; otherwise recurring entries are excluded
-                let is_recurring = RECURRING_REGEX.is_match(&raw_entry.content);
-                if filter.recurring != is_recurring {
-                    line_index_in_day += 1;
-                    continue;
-                }
-
-                if entry_matches_filter(raw_entry, filter) {
-                    entries.push(Entry::from_raw(
-                        raw_entry,
-                        source_date,
-                        line_index_in_day,
-                        SourceType::Local,
-                        path.to_path_buf(),
-                    ));
-                }
-            }
-            line_index_in_day += 1;
-        }
-    }
 
-    entries.sort_by_key(|entry| entry.source_date);
-    Ok(entries)
-}
-
-fn entry_type_to_filter_type(entry_type: &EntryType) -> FilterType {
-    match entry_type {
-        EntryType::Task { .. } => FilterType::Task,
-        EntryType::Note => FilterType::Note,
-        EntryType::Event => FilterType::Event,
-    }
-}
-
-fn entry_matches_filter(entry: &RawEntry, filter: &Filter) -> bool {
-    let entry_filter_type = entry_type_to_filter_type(&entry.entry_type);
-
-    if !filter.entry_types.is_empty() && !filter.entry_types.contains(&entry_filter_type) {
-        return false;
-    }
-
-    for excluded_type in &filter.exclude_types {
-        if &entry_filter_type == excluded_type {
-            return false;
-        }
-    }
+    // Only days within the ranges every match needs are read
+    let (start, end) = filter.day_bounds();
+    let day_matches = |date: NaiveDate| {
+        start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end)
+    };
 
-    if let Some(want_completed) = filter.completed
-        && let EntryType::Task { completed } = entry.entry_type
-        && completed != want_completed
-    {
-        return false;
+    let mut days = parse_journal_days(journal, day_matches)?;
+    for archive in archives_in_range(journal, start, end)? {
+        // The journal's own copy of a day is the one that loads
+        let found: HashSet<NaiveDate> = days.iter().map(|(date, _)| *date).collect();
+        days.extend(parse_journal_days(&journal.sibling(archive), |date| {
+            day_matches(date) && !found.contains(&date)
+        })?);
     }
 
-    let entry_tags = extract_tags(&entry.content);
-
-    for required_tag in &filter.tags {
-        if !entry_tags
-            .iter()
-            .any(|t| t.eq_ignore_ascii_case(required_tag))
-        {
-            return false;
-        }
-    }
+    for (source_date, lines) in days {
+        for (line_index, line) in lines.iter().enumerate() {
+            let Line::Entry(raw_entry) = line else {
+                continue;
+            };
 
-    for excluded_tag in &filter.exclude_tags {
-        if entry_tags
-            .iter()
-            .any(|t| t.eq_ignore_ascii_case(excluded_tag))
-        {
-            return false;
+            if filter.matches(raw_entry, source_date, today) {
+                entries.push(Entry::from_raw(
+                    raw_entry,
+                    source_date,
+                    line_index,
+                    SourceType::Local,
+                    journal.clone(),
+                ));
+            }
         }
     }
 
-    let content_lower = entry.content.to_lowercase();
-
-    for term in &filter.search_terms {
-        if !content_lower.contains(&term.to_lowercase()) {
-            return false;
-        }
+    if filter.targets_scheduled_date() {
+        entries.sort_by_key(|entry| {
+            extract_scheduled_date(&entry.content, entry.source_date)
+                .map_or(entry.source_date, |scheduled| scheduled.date)
+        });
+    } else if filter.by_completion() {
+        entries.sort_by_key(|entry| {
+            extract_done_dates(&entry.content)
+                .last()
+                .copied()
+                .unwrap_or(entry.source_date)
+        });
+    } else {
+        entries.sort_by_key(|entry| entry.source_date);
     }
-
-    for term in &filter.exclude_terms {
-        if content_lower.contains(&term.to_lowercase()) {
-            return false;
-        }
+    if filter.by_priority() {
+        entries.sort_by_key(|entry| priority_rank(&entry.content));
     }
-
-    true
+    Ok(entries)
 }
@@ -0,0 +1,193 @@
+//! What Corner knows about the git repository around a journal: the branch
+//! new entries are tagged with, and the commits made on a day.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use chrono::{Days, NaiveDate};
+
+/// Prefix of the tags naming the branch an entry was written on.
+const BRANCH_TAG_PREFIX: &str = "branch-";
+
+/// A commit, as listed next to the day it was made on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// Abbreviated hash
+    pub hash: String,
+    /// Commit time as `HH:MM`, local time
+    pub time: String,
+    pub subject: String,
+}
+
+/// The root of the git repository containing `path`, if any.
+#[must_use]
+pub fn repo_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// The branch checked out in the repository at `root`, or None when HEAD is
+/// detached. Reads HEAD directly, so it's cheap enough to call per entry.
+#[must_use]
+pub fn current_branch(root: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir(root)?.join("HEAD")).ok()?;
+    let branch = head.trim().strip_prefix("ref: refs/heads/")?;
+    (!branch.is_empty()).then(|| branch.to_string())
+}
+
+/// The repository's git directory. Worktrees and submodules have a `.git`
+/// file pointing at the real one.
+fn git_dir(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_file() {
+        let pointer = fs::read_to_string(&dot_git).ok()?;
+        Some(root.join(pointer.trim().strip_prefix("gitdir:")?.trim()))
+    } else {
+        Some(dot_git)
+    }
+}
+
+/// The tag for a branch: `feature/login` becomes `branch-feature-login`.
+#[must_use]
+pub fn branch_tag(branch: &str) -> String {
+    let name: String = branch
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{BRANCH_TAG_PREFIX}{}", name.trim_matches('-'))
+}
+
+/// Appends the tag of the branch checked out next to `journal`, unless the
+/// content already has it or the journal isn't in a repository.
+#[must_use]
+pub fn stamp_branch(content: &str, journal: &Path) -> String {
+    let Some(branch) = repo_root(journal).and_then(|root| current_branch(&root)) else {
+        return content.to_string();
+    };
+    let tag = format!("#{}", branch_tag(&branch));
+    let has_tag = content
+        .split_whitespace()
+        .any(|word| word.eq_ignore_ascii_case(&tag));
+    if has_tag || content.trim().is_empty() {
+        content.to_string()
+    } else {
+        format!("{} {tag}", content.trim_end())
+    }
+}
+
+/// Commits on any branch of the repository at `root` made on `date`, newest
+/// first.
+pub fn commits_on(root: &Path, date: NaiveDate) -> io::Result<Vec<Commit>> {
+    let next = date + Days::new(1);
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "log",
+            "--all",
+            "--no-color",
+            "--date=format-local:%H:%M",
+            "--format=%h%x1f%cd%x1f%s",
+        ])
+        .arg(format!("--since={} 00:00", date.format("%Y-%m-%d")))
+        .arg(format!("--until={} 00:00", next.format("%Y-%m-%d")))
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            Some(Commit {
+                hash: fields.next()?.to_string(),
+                time: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Commits a journal's `files`, the journal and any archives it wrote to,
+/// alone with `message`, leaving anything else staged as it is. Returns false
+/// when none of them have changes to commit.
+///
+/// Refuses while a merge, rebase, cherry-pick or revert is in progress, and
+/// when any of the files is ignored by `.gitignore`.
+pub fn commit_journal(files: &[PathBuf], message: &str) -> io::Result<bool> {
+    let Some(journal) = files.first() else {
+        return Ok(false);
+    };
+    let root = repo_root(journal).ok_or_else(|| {
+        io::Error::other(format!("{} is not in a git repository", journal.display()))
+    })?;
+    if let Some(operation) = operation_in_progress(&root) {
+        return Err(io::Error::other(format!("a {operation} is in progress")));
+    }
+    let ignored = git(&root, &["check-ignore", "--"], files)?;
+    if ignored.status.success() {
+        let stdout = String::from_utf8_lossy(&ignored.stdout);
+        let path = stdout.lines().next().unwrap_or_default();
+        return Err(io::Error::other(format!("{path} is ignored by .gitignore")));
+    }
+
+    let status = checked(git(&root, &["status", "--porcelain", "--"], files)?)?;
+    if status.trim().is_empty() {
+        return Ok(false);
+    }
+    checked(git(&root, &["add", "--"], files)?)?;
+    checked(git(
+        &root,
+        &["commit", "-q", "-m", message, "--only", "--"],
+        files,
+    )?)?;
+    Ok(true)
+}
+
+/// The operation git is in the middle of, judged by the files it leaves in
+/// the git directory until it's concluded or aborted.
+fn operation_in_progress(root: &Path) -> Option<&'static str> {
+    let git_dir = git_dir(root)?;
+    [
+        ("MERGE_HEAD", "merge"),
+        ("rebase-merge", "rebase"),
+        ("rebase-apply", "rebase"),
+        ("CHERRY_PICK_HEAD", "cherry-pick"),
+        ("REVERT_HEAD", "revert"),
+    ]
+    .into_iter()
+    .find(|(marker, _)| git_dir.join(marker).exists())
+    .map(|(_, operation)| operation)
+}
+
+fn git(root: &Path, args: &[&str], paths: &[PathBuf]) -> io::Result<Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .args(paths)
+        .output()
+}
+
+/// The command's stdout, or its stderr as the error when it failed.
+fn checked(output: Output) -> io::Result<String> {
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
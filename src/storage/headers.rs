@@ -0,0 +1,137 @@
+//! Day header formats.
+//!
+//! Each day starts with a markdown heading holding its date. Corner writes
+//! `# 2026/10/16` unless a journal is configured with another heading level and
+//! strftime pattern, such as `## 2026-10-16` or `# Friday, October 16, 2026`.
+//! A heading is read as a day only when the whole line is the date at the
+//! journal's level, in its pattern or one of its read patterns, or in the
+//! original `# 2026/10/16` form, so dated sub-headings inside a day stay content.
+
+use std::fmt::Write as _;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// The header every journal was written with before formats were configurable.
+const LEGACY_LEVEL: usize = 1;
+const LEGACY_PATTERN: &str = "%Y/%m/%d";
+
+/// Deepest markdown heading level.
+const MAX_LEVEL: usize = 6;
+
+/// How day headers are written: a heading level and a strftime pattern, plus
+/// any further headings, as a level and pattern each, to accept when reading.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DayHeaderFormat {
+    level: usize,
+    pattern: String,
+    read_patterns: Vec<(usize, String)>,
+}
+
+impl Default for DayHeaderFormat {
+    fn default() -> Self {
+        Self {
+            level: LEGACY_LEVEL,
+            pattern: LEGACY_PATTERN.to_string(),
+            read_patterns: Vec::new(),
+        }
+    }
+}
+
+impl DayHeaderFormat {
+    /// Returns None unless the level is 1-6 and headers written with the
+    /// pattern can be read back as the same date.
+    #[must_use]
+    pub fn new(level: usize, pattern: &str) -> Option<Self> {
+        if !(1..=MAX_LEVEL).contains(&level) || !round_trips(pattern) {
+            return None;
+        }
+        Some(Self {
+            level,
+            pattern: pattern.to_string(),
+            read_patterns: Vec::new(),
+        })
+    }
+
+    /// Also accepts headers in `patterns` when reading, skipping any that
+    /// can't be read back as a date. A pattern may start with its own heading
+    /// marks, as in `## %Y-%m-%d`; otherwise it's read at this format's level.
+    #[must_use]
+    pub fn with_read_patterns(mut self, patterns: &[String]) -> Self {
+        self.read_patterns = patterns
+            .iter()
+            .filter_map(|pattern| match split_heading(pattern) {
+                Some((level, pattern)) => Some((level, pattern)),
+                None if pattern.starts_with('#') => None,
+                None => Some((self.level, pattern.as_str())),
+            })
+            .filter(|(level, pattern)| (1..=MAX_LEVEL).contains(level) && round_trips(pattern))
+            .map(|(level, pattern)| (level, pattern.to_string()))
+            .collect();
+        self
+    }
+
+    /// The header line for `date`.
+    #[must_use]
+    pub fn header(&self, date: NaiveDate) -> String {
+        format!("{} {}", "#".repeat(self.level), date.format(&self.pattern))
+    }
+
+    /// The date of a day header line, in any format this one accepts.
+    #[must_use]
+    pub fn parse(&self, line: &str) -> Option<NaiveDate> {
+        let (level, text) = split_heading(line.trim_end())?;
+        std::iter::once((LEGACY_LEVEL, LEGACY_PATTERN))
+            .chain(std::iter::once((self.level, self.pattern.as_str())))
+            .chain(
+                self.read_patterns
+                    .iter()
+                    .map(|(level, pattern)| (*level, pattern.as_str())),
+            )
+            .filter(|(pattern_level, _)| *pattern_level == level)
+            .find_map(|(_, pattern)| parse_date(text, pattern))
+    }
+}
+
+/// The level and text of a markdown heading line.
+fn split_heading(line: &str) -> Option<(usize, &str)> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    let text = text.strip_prefix(' ')?;
+    (level > 0).then_some((level, text))
+}
+
+/// Whether dates written with `pattern` read back as the same date.
+fn round_trips(pattern: &str) -> bool {
+    let samples = [(2026, 1, 5), (2026, 10, 16)];
+    samples.into_iter().all(|(year, month, day)| {
+        let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
+            return false;
+        };
+        let mut text = String::new();
+        write!(text, "{}", date.format(pattern)).is_ok()
+            && !text.contains('\n')
+            && parse_date(&text, pattern) == Some(date)
+    })
+}
+
+/// Parses `text` as a date in `pattern`, with nothing before or after it.
+fn parse_date(text: &str, pattern: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, pattern).ok()
+}
+
+/// Rewrites every day header in `journal`, as `from` reads them, with `to`,
+/// leaving all other lines as they are.
+#[must_use]
+pub fn reformat_day_headers(journal: &str, from: &DayHeaderFormat, to: &DayHeaderFormat) -> String {
+    journal
+        .split_inclusive('\n')
+        .map(|line| {
+            let text = line.trim_end_matches(['\n', '\r']);
+            match from.parse(text) {
+                Some(date) => to.header(date) + &line[text.len()..],
+                None => line.to_string(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,148 @@
+//! Day-level snapshots backing the undo history.
+//!
+//! Every undoable step records the days it changed as before/after pairs, keyed
+//! by journal and date rather than by position in the current view, so a step
+//! can be undone after navigating elsewhere or restarting Corner. The history
+//! itself lives in a hidden sidecar beside the hub journal
+//! (`.journal.md.undo.json`).
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::headers::DayHeaderFormat;
+use super::lock::{sidecar_path, write_atomic};
+use super::merge::merge_lines;
+use super::persistence::{load_day, save_day};
+use super::store::{Journal, journal_store};
+
+/// One day's content before and after an undoable step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayChange {
+    /// The journal as it was configured when the change was recorded
+    pub journal: Journal,
+    pub date: NaiveDate,
+    pub before: String,
+    pub after: String,
+}
+
+impl DayChange {
+    /// The change that takes the day back from `after` to `before`.
+    #[must_use]
+    pub fn reversed(&self) -> Self {
+        Self {
+            journal: self.journal.clone(),
+            date: self.date,
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+}
+
+/// Days whose content differs between two versions of a journal, in date order.
+#[must_use]
+pub fn day_changes(journal: &Journal, before: &str, after: &str) -> Vec<DayChange> {
+    if before == after {
+        return Vec::new();
+    }
+
+    let store = journal_store(journal);
+    let before_days: HashMap<NaiveDate, String> = store.split_journal(before).into_iter().collect();
+    let after_days: HashMap<NaiveDate, String> = store.split_journal(after).into_iter().collect();
+    let dates: BTreeSet<NaiveDate> = before_days
+        .keys()
+        .chain(after_days.keys())
+        .copied()
+        .collect();
+
+    dates
+        .into_iter()
+        .filter_map(|date| {
+            let before = before_days.get(&date).cloned().unwrap_or_default();
+            let after = after_days.get(&date).cloned().unwrap_or_default();
+            (before != after).then(|| DayChange {
+                journal: journal.clone(),
+                date,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// Each day's content in file order, read the same way `load_day` reads it:
+/// when a date has several headers, only the first counts.
+pub(super) fn split_days(journal: &str, format: &DayHeaderFormat) -> Vec<(NaiveDate, String)> {
+    let mut headers = Vec::new();
+    let mut offset = 0;
+    for line in journal.split_inclusive('\n') {
+        if let Some(date) = format.parse(line.trim_end_matches(['\n', '\r'])) {
+            headers.push((offset, date));
+        }
+        offset += line.len();
+    }
+
+    let mut seen = HashSet::new();
+    let mut days = Vec::new();
+    for (i, &(start, date)) in headers.iter().enumerate() {
+        if !seen.insert(date) {
+            continue;
+        }
+        let end = headers.get(i + 1).map_or(journal.len(), |&(next, _)| next);
+        let section = &journal[start..end];
+        let body = section.split_once('\n').map_or("", |(_, body)| body);
+        days.push((date, body.trim_end().to_string()));
+    }
+    days
+}
+
+/// Moves each day from `before` to `after`. Days edited since the change was
+/// recorded keep those edits when they touch other lines; if any day's edits
+/// overlap the change, nothing is written.
+pub fn apply_day_changes(changes: &[DayChange]) -> io::Result<()> {
+    let mut writes = Vec::new();
+    for change in changes {
+        let current = load_day(change.date, &change.journal)?;
+        if current == change.after {
+            continue;
+        }
+        let target = if current == change.before {
+            change.after.clone()
+        } else {
+            let base: Vec<&str> = change.before.lines().collect();
+            let ours: Vec<&str> = current.lines().collect();
+            let theirs: Vec<&str> = change.after.lines().collect();
+            merge_lines(&base, &ours, &theirs)
+                .ok_or_else(|| {
+                    io::Error::other(format!(
+                        "{} was changed since",
+                        change.date.format("%Y/%m/%d")
+                    ))
+                })?
+                .join("\n")
+        };
+        writes.push((change, target));
+    }
+
+    for (change, target) in writes {
+        save_day(change.date, &change.journal, &target)?;
+    }
+    Ok(())
+}
+
+/// Reads the undo history saved beside the hub journal, or an empty string
+/// if there is none.
+pub fn read_undo_history(hub_journal: &Path) -> io::Result<String> {
+    match fs::read_to_string(sidecar_path(hub_journal, "undo.json")) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+pub fn write_undo_history(hub_journal: &Path, history: &str) -> io::Result<()> {
+    write_atomic(&sidecar_path(hub_journal, "undo.json"), history)
+}
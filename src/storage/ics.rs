@@ -0,0 +1,268 @@
+//! ICS export of a journal's events and open tasks.
+//!
+//! Events (`* `) become all-day VEVENTs and open tasks become VTODOs, on the
+//! day they were written or the day they're scheduled for. Recurring entries
+//! carry an RRULE instead of being repeated. UIDs are derived from the
+//! journal, day and entry text, so calendars subscribed to the feed keep
+//! their items across exports until an entry is edited. A journal can also
+//! keep an `.ics` file beside it, rewritten once its saves settle.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use super::entries::{
+    EntryType, Line, RawEntry, Recurrence, RecurrenceEnd, RecurringPattern, ScheduleKind,
+    TaskState, parse_lines,
+};
+use super::filter::{
+    RECURRING_REGEX, SCHEDULED_REGEX, extract_priority, extract_recurrence, extract_scheduled_date,
+    strip_done_meta,
+};
+use super::lock::write_atomic;
+use super::persistence::load_days;
+use super::store::Journal;
+
+/// How far ahead to look for the first occurrence of a recurring entry.
+const MAX_FIRST_OCCURRENCE_DAYS: usize = 800;
+
+/// Longest content line before it's folded, in octets.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// The `.ics` file kept beside the journal at `path`.
+#[must_use]
+pub fn ics_path(path: &Path) -> PathBuf {
+    path.with_extension("ics")
+}
+
+/// Rewrites the `.ics` file beside the journal if its feed changed.
+pub fn update_ics_export(journal: &Journal) -> io::Result<()> {
+    let feed = journal_ics(journal)?;
+    let file = ics_path(journal);
+    if fs::read_to_string(&file).is_ok_and(|existing| existing == feed) {
+        return Ok(());
+    }
+    write_atomic(&file, &feed)
+}
+
+/// The journal's events and open tasks as an iCalendar feed.
+pub fn journal_ics(journal: &Journal) -> io::Result<String> {
+    let name = journal
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Corner//Journal//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(&name)),
+    ];
+
+    for (date, content) in load_days(journal)? {
+        // Identical entries on a day get their position among them in the UID
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        let parsed = parse_lines(&content);
+        for line in &parsed {
+            let Line::Entry(entry) = line else {
+                continue;
+            };
+            let copy = seen.entry(entry.content.as_str()).or_default();
+            *copy += 1;
+            let uid = format!(
+                "{:016x}@corner",
+                stable_hash(&format!(
+                    "{}\n{date}\n{}\n{copy}",
+                    journal.display(),
+                    entry.content
+                ))
+            );
+            if let Some(component) = entry_component(entry, date, &uid) {
+                lines.extend(component);
+            }
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold_line(&line));
+    }
+    Ok(ics)
+}
+
+/// The VEVENT or VTODO for an entry, or None for entries that aren't exported.
+fn entry_component(entry: &RawEntry, date: NaiveDate, uid: &str) -> Option<Vec<String>> {
+    let (component, status) = match entry.entry_type {
+        EntryType::Event => ("VEVENT", None),
+        EntryType::Task { state } if !state.is_closed() => {
+            let status = if state == TaskState::InProgress {
+                "IN-PROCESS"
+            } else {
+                "NEEDS-ACTION"
+            };
+            ("VTODO", Some(status))
+        }
+        _ => return None,
+    };
+
+    let scheduled = extract_scheduled_date(&entry.content, date);
+    let start = scheduled
+        .filter(|scheduled| scheduled.kind == ScheduleKind::On || component == "VEVENT")
+        .map_or(date, |scheduled| scheduled.date);
+    let recurrence = extract_recurrence(&entry.content, date);
+    let (start, rrule) = match recurrence {
+        Some(recurrence) => {
+            let first = first_occurrence(&recurrence)?;
+            (first, Some(rrule(&recurrence, first)))
+        }
+        None => (start, None),
+    };
+
+    let mut lines = vec![
+        format!("BEGIN:{component}"),
+        format!("UID:{uid}"),
+        format!("DTSTAMP:{}T000000Z", date.format("%Y%m%d")),
+        format!("DTSTART;VALUE=DATE:{}", ics_date(start)),
+    ];
+    if component == "VEVENT" {
+        let end = start + Days::new(1);
+        lines.push(format!("DTEND;VALUE=DATE:{}", ics_date(end)));
+    }
+    if let Some(due) = scheduled.filter(|scheduled| {
+        scheduled.kind == ScheduleKind::Due && component == "VTODO" && scheduled.date >= start
+    }) {
+        lines.push(format!("DUE;VALUE=DATE:{}", ics_date(due.date)));
+    }
+    lines.extend(rrule.map(|rule| format!("RRULE:{rule}")));
+    lines.push(format!("SUMMARY:{}", escape_text(&summary(&entry.content))));
+    if !entry.body.is_empty() {
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_text(&entry.body.join("\n"))
+        ));
+    }
+    lines.extend(status.map(|status| format!("STATUS:{status}")));
+    if let Some(priority) = extract_priority(&entry.content) {
+        // iCalendar ranks 1 highest and 9 lowest
+        lines.push(format!("PRIORITY:{}", priority * 4 - 3));
+    }
+    lines.push(format!("END:{component}"));
+    Some(lines)
+}
+
+/// The entry's text without the markup that's exported as properties.
+fn summary(content: &str) -> String {
+    let text = strip_done_meta(content);
+    let text = RECURRING_REGEX.replace_all(&text, " ");
+    let text = SCHEDULED_REGEX.replace_all(&text, " ");
+    text.split_whitespace()
+        .filter(|word| !matches!(*word, "!1" | "!2" | "!3"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The first day on or after the entry's own that the recurrence falls on.
+/// An RRULE's DTSTART is always an occurrence, so the rule starts there.
+fn first_occurrence(recurrence: &Recurrence) -> Option<NaiveDate> {
+    let last = recurrence.last_date();
+    recurrence
+        .start
+        .iter_days()
+        .take(MAX_FIRST_OCCURRENCE_DAYS)
+        .find(|date| recurrence.matches_until(*date, last))
+}
+
+/// The RRULE value for a recurrence whose first occurrence is `first`.
+fn rrule(recurrence: &Recurrence, first: NaiveDate) -> String {
+    let mut rule = match recurrence.pattern {
+        RecurringPattern::Daily => "FREQ=DAILY".to_string(),
+        RecurringPattern::Weekday => "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".to_string(),
+        RecurringPattern::Weekly(day) => format!("FREQ=WEEKLY;BYDAY={}", ics_weekday(day)),
+        RecurringPattern::Monthly(day) => format!("FREQ=MONTHLY;{}", month_day(day)),
+        RecurringPattern::EveryNWeeks(interval, day) => {
+            format!("FREQ=WEEKLY;INTERVAL={interval};BYDAY={}", ics_weekday(day))
+        }
+        RecurringPattern::LastWeekdayOfMonth(day) => {
+            format!("FREQ=MONTHLY;BYDAY=-1{}", ics_weekday(day))
+        }
+        RecurringPattern::Quarterly(day) => format!("FREQ=MONTHLY;INTERVAL=3;{}", month_day(day)),
+        RecurringPattern::Yearly(month, day) => {
+            format!("FREQ=YEARLY;BYMONTH={month};{}", month_day(day))
+        }
+    };
+    match recurrence.end {
+        Some(RecurrenceEnd::Until(until)) => {
+            let _ = write!(rule, ";UNTIL={}", ics_date(until.max(first)));
+        }
+        Some(RecurrenceEnd::Count(count)) => {
+            let _ = write!(rule, ";COUNT={}", count.max(1));
+        }
+        None => {}
+    }
+    rule
+}
+
+/// BYMONTHDAY for a day of month. Like Corner, days past the end of a short
+/// month fall on its last day: the latest of days 28 through `day` that the
+/// month has.
+fn month_day(day: u8) -> String {
+    if day <= 28 {
+        return format!("BYMONTHDAY={day}");
+    }
+    let days: Vec<String> = (28..=day).map(|day| day.to_string()).collect();
+    format!("BYMONTHDAY={};BYSETPOS=-1", days.join(","))
+}
+
+fn ics_weekday(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn ics_date(date: NaiveDate) -> String {
+    format!("{:04}{:02}{:02}", date.year(), date.month(), date.day())
+}
+
+/// Escapes TEXT values per RFC 5545.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// A content line with CRLF ending, folded onto continuation lines that start
+/// with a space so none is longer than 75 octets.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// FNV-1a, which unlike the standard hasher gives the same value on every
+/// platform and Rust version.
+fn stable_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
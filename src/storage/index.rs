@@ -0,0 +1,254 @@
+//! In-memory index of journal files, shared by the storage functions.
+//!
+//! Each journal's content is cached along with the byte offset of every day
+//! header, so loading a day is a lookup and saving a day splices only its
+//! section. Headers are found with the journal's own header format, and a
+//! file is re-indexed if it's read with another one. Entries are revalidated against the file's mtime and size on every
+//! access, so external edits are picked up the same way `check_external_changes`
+//! notices them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+
+use super::headers::DayHeaderFormat;
+use super::lock::write_atomic;
+use super::persistence::splice_day;
+
+static INDEXES: LazyLock<Mutex<HashMap<PathBuf, JournalIndex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Identifies a version of the file on disk. None when the file doesn't exist.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+pub(super) struct JournalIndex {
+    stamp: Option<FileStamp>,
+    format: DayHeaderFormat,
+    content: String,
+    /// Offset of each day header line, in file order
+    headers: Vec<(usize, NaiveDate)>,
+    /// Offset of the first header for each date
+    days: HashMap<NaiveDate, usize>,
+}
+
+impl JournalIndex {
+    fn build(content: String, stamp: Option<FileStamp>, format: &DayHeaderFormat) -> Self {
+        let mut headers = Vec::new();
+        let mut offset = 0;
+        for line in content.split_inclusive('\n') {
+            if let Some(date) = format.parse(line.trim_end_matches(['\n', '\r'])) {
+                headers.push((offset, date));
+            }
+            offset += line.len();
+        }
+        let mut index = Self {
+            stamp,
+            format: format.clone(),
+            content,
+            headers,
+            days: HashMap::new(),
+        };
+        index.rebuild_days();
+        index
+    }
+
+    fn rebuild_days(&mut self) {
+        self.days.clear();
+        for &(offset, date) in &self.headers {
+            self.days.entry(date).or_insert(offset);
+        }
+    }
+
+    pub(super) fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// The content from the first day header on, leaving out any text above it.
+    pub(super) fn days_text(&self) -> &str {
+        self.headers
+            .first()
+            .map_or("", |&(offset, _)| &self.content[offset..])
+    }
+
+    pub(super) fn has_day(&self, date: NaiveDate) -> bool {
+        self.days.contains_key(&date)
+    }
+
+    /// Dates with a header in the file, in file order.
+    pub(super) fn dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.headers.iter().map(|&(_, date)| date)
+    }
+
+    /// Byte range of a day's section: its header through the start of the next header.
+    pub(super) fn day_span(&self, date: NaiveDate) -> Option<Range<usize>> {
+        let start = *self.days.get(&date)?;
+        let next = self.headers.partition_point(|&(offset, _)| offset <= start);
+        let end = self
+            .headers
+            .get(next)
+            .map_or(self.content.len(), |&(offset, _)| offset);
+        Some(start..end)
+    }
+
+    /// The day's content without its header, trimmed like `extract_day_content`.
+    pub(super) fn day_content(&self, date: NaiveDate) -> String {
+        let Some(span) = self.day_span(date) else {
+            return String::new();
+        };
+        let section = &self.content[span];
+        let body = section.split_once('\n').map_or("", |(_, body)| body);
+        body.trim_end().to_string()
+    }
+
+    /// Rewrites one day's section in place, shifting the offsets of later days
+    /// instead of rescanning the file.
+    fn update_day(&mut self, date: NaiveDate, new_content: &str) {
+        let header = self.format.header(date);
+        let span = self.day_span(date);
+        // New days go before the first later day in file order
+        let insert_pos = match span {
+            Some(_) => None,
+            None => self
+                .headers
+                .iter()
+                .find(|&&(_, existing)| existing > date)
+                .map(|&(offset, _)| offset),
+        };
+        let tail_start = span
+            .as_ref()
+            .map_or(insert_pos.unwrap_or(self.content.len()), |span| span.end);
+        let tail_len = self.content[tail_start..].trim_end().len();
+
+        let (updated, header_offset) = splice_day(
+            &self.content,
+            &header,
+            new_content,
+            span.clone(),
+            insert_pos,
+        );
+
+        let new_tail_start = if tail_len == 0 {
+            updated.len()
+        } else {
+            updated.len() - tail_len - 1
+        };
+        let edit_start = span.as_ref().map_or(tail_start, |span| span.start);
+
+        let mut headers: Vec<(usize, NaiveDate)> = self
+            .headers
+            .iter()
+            .filter(|&&(offset, _)| offset < edit_start)
+            .copied()
+            .collect();
+        headers.extend(header_offset.map(|offset| (offset, date)));
+        headers.extend(
+            self.headers
+                .iter()
+                .filter(|&&(offset, _)| offset >= tail_start)
+                .map(|&(offset, existing)| (offset - tail_start + new_tail_start, existing)),
+        );
+
+        self.headers = headers;
+        self.content = updated;
+        self.rebuild_days();
+    }
+}
+
+/// Runs `f` against the up-to-date index for `path`, reading the file only
+/// when it changed since it was last indexed.
+pub(super) fn with_index<R>(
+    path: &Path,
+    format: &DayHeaderFormat,
+    f: impl FnOnce(&JournalIndex) -> R,
+) -> io::Result<R> {
+    let mut indexes = INDEXES.lock().unwrap_or_else(PoisonError::into_inner);
+    let index = current_index(&mut indexes, path, format)?;
+    Ok(f(index))
+}
+
+fn current_index<'a>(
+    indexes: &'a mut HashMap<PathBuf, JournalIndex>,
+    path: &Path,
+    format: &DayHeaderFormat,
+) -> io::Result<&'a mut JournalIndex> {
+    let stamp = file_stamp(path);
+    let is_current = indexes.get(path).is_some_and(|index| {
+        index.stamp.is_some() && index.stamp == stamp && index.format == *format
+    });
+    if !is_current {
+        let content = if stamp.is_some() {
+            fs::read_to_string(path)?
+        } else {
+            String::new()
+        };
+        indexes.insert(
+            path.to_path_buf(),
+            JournalIndex::build(content, stamp, format),
+        );
+    }
+    Ok(indexes.get_mut(path).expect("index was just inserted"))
+}
+
+/// Files under `dir` that have been indexed.
+pub(super) fn indexed_files(dir: &Path) -> Vec<PathBuf> {
+    INDEXES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .keys()
+        .filter(|path| path.starts_with(dir))
+        .cloned()
+        .collect()
+}
+
+/// Replaces one day's content and writes the journal, keeping the index in step.
+/// A day that's new gets a header in `format`.
+pub(super) fn save_day(
+    path: &Path,
+    format: &DayHeaderFormat,
+    date: NaiveDate,
+    content: &str,
+) -> io::Result<()> {
+    let mut indexes = INDEXES.lock().unwrap_or_else(PoisonError::into_inner);
+    let index = current_index(&mut indexes, path, format)?;
+    index.update_day(date, content);
+    if let Err(e) = write_file(path, &index.content) {
+        // The index now holds content that never reached the file
+        indexes.remove(path);
+        return Err(e);
+    }
+    index.stamp = file_stamp(path);
+    Ok(())
+}
+
+/// Writes a whole journal and re-indexes it from the written content.
+pub(super) fn save_journal(path: &Path, format: &DayHeaderFormat, content: &str) -> io::Result<()> {
+    let mut indexes = INDEXES.lock().unwrap_or_else(PoisonError::into_inner);
+    write_file(path, content)?;
+    indexes.insert(
+        path.to_path_buf(),
+        JournalIndex::build(content.to_string(), file_stamp(path), format),
+    );
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> io::Result<()> {
+    write_atomic(path, content)
+}
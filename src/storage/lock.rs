@@ -0,0 +1,116 @@
+//! Advisory locking and crash-safe writes for journal files.
+//!
+//! The lock lives in a hidden sidecar file (`.journal.md.lock`) rather than on
+//! the journal itself, because atomic writes replace the journal's inode and a
+//! lock on the old inode would no longer exclude anyone.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+thread_local! {
+    /// Journals this thread already holds the lock for, so nested
+    /// read-modify-write helpers don't deadlock on their own lock.
+    static HELD: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// Runs `f` while holding an exclusive advisory lock on the journal at `path`.
+/// Blocks until other Corner processes release the lock.
+pub(super) fn with_journal_lock<R>(
+    path: &Path,
+    f: impl FnOnce() -> io::Result<R>,
+) -> io::Result<R> {
+    if HELD.with(|held| held.borrow().contains(path)) {
+        return f();
+    }
+
+    let lock_file = open_lock_file(path)?;
+    lock_exclusive(&lock_file)?;
+    HELD.with(|held| held.borrow_mut().insert(path.to_path_buf()));
+
+    let result = f();
+
+    HELD.with(|held| held.borrow_mut().remove(path));
+    // Closing the file releases the lock
+    drop(lock_file);
+    result
+}
+
+pub(super) fn sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.{suffix}"))
+}
+
+fn open_lock_file(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sidecar_path(path, "lock"))
+}
+
+#[cfg(unix)]
+fn lock_exclusive(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    loop {
+        // SAFETY: flock on a file descriptor we own has no memory safety concerns
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn lock_exclusive(_file: &File) -> io::Result<()> {
+    Ok(())
+}
+
+/// Writes `content` to a temp file beside `path`, syncs it, and renames it over
+/// `path`, so a crash or full disk leaves either the old or the new journal.
+pub(super) fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = sidecar_path(path, &format!("tmp.{}", std::process::id()));
+    let result =
+        write_and_sync(&temp_path, path, content).and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    sync_parent_dir(path);
+    Ok(())
+}
+
+fn write_and_sync(temp_path: &Path, path: &Path, content: &str) -> io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(content.as_bytes())?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()
+}
+
+/// Persists the rename itself. Best effort: not every platform can open a directory.
+fn sync_parent_dir(path: &Path) {
+    if let Some(parent) = path.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+}
@@ -0,0 +1,165 @@
+//! Line diffs and line-level three-way merge of a day's content.
+//!
+//! The merge is used when a day changed on disk while Corner had it loaded: the
+//! user's version and the on-disk version are both diffed against the snapshot
+//! Corner loaded, and changes to different lines are combined.
+
+/// Above this many line pairs, `diff_lines` stops looking for common lines.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// One line of a diff between two versions of a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineDiff {
+    Unchanged(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff from `old` to `new`. A changed region too large to compare line
+/// by line is reported as all of its old lines removed and new lines added.
+#[must_use]
+pub fn diff_lines(old: &str, new: &str) -> Vec<LineDiff> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut diff: Vec<LineDiff> = old[..prefix]
+        .iter()
+        .map(|line| LineDiff::Unchanged((*line).to_string()))
+        .collect();
+
+    let matches = if old_mid.len().saturating_mul(new_mid.len()) <= MAX_DIFF_CELLS {
+        match_lines(old_mid, new_mid)
+    } else {
+        vec![None; old_mid.len()]
+    };
+    let mut j = 0;
+    for (i, matched) in matches.into_iter().enumerate() {
+        if let Some(matched) = matched {
+            diff.extend(
+                new_mid[j..matched]
+                    .iter()
+                    .map(|line| LineDiff::Added((*line).to_string())),
+            );
+            diff.push(LineDiff::Unchanged(old_mid[i].to_string()));
+            j = matched + 1;
+        } else {
+            diff.push(LineDiff::Removed(old_mid[i].to_string()));
+        }
+    }
+    diff.extend(
+        new_mid[j..]
+            .iter()
+            .map(|line| LineDiff::Added((*line).to_string())),
+    );
+
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| LineDiff::Unchanged((*line).to_string())),
+    );
+    diff
+}
+
+/// A run of base lines `start..end` replaced by `lines` on one side.
+#[derive(PartialEq, Eq)]
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: &'a [&'a str],
+}
+
+/// Merges `ours` and `theirs`, which both derive from `base`.
+/// Returns None when the same lines were changed differently on both sides.
+#[must_use]
+pub fn merge_lines(base: &[&str], ours: &[&str], theirs: &[&str]) -> Option<Vec<String>> {
+    let mut hunks = diff_hunks(base, ours);
+    hunks.extend(diff_hunks(base, theirs));
+    hunks.sort_by_key(|hunk| (hunk.start, hunk.end));
+
+    let mut merged = Vec::new();
+    let mut pos = 0;
+    let mut last: Option<&Hunk> = None;
+
+    for hunk in &hunks {
+        if let Some(last) = last {
+            // Both sides made the identical change
+            if last == hunk {
+                continue;
+            }
+            let same_span = last.start == hunk.start && last.end == hunk.end;
+            if hunk.start < pos || same_span {
+                return None;
+            }
+        }
+        merged.extend(base[pos..hunk.start].iter().map(|line| (*line).to_string()));
+        merged.extend(hunk.lines.iter().map(|line| (*line).to_string()));
+        pos = hunk.end;
+        last = Some(hunk);
+    }
+    merged.extend(base[pos..].iter().map(|line| (*line).to_string()));
+
+    Some(merged)
+}
+
+/// Changes from `base` to `other`, found along a longest common subsequence.
+fn diff_hunks<'a>(base: &[&str], other: &'a [&'a str]) -> Vec<Hunk<'a>> {
+    let mut hunks = Vec::new();
+    let (mut prev_base, mut prev_other) = (0, 0);
+
+    let matched = match_lines(base, other)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, j)| j.map(|j| (i, j)));
+    for (i, j) in matched.chain([(base.len(), other.len())]) {
+        if i > prev_base || j > prev_other {
+            hunks.push(Hunk {
+                start: prev_base,
+                end: i,
+                lines: &other[prev_other..j],
+            });
+        }
+        (prev_base, prev_other) = (i + 1, j + 1);
+    }
+    hunks
+}
+
+/// For each line of `base`, the index of the line it matches in `other`, or
+/// None if it was changed or removed.
+fn match_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let (n, m) = (base.len(), other.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if base[i] == other[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
@@ -1,42 +1,94 @@
+mod archive;
+mod backup;
+mod check;
 mod context;
 mod date_parsing;
 mod entries;
 mod filter;
+mod git;
+mod headers;
+mod history;
+mod ics;
+mod index;
+mod lock;
+mod merge;
 mod persistence;
 mod project_registry;
+mod query;
+mod store;
 
 // Re-export context types and functions
 pub use context::{JournalContext, JournalSlot, detect_project_journal, find_git_root};
 
 // Re-export entry types
 pub use entries::{
-    Entry, EntryType, Line, RawEntry, RecurringPattern, SourceType, parse_lines,
-    parse_to_raw_entry, serialize_lines,
+    Entry, EntryType, Line, RawEntry, Recurrence, RecurrenceEnd, RecurringPattern, ScheduleKind,
+    ScheduledDate, SourceType, TaskState, join_entry_text, parse_lines, parse_to_raw_entry,
+    serialize_entry, serialize_lines, split_entry_text, subtask_progress, subtree_range,
+    toggle_with_subtasks,
 };
 
+// Re-export archive types and functions
+pub use archive::{
+    ArchiveOptions, archive_journal, archive_path, archives_for_day, journal_archives,
+};
+
+// Re-export backup types and functions
+pub use backup::{Backup, backup_journal, ensure_daily_backup, list_backups, restore_backup};
+
+// Re-export journal check types and functions
+pub use check::{JournalProblem, ProblemKind, check_journal, fix_journal};
+
+// Re-export git types and functions
+pub use git::{
+    Commit, branch_tag, commit_journal, commits_on, current_branch, repo_root, stamp_branch,
+};
+
+// Re-export ICS export functions
+pub use ics::{ics_path, journal_ics, update_ics_export};
+
+// Re-export undo history types and functions
+pub use history::{
+    DayChange, apply_day_changes, day_changes, read_undo_history, write_undo_history,
+};
+
+// Re-export diff and merge types and functions
+pub use merge::{LineDiff, diff_lines, merge_lines};
+
 // Re-export persistence functions and types
 pub use persistence::{
-    DayInfo, cycle_entry_type, delete_entry, extract_day_content, get_entry_content,
-    get_entry_type, load_day, load_day_lines, load_journal, mutate_entry, parse_day_header,
-    save_day, save_day_lines, save_journal, scan_days_in_range, toggle_entry_complete,
-    update_day_content, update_entry_content,
+    DayInfo, create_journal, cycle_entry_type, delete_entry, extract_day_content,
+    get_entry_content, get_entry_type, journal_day_file, journal_modified, load_day,
+    load_day_lines, load_days, load_journal, mutate_entry, reformat_journal, save_day,
+    save_day_lines, save_day_lines_merged, save_journal, scan_days_in_range, toggle_entry_complete,
+    update_day_content, update_day_lines, update_entry_content, update_entry_text,
 };
 
+// Re-export day header types and functions
+pub use headers::{DayHeaderFormat, reformat_day_headers};
+
+// Re-export journal layout types and functions
+pub use store::{DEFAULT_DAILY_NOTES_FORMAT, Journal, JournalLayout};
+
 // Re-export date parsing types and functions
 pub use date_parsing::{ParseContext, parse_date, parse_weekday};
 
 // Re-export filter types and functions
 pub use filter::{
-    FAVORITE_TAG_REGEX, Filter, FilterType, LAST_TRAILING_TAG_REGEX, RECURRING_REGEX,
-    SAVED_FILTER_REGEX, TAG_CHAR_CLASS, TAG_REGEX, TRAILING_TAGS_REGEX, add_done_date,
-    collect_filtered_entries, collect_journal_tags, collect_projected_entries_for_date,
-    create_tag_delete_regex, create_tag_match_regex, expand_favorite_tags, expand_saved_filters,
-    extract_recurring_pattern, extract_tags, is_done_on_date, normalize_entry_structure,
-    parse_filter_date, parse_filter_query, parse_natural_date, parse_recurring_pattern,
-    remove_done_date, restore_done_meta, scan_recurring_in_range, strip_done_meta,
-    strip_recurring_tags,
+    FAVORITE_TAG_REGEX, LAST_TRAILING_TAG_REGEX, PRIORITY_REGEX, RECURRING_REGEX,
+    SAVED_FILTER_REGEX, SCHEDULED_REGEX, TAG_CHAR_CLASS, TAG_REGEX, TRAILING_TAGS_REGEX,
+    add_done_date, collect_filtered_entries, collect_journal_tags,
+    collect_projected_entries_for_date, create_tag_delete_regex, create_tag_match_regex,
+    expand_favorite_tags, expand_saved_filters, extract_done_dates, extract_priority,
+    extract_recurrence, extract_recurring_pattern, extract_scheduled_date, extract_tags,
+    is_done_on_date, normalize_entry_structure, parse_filter_date, parse_natural_date,
+    parse_recurring_pattern, priority_rank, remove_done_date, restore_done_meta,
+    scan_recurring_in_range, strip_done_meta, strip_recurring_tags,
 };
 
+// Re-export filter query types and functions
+pub use query::{Filter, FilterError, FilterExpr, FilterTerm, FilterType, parse_filter_query};
+
 // Re-export project registry types
 pub use project_registry::{
     ProjectInfo, ProjectRegistry, get_registry_path, set_hide_from_registry,
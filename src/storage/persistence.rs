@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 use chrono::NaiveDate;
 
@@ -15,70 +17,145 @@ pub struct DayInfo {
     pub has_recurring: bool,
 }
 
-use super::entries::{EntryType, Line, RawEntry, parse_lines, serialize_lines};
-
-pub fn load_day_lines(date: NaiveDate, path: &Path) -> io::Result<Vec<Line>> {
-    let content = load_day(date, path)?;
+use super::entries::{
+    EntryType, Line, RawEntry, parse_lines, serialize_lines, toggle_with_subtasks,
+};
+use super::headers::{DayHeaderFormat, reformat_day_headers};
+use super::history::split_days;
+use super::index;
+use super::lock::with_journal_lock;
+use super::merge::merge_lines;
+use super::store::{Journal, journal_store};
+
+pub fn load_day_lines(date: NaiveDate, journal: &Journal) -> io::Result<Vec<Line>> {
+    let content = load_day(date, journal)?;
     Ok(parse_lines(&content))
 }
 
-pub fn save_day_lines(date: NaiveDate, path: &Path, lines: &[Line]) -> io::Result<()> {
+pub fn save_day_lines(date: NaiveDate, journal: &Journal, lines: &[Line]) -> io::Result<()> {
+    let content = serialize_lines(lines);
+    save_day(date, journal, &content)
+}
+
+/// Saves a day that was loaded as `base`, merging in any changes another
+/// process made to it on disk since. Returns the lines that were written, or
+/// None without writing when both sides changed the same line.
+pub fn save_day_lines_merged(
+    date: NaiveDate,
+    journal: &Journal,
+    base: &str,
+    lines: &[Line],
+) -> io::Result<Option<Vec<Line>>> {
     let content = serialize_lines(lines);
-    save_day(date, path, &content)
+    with_journal_lock(journal, || {
+        let on_disk = load_day(date, journal)?;
+        if on_disk.trim_end() == base.trim_end() || on_disk.trim_end() == content.trim_end() {
+            save_day(date, journal, &content)?;
+            return Ok(Some(lines.to_vec()));
+        }
+
+        let base_lines: Vec<&str> = base.lines().collect();
+        let our_lines: Vec<&str> = content.lines().collect();
+        let their_lines: Vec<&str> = on_disk.lines().collect();
+        let Some(merged) = merge_lines(&base_lines, &our_lines, &their_lines) else {
+            return Ok(None);
+        };
+
+        let merged = merged.join("\n");
+        save_day(date, journal, &merged)?;
+        Ok(Some(parse_lines(&merged)))
+    })
+}
+
+/// Loads a day's lines, applies `f`, and saves them back while holding the
+/// journal lock, so concurrent writers can't interleave.
+pub fn update_day_lines<F, R>(date: NaiveDate, journal: &Journal, f: F) -> io::Result<R>
+where
+    F: FnOnce(&mut Vec<Line>) -> R,
+{
+    with_journal_lock(journal, || {
+        let mut lines = load_day_lines(date, journal)?;
+        let result = f(&mut lines);
+        save_day_lines(date, journal, &lines)?;
+        Ok(result)
+    })
 }
 
 /// Helper to load, mutate an entry, and save in one operation.
 /// Returns the result of the mutation function if the entry exists.
 pub fn mutate_entry<F, R>(
     date: NaiveDate,
-    path: &Path,
+    journal: &Journal,
     line_index: usize,
     f: F,
 ) -> io::Result<Option<R>>
 where
     F: FnOnce(&mut RawEntry) -> R,
 {
-    let mut lines = load_day_lines(date, path)?;
-    let result = lines.get_mut(line_index).and_then(|line| match line {
-        Line::Entry(entry) => Some(f(entry)),
-        _ => None,
-    });
-    if result.is_some() {
-        save_day_lines(date, path, &lines)?;
-    }
-    Ok(result)
+    with_journal_lock(journal, || {
+        let mut lines = load_day_lines(date, journal)?;
+        let result = lines.get_mut(line_index).and_then(|line| match line {
+            Line::Entry(entry) => Some(f(entry)),
+            _ => None,
+        });
+        if result.is_some() {
+            save_day_lines(date, journal, &lines)?;
+        }
+        Ok(result)
+    })
 }
 
 /// Updates an entry's content at a specific line index for a given date.
 /// Returns Ok(true) if update succeeded, Ok(false) if no entry at that index.
 pub fn update_entry_content(
     date: NaiveDate,
-    path: &Path,
+    journal: &Journal,
     line_index: usize,
     content: String,
 ) -> io::Result<bool> {
-    mutate_entry(date, path, line_index, |entry| {
+    mutate_entry(date, journal, line_index, |entry| {
         entry.content = content;
     })
     .map(|opt| opt.is_some())
 }
 
-/// Toggles the completion status of a task at a specific line index.
-pub fn toggle_entry_complete(date: NaiveDate, path: &Path, line_index: usize) -> io::Result<()> {
-    mutate_entry(date, path, line_index, |entry| {
-        entry.toggle_complete();
-    })?;
-    Ok(())
+/// Replaces an entry's content and body from newline-separated edit text.
+/// Returns Ok(true) if update succeeded, Ok(false) if no entry at that index.
+pub fn update_entry_text(
+    date: NaiveDate,
+    journal: &Journal,
+    line_index: usize,
+    text: &str,
+) -> io::Result<bool> {
+    mutate_entry(date, journal, line_index, |entry| entry.set_text(text)).map(|opt| opt.is_some())
+}
+
+/// Toggles the completion status of a task at a specific line index,
+/// carrying its nested tasks along. See `toggle_with_subtasks` for `completed_on`.
+pub fn toggle_entry_complete(
+    date: NaiveDate,
+    journal: &Journal,
+    line_index: usize,
+    completed_on: Option<NaiveDate>,
+) -> io::Result<()> {
+    with_journal_lock(journal, || {
+        let mut lines = load_day_lines(date, journal)?;
+        if matches!(lines.get(line_index), Some(Line::Entry(_))) {
+            toggle_with_subtasks(&mut lines, line_index, completed_on);
+            save_day_lines(date, journal, &lines)?;
+        }
+        Ok(())
+    })
 }
 
 /// Cycles the entry type (Task -> Note -> Event -> Task) at a specific line index.
 /// Returns the new entry type if successful.
 pub fn cycle_entry_type(
     date: NaiveDate,
-    path: &Path,
+    journal: &Journal,
     line_index: usize,
 ) -> io::Result<Option<EntryType>> {
-    mutate_entry(date, path, line_index, |entry| {
+    mutate_entry(date, journal, line_index, |entry| {
         entry.entry_type = entry.entry_type.cycle();
         entry.entry_type.clone()
     })
@@ -87,8 +164,8 @@ pub fn cycle_entry_type(
 /// Gets the entry type at a specific line index for a given date.
 /// Returns the default task type if the entry doesn't exist.
 #[must_use]
-pub fn get_entry_type(date: NaiveDate, path: &Path, line_index: usize) -> EntryType {
-    load_day_lines(date, path)
+pub fn get_entry_type(date: NaiveDate, journal: &Journal, line_index: usize) -> EntryType {
+    load_day_lines(date, journal)
         .ok()
         .and_then(|lines| {
             lines.get(line_index).and_then(|line| {
@@ -99,14 +176,14 @@ pub fn get_entry_type(date: NaiveDate, path: &Path, line_index: usize) -> EntryT
                 }
             })
         })
-        .unwrap_or(EntryType::Task { completed: false })
+        .unwrap_or(EntryType::OPEN_TASK)
 }
 
 /// Gets the entry content at a specific line index for a given date.
 /// Returns None if the entry doesn't exist.
 #[must_use]
-pub fn get_entry_content(date: NaiveDate, path: &Path, line_index: usize) -> Option<String> {
-    load_day_lines(date, path).ok().and_then(|lines| {
+pub fn get_entry_content(date: NaiveDate, journal: &Journal, line_index: usize) -> Option<String> {
+    load_day_lines(date, journal).ok().and_then(|lines| {
         lines.get(line_index).and_then(|line| {
             if let Line::Entry(entry) = line {
                 Some(entry.content.clone())
@@ -118,43 +195,101 @@ pub fn get_entry_content(date: NaiveDate, path: &Path, line_index: usize) -> Opt
 }
 
 /// Deletes an entry at a specific line index for a given date.
-pub fn delete_entry(date: NaiveDate, path: &Path, line_index: usize) -> io::Result<()> {
-    let mut lines = load_day_lines(date, path)?;
-    if line_index < lines.len() {
-        lines.remove(line_index);
-    }
-    save_day_lines(date, path, &lines)
+pub fn delete_entry(date: NaiveDate, journal: &Journal, line_index: usize) -> io::Result<()> {
+    update_day_lines(date, journal, |lines| {
+        if line_index < lines.len() {
+            lines.remove(line_index);
+        }
+    })
 }
 
-fn day_header(date: NaiveDate) -> String {
-    format!("# {}", date.format("%Y/%m/%d"))
+/// Returns the journal's content. Served from the journal index when the file
+/// hasn't changed since it was last read. Directory journals are read as one
+/// document of day sections in date order.
+pub fn load_journal(journal: &Journal) -> io::Result<String> {
+    journal_store(journal).load_journal()
 }
 
-pub fn load_journal(path: &Path) -> io::Result<String> {
-    if path.exists() {
-        fs::read_to_string(path)
-    } else {
-        Ok(String::new())
-    }
+/// Every day in the journal with its content, in order, as `load_day` reads
+/// it. Unlike scanning `load_journal` for day headers, this also finds the
+/// days of daily notes.
+pub fn load_days(journal: &Journal) -> io::Result<Vec<(NaiveDate, String)>> {
+    let store = journal_store(journal);
+    Ok(store.split_journal(&store.load_journal()?))
 }
 
-pub fn save_journal(path: &Path, content: &str) -> io::Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
+/// Replaces the whole journal. The write is atomic: readers and crashes see
+/// either the old or the new file, never a partial one. Directory journals
+/// only rewrite the files of days that changed.
+pub fn save_journal(journal: &Journal, content: &str) -> io::Result<()> {
+    with_journal_lock(journal, || journal_store(journal).save_journal(content))
+}
+
+/// When the journal last changed on disk, for noticing external edits.
+/// None if the journal doesn't exist.
+#[must_use]
+pub fn journal_modified(journal: &Journal) -> Option<SystemTime> {
+    journal_store(journal).modified()
+}
 
-    fs::write(path, content)
+/// The file a day is stored in: the journal itself, or one of the files in a
+/// directory journal.
+#[must_use]
+pub fn journal_day_file(date: NaiveDate, journal: &Journal) -> PathBuf {
+    journal_store(journal).day_file(date)
 }
 
-pub fn extract_day_content(journal: &str, date: NaiveDate) -> String {
-    let header = day_header(date);
+/// Creates an empty journal, in its configured layout, if there is none.
+pub fn create_journal(journal: &Journal) -> io::Result<()> {
+    journal_store(journal).create()
+}
 
-    let Some(start_idx) = journal.find(&header) else {
+/// Rewrites every day header in the journal with `format` and returns how many
+/// changed. Nothing is written unless every file still reads back, with the
+/// journal's own day headers, as the same days and content as before. Daily notes have no day headers, so are left alone.
+pub fn reformat_journal(journal: &Journal, format: &DayHeaderFormat) -> io::Result<usize> {
+    with_journal_lock(journal, || {
+        let store = journal_store(journal);
+        if !store.has_day_headers() {
+            return Ok(0);
+        }
+        let mut rewrites = Vec::new();
+        let mut changed = 0;
+        for file in store.files()? {
+            let before = fs::read_to_string(&file)?;
+            let headers = journal.day_header();
+            let after = reformat_day_headers(&before, headers, format);
+            if after == before {
+                continue;
+            }
+            if split_days(&after, headers) != split_days(&before, headers) {
+                return Err(io::Error::other(format!(
+                    "Reformatting {} would change its days",
+                    file.display()
+                )));
+            }
+            changed += before
+                .lines()
+                .zip(after.lines())
+                .filter(|(old, new)| old != new)
+                .count();
+            rewrites.push((file, after));
+        }
+
+        for (file, content) in rewrites {
+            index::save_journal(&file, journal.day_header(), &content)?;
+        }
+        Ok(changed)
+    })
+}
+
+/// A day's content in a journal with default day headers.
+pub fn extract_day_content(journal: &str, date: NaiveDate) -> String {
+    let Some(header) = find_day_header(journal, date) else {
         return String::new();
     };
 
-    let content_start = start_idx + header.len();
-    let after_header = &journal[content_start..];
+    let after_header = &journal[header.end..];
     let after_header = after_header.strip_prefix('\n').unwrap_or(after_header);
     let end_idx = find_next_day_header(after_header);
 
@@ -164,19 +299,21 @@ pub fn extract_day_content(journal: &str, date: NaiveDate) -> String {
     }
 }
 
-pub fn parse_day_header(line: &str) -> Option<NaiveDate> {
-    if !line.starts_with("# ") {
-        return None;
-    }
-    let rest = &line[2..];
-    if rest.len() < 10 {
-        return None;
+/// Byte range of the first header line for `date`, without its newline.
+fn find_day_header(journal: &str, date: NaiveDate) -> Option<Range<usize>> {
+    let mut offset = 0;
+    for line in journal.split_inclusive('\n') {
+        let text = line.trim_end_matches('\n');
+        if DayHeaderFormat::default().parse(text) == Some(date) {
+            return Some(offset..offset + text.len());
+        }
+        offset += line.len();
     }
-    NaiveDate::parse_from_str(&rest[..10], "%Y/%m/%d").ok()
+    None
 }
 
 fn is_day_header(line: &str) -> bool {
-    parse_day_header(line).is_some()
+    DayHeaderFormat::default().parse(line).is_some()
 }
 
 fn find_next_day_header(content: &str) -> Option<usize> {
@@ -203,36 +340,54 @@ fn find_next_day_header(content: &str) -> Option<usize> {
     None
 }
 
+/// Replaces a day's content, writing any new header in the default format.
 pub fn update_day_content(journal: &str, date: NaiveDate, new_content: &str) -> String {
-    let header = day_header(date);
+    let header = DayHeaderFormat::default().header(date);
+    let span = find_day_header(journal, date)
+        .map(|existing| existing.start..day_section_end(journal, existing.end));
+    let insert_pos = find_insertion_point(journal, date);
+    splice_day(journal, &header, new_content, span, insert_pos).0
+}
+
+/// Replaces the day section at `span`, keeping its header line, or inserts a
+/// new day under `header` at `insert_pos` (appending when None). Returns the
+/// new journal and the offset of the day's header in it, if the day still exists.
+pub(super) fn splice_day(
+    journal: &str,
+    header: &str,
+    new_content: &str,
+    span: Option<Range<usize>>,
+    insert_pos: Option<usize>,
+) -> (String, Option<usize>) {
     let content_is_empty = new_content.trim().is_empty();
 
-    if let Some(start_idx) = journal.find(&header) {
-        let (before, after) = split_around_day(journal, start_idx, &header);
-        if content_is_empty {
-            remove_day(before, after)
-        } else {
-            replace_day(before, &header, new_content, after)
+    match span {
+        Some(span) => {
+            let (before, after) = (&journal[..span.start], &journal[span.end..]);
+            let existing_header = journal[span].lines().next().unwrap_or(header);
+            if content_is_empty {
+                (remove_day(before, after), None)
+            } else {
+                (
+                    replace_day(before, existing_header, new_content, after),
+                    Some(before.len()),
+                )
+            }
         }
-    } else if content_is_empty {
-        journal.to_string()
-    } else {
-        insert_new_day(journal, date, &header, new_content)
+        None if content_is_empty => (journal.to_string(), None),
+        None => insert_new_day(journal, header, new_content, insert_pos),
     }
 }
 
-fn split_around_day<'a>(journal: &'a str, start_idx: usize, header: &str) -> (&'a str, &'a str) {
-    let before = &journal[..start_idx];
-    let content_start = start_idx + header.len();
-    let after_header = &journal[content_start..];
-    let after_header = after_header.strip_prefix('\n').unwrap_or(after_header);
-
-    let after = match find_next_day_header(after_header) {
-        Some(idx) => &after_header[idx..],
-        None => "",
-    };
+fn day_section_end(journal: &str, header_end: usize) -> usize {
+    let after_header = &journal[header_end..];
+    let stripped = after_header.strip_prefix('\n').unwrap_or(after_header);
+    let stripped_start = journal.len() - stripped.len();
 
-    (before, after)
+    match find_next_day_header(stripped) {
+        Some(idx) => stripped_start + idx,
+        None => journal.len(),
+    }
 }
 
 fn remove_day(before: &str, after: &str) -> String {
@@ -255,44 +410,54 @@ fn replace_day(before: &str, header: &str, content: &str, after: &str) -> String
         + "\n"
 }
 
-fn insert_new_day(journal: &str, date: NaiveDate, header: &str, content: &str) -> String {
+fn insert_new_day(
+    journal: &str,
+    header: &str,
+    content: &str,
+    insert_pos: Option<usize>,
+) -> (String, Option<usize>) {
     let new_day = format!("{}\n{}\n", header, content.trim_end());
 
-    let insert_pos = find_insertion_point(journal, date);
-
     if let Some(pos) = insert_pos {
         let before = journal[..pos].trim_end();
         let after = &journal[pos..];
         if before.is_empty() {
-            format!("{}\n{}", new_day.trim_end(), after.trim_start())
+            (
+                format!("{}\n{}", new_day.trim_end(), after.trim_start())
+                    .trim_end()
+                    .to_string()
+                    + "\n",
+                Some(0),
+            )
+        } else {
+            (
+                format!(
+                    "{}\n\n{}\n{}",
+                    before,
+                    new_day.trim_end(),
+                    after.trim_start()
+                )
                 .trim_end()
                 .to_string()
-                + "\n"
-        } else {
-            format!(
-                "{}\n\n{}\n{}",
-                before,
-                new_day.trim_end(),
-                after.trim_start()
+                    + "\n",
+                Some(before.len() + 2),
             )
-            .trim_end()
-            .to_string()
-                + "\n"
         }
     } else {
         let mut result = journal.trim_end().to_string();
         if !result.is_empty() {
             result.push_str("\n\n");
         }
+        let header_offset = result.len();
         result.push_str(new_day.trim_end());
         result.push('\n');
-        result
+        (result, Some(header_offset))
     }
 }
 
 fn find_insertion_point(journal: &str, date: NaiveDate) -> Option<usize> {
     for line in journal.lines() {
-        if let Some(existing_date) = parse_day_header(line)
+        if let Some(existing_date) = DayHeaderFormat::default().parse(line)
             && existing_date > date
         {
             return journal.find(line);
@@ -301,15 +466,12 @@ fn find_insertion_point(journal: &str, date: NaiveDate) -> Option<usize> {
     None
 }
 
-pub fn load_day(date: NaiveDate, path: &Path) -> io::Result<String> {
-    let journal = load_journal(path)?;
-    Ok(extract_day_content(&journal, date))
+pub fn load_day(date: NaiveDate, journal: &Journal) -> io::Result<String> {
+    journal_store(journal).load_day(date)
 }
 
-pub fn save_day(date: NaiveDate, path: &Path, content: &str) -> io::Result<()> {
-    let journal = load_journal(path)?;
-    let updated = update_day_content(&journal, date, content);
-    save_journal(path, &updated)
+pub fn save_day(date: NaiveDate, journal: &Journal, content: &str) -> io::Result<()> {
+    with_journal_lock(journal, || journal_store(journal).save_day(date, content))
 }
 
 /// Scans journal for day info within a date range (inclusive).
@@ -317,53 +479,30 @@ pub fn save_day(date: NaiveDate, path: &Path, content: &str) -> io::Result<()> {
 pub fn scan_days_in_range(
     start: NaiveDate,
     end: NaiveDate,
-    path: &Path,
+    journal: &Journal,
 ) -> io::Result<HashMap<NaiveDate, DayInfo>> {
-    let journal = load_journal(path)?;
-    let mut result = HashMap::new();
-    let mut current_date: Option<NaiveDate> = None;
-    let mut current_info = DayInfo::default();
+    let days = journal_store(journal).days_in_range(start, end)?;
+    Ok(days
+        .into_iter()
+        .filter_map(|(date, content)| {
+            let info = day_info(&content);
+            info.has_entries.then_some((date, info))
+        })
+        .collect())
+}
 
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            // Save previous day if in range
-            if let Some(prev_date) = current_date
-                && prev_date >= start
-                && prev_date <= end
-                && current_info.has_entries
-            {
-                result.insert(prev_date, current_info);
-            }
-            current_date = Some(date);
-            current_info = DayInfo::default();
+fn day_info(content: &str) -> DayInfo {
+    let mut info = DayInfo::default();
+    for line in parse_lines(content) {
+        let Line::Entry(entry) = line else {
             continue;
-        }
-
-        if current_date.is_some() {
-            let trimmed = line.trim_start();
-            if trimmed.starts_with("- [ ] ") {
-                current_info.has_entries = true;
-                current_info.has_incomplete_tasks = true;
-            } else if trimmed.starts_with("* ") {
-                current_info.has_entries = true;
-                current_info.has_events = true;
-            } else if trimmed.starts_with("- [x] ")
-                || trimmed.starts_with("- [X] ")
-                || (trimmed.starts_with("- ") && !trimmed.starts_with("- ["))
-            {
-                current_info.has_entries = true;
-            }
+        };
+        info.has_entries = true;
+        match entry.entry_type {
+            EntryType::Event => info.has_events = true,
+            entry_type if entry_type.is_open_task() => info.has_incomplete_tasks = true,
+            _ => {}
         }
     }
-
-    // Save last day if in range
-    if let Some(date) = current_date
-        && date >= start
-        && date <= end
-        && current_info.has_entries
-    {
-        result.insert(date, current_info);
-    }
-
-    Ok(result)
+    info
 }
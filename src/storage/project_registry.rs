@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use super::store::Journal;
 use crate::config::get_config_dir;
 
 /// Entry in the registry file - stores path and optional calendar visibility
@@ -35,15 +36,15 @@ pub struct ProjectInfo {
 }
 
 impl ProjectInfo {
-    /// Get journal path, checking config for custom location.
+    /// Get the project's journal, checking config for custom location.
     #[must_use]
-    pub fn journal_path(&self) -> PathBuf {
+    pub fn journal_path(&self) -> Journal {
         use crate::config::Config;
 
         if let Ok(config_load) = Config::load_merged_from(&self.root) {
             config_load.config.get_project_journal_path(&self.root)
         } else {
-            self.path.join("journal.md")
+            Journal::new(self.path.join("journal.md"))
         }
     }
 
@@ -215,10 +216,7 @@ fn normalize_to_corner_dir(path: &Path) -> PathBuf {
     path.to_path_buf()
 }
 
-fn resolve_project_info(
-    corner_path: &Path,
-    calendars: Option<Vec<String>>,
-) -> Option<ProjectInfo> {
+fn resolve_project_info(corner_path: &Path, calendars: Option<Vec<String>>) -> Option<ProjectInfo> {
     use crate::config::Config;
 
     if corner_path.file_name()?.to_str()? != ".corner" {
@@ -229,7 +227,7 @@ fn resolve_project_info(
     // Check config for custom journal location
     let journal_path = Config::load_merged_from(root)
         .map(|c| c.config.get_project_journal_path(root))
-        .unwrap_or_else(|_| corner_path.join("journal.md"));
+        .unwrap_or_else(|_| Journal::new(corner_path.join("journal.md")));
     let available = journal_path.exists();
 
     let (name, id) = derive_identity(root);
@@ -0,0 +1,591 @@
+//! The filter query language.
+//!
+//! Every filter token (`#tag`, `!tasks`, `d7..`, `@due`, plain words) is a
+//! leaf of an expression. Leaves side by side must all match; `OR` (or `|`)
+//! matches either side, `NOT` (or a leading `-`) negates, parentheses group,
+//! and `"quoted phrases"` search for their words together. `NOT` binds
+//! tightest, then AND, then OR.
+
+use std::fmt;
+use std::io;
+
+use chrono::NaiveDate;
+
+use super::entries::{EntryType, RawEntry, ScheduleKind, ScheduledDate, TaskState};
+use super::filter::{
+    RECURRING_REGEX, extract_done_dates, extract_priority, extract_scheduled_date, extract_tags,
+    is_spread_syntax, parse_spread_date,
+};
+use super::git::branch_tag;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterType {
+    Task,
+    Note,
+    Event,
+}
+
+/// States `!tasks` matches.
+const OPEN_STATES: [TaskState; 3] = [TaskState::Open, TaskState::InProgress, TaskState::Blocked];
+
+/// A single filter token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterTerm {
+    /// `!tasks`, `!completed`, `!notes`...: entries of the type, and for tasks
+    /// one of `states`, or any state when empty
+    Type {
+        entry_type: FilterType,
+        states: Vec<TaskState>,
+    },
+    /// `!p1` through `!p3`
+    Priority(u8),
+    /// `#tag`, and `branch:NAME` as the branch's tag
+    Tag(String),
+    /// A word or quoted phrase, found case-insensitively in the entry or its body
+    Text(String),
+    /// A date range: the day written, or the scheduled date when the query
+    /// has `@due`, `@scheduled` or `@overdue`
+    Date {
+        before: Option<NaiveDate>,
+        after: Option<NaiveDate>,
+    },
+    /// `done:` with a date range: completed tasks and recurring entries with a
+    /// done date in it
+    Done {
+        before: Option<NaiveDate>,
+        after: Option<NaiveDate>,
+    },
+    /// `@recurring`; recurring entries are left out of queries without it
+    Recurring,
+    /// `@due`
+    Due,
+    /// `@scheduled`
+    Scheduled,
+    /// `@overdue`: open tasks past their due date
+    Overdue,
+    /// `@by-priority` orders the results and matches every entry
+    ByPriority,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterExpr {
+    Term(FilterTerm),
+    Not(Box<FilterExpr>),
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+}
+
+/// Why a query couldn't be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    pub message: String,
+    /// Character offset into the query
+    pub position: usize,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl From<FilterError> for io::Error {
+    fn from(error: FilterError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// A parsed filter query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// None for an empty query, which matches every entry
+    pub expr: Option<FilterExpr>,
+}
+
+impl Filter {
+    /// Whether date ranges apply to the scheduled date instead of the day header.
+    #[must_use]
+    pub fn targets_scheduled_date(&self) -> bool {
+        self.has_term(&|term| {
+            matches!(
+                term,
+                FilterTerm::Due | FilterTerm::Scheduled | FilterTerm::Overdue
+            )
+        })
+    }
+
+    /// Whether recurring entries are considered at all.
+    #[must_use]
+    pub fn includes_recurring(&self) -> bool {
+        self.has_term(&|term| *term == FilterTerm::Recurring)
+    }
+
+    /// Whether results are ordered by priority, then date.
+    #[must_use]
+    pub fn by_priority(&self) -> bool {
+        self.has_term(&|term| *term == FilterTerm::ByPriority)
+    }
+
+    /// Whether results are ordered by completion date.
+    #[must_use]
+    pub fn by_completion(&self) -> bool {
+        self.has_term(&|term| matches!(term, FilterTerm::Done { .. }))
+    }
+
+    /// `(after, before)` bounds every match's day header falls within, from
+    /// the date ranges all matches need. Unbounded when ranges target
+    /// scheduled dates or sit under OR or NOT.
+    #[must_use]
+    pub fn day_bounds(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        if self.targets_scheduled_date() {
+            return (None, None);
+        }
+        let required: Vec<&FilterExpr> = match &self.expr {
+            Some(FilterExpr::And(items)) => items.iter().collect(),
+            Some(expr) => vec![expr],
+            None => Vec::new(),
+        };
+        let mut bounds: (Option<NaiveDate>, Option<NaiveDate>) = (None, None);
+        for expr in required {
+            if let FilterExpr::Term(FilterTerm::Date { before, after }) = expr {
+                bounds.0 = bounds.0.max(*after);
+                bounds.1 = match (bounds.1, *before) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+        bounds
+    }
+
+    /// Whether the entry, written on `date`, matches.
+    #[must_use]
+    pub fn matches(&self, entry: &RawEntry, date: NaiveDate, today: NaiveDate) -> bool {
+        let is_recurring = RECURRING_REGEX.is_match(&entry.content);
+        if is_recurring && !self.includes_recurring() {
+            return false;
+        }
+        let Some(expr) = &self.expr else {
+            return true;
+        };
+        let candidate = Candidate {
+            entry,
+            date,
+            today,
+            is_recurring,
+            scheduled: extract_scheduled_date(&entry.content, date),
+            targets_scheduled: self.targets_scheduled_date(),
+            tags: extract_tags(&entry.content),
+            text: entry.text().to_lowercase(),
+        };
+        expr.matches(&candidate)
+    }
+
+    fn has_term(&self, check: &dyn Fn(&FilterTerm) -> bool) -> bool {
+        self.expr.as_ref().is_some_and(|expr| expr.has_term(check))
+    }
+}
+
+impl FilterExpr {
+    fn has_term(&self, check: &dyn Fn(&FilterTerm) -> bool) -> bool {
+        match self {
+            Self::Term(term) => check(term),
+            Self::Not(inner) => inner.has_term(check),
+            Self::And(items) | Self::Or(items) => items.iter().any(|item| item.has_term(check)),
+        }
+    }
+
+    fn matches(&self, candidate: &Candidate) -> bool {
+        match self {
+            Self::Term(term) => term.matches(candidate),
+            Self::Not(inner) => !inner.matches(candidate),
+            Self::Or(items) => items.iter().any(|item| item.matches(candidate)),
+            Self::And(items) => {
+                // Entry types and priorities side by side match any of them,
+                // so `!tasks !notes` shows both
+                let mut types = None;
+                let mut priorities = None;
+                for item in items {
+                    match item {
+                        Self::Term(term @ FilterTerm::Type { .. }) => {
+                            types = Some(types == Some(true) || term.matches(candidate));
+                        }
+                        Self::Term(term @ FilterTerm::Priority(_)) => {
+                            priorities = Some(priorities == Some(true) || term.matches(candidate));
+                        }
+                        item if !item.matches(candidate) => return false,
+                        _ => {}
+                    }
+                }
+                types.unwrap_or(true) && priorities.unwrap_or(true)
+            }
+        }
+    }
+}
+
+/// An entry being matched, with what the terms look at worked out once.
+struct Candidate<'a> {
+    entry: &'a RawEntry,
+    date: NaiveDate,
+    today: NaiveDate,
+    is_recurring: bool,
+    scheduled: Option<ScheduledDate>,
+    targets_scheduled: bool,
+    tags: Vec<String>,
+    /// Lowercased text and body
+    text: String,
+}
+
+impl FilterTerm {
+    fn matches(&self, candidate: &Candidate) -> bool {
+        let entry = candidate.entry;
+        match self {
+            Self::Type { entry_type, states } => {
+                let type_matches = match entry.entry_type {
+                    EntryType::Task { .. } => *entry_type == FilterType::Task,
+                    EntryType::Note => *entry_type == FilterType::Note,
+                    EntryType::Event => *entry_type == FilterType::Event,
+                };
+                let state_matches = match entry.entry_type {
+                    EntryType::Task { state } => states.is_empty() || states.contains(&state),
+                    _ => true,
+                };
+                type_matches && state_matches
+            }
+            Self::Priority(priority) => extract_priority(&entry.content) == Some(*priority),
+            Self::Tag(tag) => candidate.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Self::Text(text) => candidate.text.contains(&text.to_lowercase()),
+            Self::Date { before, after } => {
+                let date = match (&candidate.scheduled, candidate.targets_scheduled) {
+                    (Some(scheduled), true) => scheduled.date,
+                    _ => candidate.date,
+                };
+                in_range(date, *before, *after)
+            }
+            Self::Done { before, after } => {
+                let has_done_dates =
+                    entry.entry_type == EntryType::DONE_TASK || candidate.is_recurring;
+                has_done_dates
+                    && extract_done_dates(&entry.content)
+                        .into_iter()
+                        .any(|date| in_range(date, *before, *after))
+            }
+            Self::Recurring => candidate.is_recurring,
+            Self::Due => candidate
+                .scheduled
+                .as_ref()
+                .is_some_and(|scheduled| scheduled.kind == ScheduleKind::Due),
+            Self::Scheduled => candidate
+                .scheduled
+                .as_ref()
+                .is_some_and(|scheduled| scheduled.kind == ScheduleKind::On),
+            Self::Overdue => candidate.scheduled.as_ref().is_some_and(|scheduled| {
+                scheduled.kind == ScheduleKind::Due
+                    && entry.entry_type.is_open_task()
+                    && scheduled.date < candidate.today
+            }),
+            Self::ByPriority => true,
+        }
+    }
+}
+
+fn in_range(date: NaiveDate, before: Option<NaiveDate>, after: Option<NaiveDate>) -> bool {
+    before.is_none_or(|before| date <= before) && after.is_none_or(|after| date >= after)
+}
+
+/// Parses a filter query. Saved filters must already be expanded.
+pub fn parse_filter_query(query: &str) -> Result<Filter, FilterError> {
+    let today = chrono::Local::now().date_naive();
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Ok(Filter::default());
+    }
+    let mut parser = Parser {
+        query,
+        tokens,
+        next: 0,
+        today,
+    };
+    let expr = parser.parse_or()?;
+    if let Some((_, start)) = parser.peek() {
+        return Err(parser.error("Unexpected )", start));
+    }
+    Ok(Filter { expr: Some(expr) })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Open,
+    Close,
+    Or,
+    And,
+    Not,
+    Word(&'a str),
+    Phrase(&'a str),
+}
+
+/// Splits the query into tokens with their byte offsets.
+fn tokenize(query: &str) -> Result<Vec<(Token<'_>, usize)>, FilterError> {
+    let error = |message: &str, start: usize| FilterError {
+        message: message.to_string(),
+        position: query[..start].chars().count(),
+    };
+    let ends_word = |c: char| c.is_whitespace() || matches!(c, '(' | ')' | '|');
+
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '|' => Token::Or,
+            '"' => {
+                let Some(len) = query[start + 1..].find('"') else {
+                    return Err(error("Missing closing quote", start));
+                };
+                let phrase = &query[start + 1..start + 1 + len];
+                if phrase.trim().is_empty() {
+                    return Err(error("Empty phrase", start));
+                }
+                while chars.next_if(|&(i, _)| i <= start + 1 + len).is_some() {}
+                Token::Phrase(phrase)
+            }
+            '-' => {
+                if chars
+                    .peek()
+                    .is_none_or(|&(_, next)| ends_word(next) && next != '(')
+                {
+                    return Err(error("Nothing to negate after -", start));
+                }
+                Token::Not
+            }
+            _ => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, next)) = chars.next_if(|&(_, next)| !ends_word(next)) {
+                    end = i + next.len_utf8();
+                }
+                match &query[start..end] {
+                    "OR" => Token::Or,
+                    "AND" => Token::And,
+                    "NOT" => Token::Not,
+                    word => Token::Word(word),
+                }
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<(Token<'a>, usize)>,
+    next: usize,
+    today: NaiveDate,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<(Token<'a>, usize)> {
+        self.tokens.get(self.next).cloned()
+    }
+
+    fn advance(&mut self) -> Option<(Token<'a>, usize)> {
+        let token = self.peek();
+        self.next += 1;
+        token
+    }
+
+    fn error(&self, message: impl Into<String>, start: usize) -> FilterError {
+        FilterError {
+            message: message.into(),
+            position: self.query[..start].chars().count(),
+        }
+    }
+
+    fn end(&self) -> usize {
+        self.query.len()
+    }
+
+    /// `and (OR and)*`
+    fn parse_or(&mut self) -> Result<FilterExpr, FilterError> {
+        let mut items = vec![self.parse_and()?];
+        while let Some((Token::Or, start)) = self.peek() {
+            self.advance();
+            if matches!(self.peek(), None | Some((Token::Close | Token::Or, _))) {
+                return Err(self.error("OR needs a filter on each side", start));
+            }
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            FilterExpr::Or(items)
+        })
+    }
+
+    /// `unary (AND? unary)*`
+    fn parse_and(&mut self) -> Result<FilterExpr, FilterError> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                None | Some((Token::Close | Token::Or, _)) => break,
+                Some((Token::And, start)) => {
+                    self.advance();
+                    if matches!(self.peek(), None | Some((Token::Close | Token::Or, _))) {
+                        return Err(self.error("AND needs a filter on each side", start));
+                    }
+                }
+                Some(_) => {}
+            }
+            items.push(self.parse_unary()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            FilterExpr::And(items)
+        })
+    }
+
+    /// `(NOT | -) unary | primary`
+    fn parse_unary(&mut self) -> Result<FilterExpr, FilterError> {
+        let Some((Token::Not, start)) = self.peek() else {
+            return self.parse_primary();
+        };
+        self.advance();
+        if matches!(
+            self.peek(),
+            None | Some((Token::Close | Token::Or | Token::And, _))
+        ) {
+            return Err(self.error("Nothing to negate", start));
+        }
+        match self.parse_unary()? {
+            FilterExpr::Term(FilterTerm::ByPriority) => {
+                Err(self.error("@by-priority can't be negated", start))
+            }
+            // `-!tasks` has always left out every task, not just open ones
+            FilterExpr::Term(FilterTerm::Type {
+                entry_type: FilterType::Task,
+                states,
+            }) if states == OPEN_STATES => Ok(FilterExpr::Not(Box::new(FilterExpr::Term(
+                FilterTerm::Type {
+                    entry_type: FilterType::Task,
+                    states: Vec::new(),
+                },
+            )))),
+            expr => Ok(FilterExpr::Not(Box::new(expr))),
+        }
+    }
+
+    /// `( or ) | word | "phrase"`
+    fn parse_primary(&mut self) -> Result<FilterExpr, FilterError> {
+        let Some((token, start)) = self.advance() else {
+            return Err(self.error("Expected a filter", self.end()));
+        };
+        match token {
+            Token::Open => {
+                if let Some((Token::Close, _)) = self.peek() {
+                    return Err(self.error("Empty group", start));
+                }
+                let expr = self.parse_or()?;
+                match self.advance() {
+                    Some((Token::Close, _)) => Ok(expr),
+                    _ => Err(self.error("Missing ) for this group", start)),
+                }
+            }
+            Token::Close => Err(self.error("Unexpected )", start)),
+            Token::Or => Err(self.error("OR needs a filter on each side", start)),
+            Token::And => Err(self.error("AND needs a filter on each side", start)),
+            Token::Not => Err(self.error("Nothing to negate", start)),
+            Token::Phrase(phrase) => Ok(FilterExpr::Term(FilterTerm::Text(phrase.to_string()))),
+            Token::Word(word) => self.parse_word(word, start).map(FilterExpr::Term),
+        }
+    }
+
+    fn parse_word(&self, word: &str, start: usize) -> Result<FilterTerm, FilterError> {
+        // Spread date syntax: DATE, DATE.., ..DATE, DATE..DATE
+        // Dates default to past (d7 = 7 days ago, mon = last Monday)
+        // Append + for explicit future (d7+ = 7 days from now, mon+ = next Monday)
+        if is_spread_syntax(word) {
+            let (before, after) = parse_spread_date(word, self.today)
+                .ok_or_else(|| self.error(format!("Invalid date range {word}"), start))?;
+            return Ok(FilterTerm::Date { before, after });
+        }
+
+        // Completion date range: done:DATE, done:DATE.., done:..DATE, done:DATE..DATE
+        if let Some(range) = word.strip_prefix("done:") {
+            let (before, after) = parse_spread_date(range, self.today)
+                .ok_or_else(|| self.error(format!("Invalid completion range {word}"), start))?;
+            return Ok(FilterTerm::Done { before, after });
+        }
+
+        // Branch filter: branch:NAME matches entries tagged with the branch
+        if let Some(branch) = word.strip_prefix("branch:") {
+            if branch.is_empty() {
+                return Err(self.error("branch: needs a branch name", start));
+            }
+            return Ok(FilterTerm::Tag(branch_tag(branch)));
+        }
+
+        if let Some(pattern) = word.strip_prefix('@') {
+            return match pattern {
+                "recurring" => Ok(FilterTerm::Recurring),
+                "due" => Ok(FilterTerm::Due),
+                "scheduled" => Ok(FilterTerm::Scheduled),
+                "overdue" => Ok(FilterTerm::Overdue),
+                "by-priority" => Ok(FilterTerm::ByPriority),
+                _ => Err(self.error(format!("Unknown filter {word}"), start)),
+            };
+        }
+
+        if let Some(name) = word.strip_prefix('$') {
+            return Err(self.error(format!("Unknown saved filter ${name}"), start));
+        }
+
+        if let Some(tag) = word.strip_prefix('#') {
+            if tag.is_empty() {
+                return Err(self.error("# needs a tag name", start));
+            }
+            return Ok(FilterTerm::Tag(tag.to_string()));
+        }
+
+        if let Some(type_str) = word.strip_prefix('!') {
+            return parse_type(type_str)
+                .ok_or_else(|| self.error(format!("Unknown entry type {word}"), start));
+        }
+
+        Ok(FilterTerm::Text(word.to_string()))
+    }
+}
+
+/// The term for `!type`, `!type/...` or `!p1`..`!p3`.
+fn parse_type(type_str: &str) -> Option<FilterTerm> {
+    let base_type = type_str.split('/').next().unwrap_or(type_str);
+
+    // !p1..!p3 match entries of that priority
+    if let Some(priority) = base_type
+        .strip_prefix('p')
+        .and_then(|level| level.parse::<u8>().ok())
+        .filter(|level| (1..=3).contains(level))
+    {
+        return Some(FilterTerm::Priority(priority));
+    }
+
+    let (entry_type, states): (_, &[TaskState]) = match base_type {
+        "tasks" | "task" | "t" => (FilterType::Task, &OPEN_STATES),
+        "completed" | "c" => (FilterType::Task, &[TaskState::Done]),
+        "active" | "in-progress" => (FilterType::Task, &[TaskState::InProgress]),
+        "blocked" => (FilterType::Task, &[TaskState::Blocked]),
+        "migrated" => (FilterType::Task, &[TaskState::Migrated]),
+        "cancelled" | "canceled" => (FilterType::Task, &[TaskState::Cancelled]),
+        "notes" | "note" | "n" => (FilterType::Note, &[]),
+        "events" | "event" | "e" => (FilterType::Event, &[]),
+        _ => return None,
+    };
+    Some(FilterTerm::Type {
+        entry_type,
+        states: states.to_vec(),
+    })
+}
@@ -0,0 +1,698 @@
+//! Journal storage backends.
+//!
+//! A journal is either a single markdown file holding every day under a
+//! `# YYYY/MM/DD` header, or a directory of smaller files, one per day
+//! (`2026/10/2026-10-16.md`) or one per month (`2026/2026-10.md`). Each file in
+//! a directory journal is a journal in its own right, day headers included, so
+//! both backends are served by the journal index. A daily-notes folder, as kept
+//! by Obsidian or Logseq, is a directory of day files without headers: the file
+//! name gives the date, and everything in the file is the day's content. A
+//! `Journal` carries its layout and header format from config into every call.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::archive::{archives_for_day, archives_in_range};
+use super::headers::DayHeaderFormat;
+use super::history::{day_changes, split_days};
+use super::index;
+
+/// File name pattern of daily notes, as Obsidian names them by default.
+pub const DEFAULT_DAILY_NOTES_FORMAT: &str = "%Y-%m-%d";
+
+/// How a journal is laid out on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalLayout {
+    /// One markdown file with every day in it
+    #[default]
+    File,
+    /// A directory with one file per day: `YYYY/MM/YYYY-MM-DD.md`
+    Daily,
+    /// A directory with one file per month: `YYYY/YYYY-MM.md`
+    Monthly,
+    /// A folder of daily notes without day headers, named by date
+    #[serde(rename = "daily-notes")]
+    DailyNotes,
+}
+
+/// A journal's path along with how it's stored: its layout, how its daily
+/// notes are named, and the day headers it reads and writes. Derefs to the path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Journal {
+    path: PathBuf,
+    layout: JournalLayout,
+    daily_notes_format: String,
+    day_header: DayHeaderFormat,
+}
+
+impl Journal {
+    /// A single-file journal with default day headers.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            layout: JournalLayout::File,
+            daily_notes_format: DEFAULT_DAILY_NOTES_FORMAT.to_string(),
+            day_header: DayHeaderFormat::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_layout(mut self, layout: JournalLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Names daily notes with the strftime pattern, without `.md`. It may
+    /// contain `/` to nest notes in folders. An empty pattern keeps the default.
+    #[must_use]
+    pub fn with_daily_notes_format(mut self, pattern: &str) -> Self {
+        if !pattern.is_empty() {
+            self.daily_notes_format = pattern.to_string();
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn with_day_header(mut self, format: DayHeaderFormat) -> Self {
+        self.day_header = format;
+        self
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[must_use]
+    pub fn layout(&self) -> JournalLayout {
+        self.layout
+    }
+
+    #[must_use]
+    pub fn day_header(&self) -> &DayHeaderFormat {
+        &self.day_header
+    }
+
+    /// Another journal stored the same way, such as an archive of this one.
+    #[must_use]
+    pub fn sibling(&self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Deref for Journal {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for Journal {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Day-level access to a journal, whatever its layout.
+pub(super) trait JournalStore {
+    /// The day's content without its header.
+    fn load_day(&self, date: NaiveDate) -> io::Result<String>;
+
+    /// Replaces the day's content, removing the day when `content` is blank.
+    fn save_day(&self, date: NaiveDate, content: &str) -> io::Result<()>;
+
+    /// The whole journal as one document of day sections in date order.
+    fn load_journal(&self) -> io::Result<String>;
+
+    /// Replaces the whole journal from a document like `load_journal` returns.
+    fn save_journal(&self, content: &str) -> io::Result<()>;
+
+    /// Each day in a document like `load_journal` returns, in order, with its
+    /// content as `load_day` would read it.
+    fn split_journal(&self, journal: &str) -> Vec<(NaiveDate, String)>;
+
+    /// Each day with a header between `start` and `end` inclusive, with its content.
+    fn days_in_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> io::Result<Vec<(NaiveDate, String)>>;
+
+    /// The file the day is stored in.
+    fn day_file(&self, date: NaiveDate) -> PathBuf;
+
+    /// Every file holding days of the journal.
+    fn files(&self) -> io::Result<Vec<PathBuf>>;
+
+    /// When anything in the journal last changed. None if it doesn't exist.
+    fn modified(&self) -> Option<SystemTime>;
+
+    /// Creates an empty journal if there is none.
+    fn create(&self) -> io::Result<()>;
+
+    /// Whether the journal's files mark days with headers.
+    fn has_day_headers(&self) -> bool {
+        true
+    }
+}
+
+pub(super) fn journal_store(journal: &Journal) -> Box<dyn JournalStore + '_> {
+    match journal.layout {
+        JournalLayout::File => Box::new(FileStore { journal }),
+        JournalLayout::Daily => Box::new(DirectoryStore {
+            journal,
+            period: Period::Day,
+        }),
+        JournalLayout::Monthly => Box::new(DirectoryStore {
+            journal,
+            period: Period::Month,
+        }),
+        JournalLayout::DailyNotes => Box::new(DailyNotesStore { journal }),
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+struct FileStore<'a> {
+    journal: &'a Journal,
+}
+
+impl FileStore<'_> {
+    fn format(&self) -> &DayHeaderFormat {
+        &self.journal.day_header
+    }
+
+    /// The file holding `date`: the journal, or the archive the day was moved
+    /// to. Days in neither belong in the journal.
+    fn day_path(&self, date: NaiveDate) -> io::Result<PathBuf> {
+        let format = self.format();
+        if index::with_index(self.journal, format, |index| index.has_day(date))? {
+            return Ok(self.journal.to_path_buf());
+        }
+        for archive in archives_for_day(self.journal, date) {
+            if index::with_index(&archive, format, |index| index.has_day(date))? {
+                return Ok(archive);
+            }
+        }
+        Ok(self.journal.to_path_buf())
+    }
+}
+
+impl JournalStore for FileStore<'_> {
+    fn load_day(&self, date: NaiveDate) -> io::Result<String> {
+        index::with_index(&self.day_path(date)?, self.format(), |index| {
+            index.day_content(date)
+        })
+    }
+
+    fn save_day(&self, date: NaiveDate, content: &str) -> io::Result<()> {
+        index::save_day(&self.day_path(date)?, self.format(), date, content)
+    }
+
+    fn load_journal(&self) -> io::Result<String> {
+        index::with_index(self.journal, self.format(), |index| {
+            index.content().to_string()
+        })
+    }
+
+    fn save_journal(&self, content: &str) -> io::Result<()> {
+        index::save_journal(self.journal, self.format(), content)
+    }
+
+    fn split_journal(&self, journal: &str) -> Vec<(NaiveDate, String)> {
+        split_days(journal, self.format())
+    }
+
+    fn days_in_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> io::Result<Vec<(NaiveDate, String)>> {
+        let format = self.format();
+        let mut days = index::with_index(self.journal, format, |index| {
+            days_in_range(index, start, end)
+        })?;
+        for archive in archives_in_range(self.journal, Some(start), Some(end))? {
+            let archived =
+                index::with_index(&archive, format, |index| days_in_range(index, start, end))?;
+            let found: HashSet<NaiveDate> = days.iter().map(|(date, _)| *date).collect();
+            days.extend(
+                archived
+                    .into_iter()
+                    .filter(|(date, _)| !found.contains(date)),
+            );
+        }
+        Ok(days)
+    }
+
+    fn day_file(&self, date: NaiveDate) -> PathBuf {
+        self.day_path(date)
+            .unwrap_or_else(|_| self.journal.to_path_buf())
+    }
+
+    fn files(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .journal
+            .exists()
+            .then(|| self.journal.to_path_buf())
+            .into_iter()
+            .collect())
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        modified_time(self.journal)
+    }
+
+    fn create(&self) -> io::Result<()> {
+        if self.journal.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = self.journal.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(self.journal.path(), "")
+    }
+}
+
+/// Only the sections of days in range are read.
+fn days_in_range(
+    index: &index::JournalIndex,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<(NaiveDate, String)> {
+    let in_range: HashSet<NaiveDate> = index
+        .dates()
+        .filter(|date| *date >= start && *date <= end)
+        .collect();
+    in_range
+        .into_iter()
+        .map(|date| (date, index.day_content(date)))
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Period {
+    Day,
+    Month,
+}
+
+impl Period {
+    fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Period::Day => start.checked_add_days(Days::new(1)),
+            Period::Month => start.checked_add_months(Months::new(1)),
+        }
+    }
+
+    /// The first date of the period a journal file covers, from its name.
+    fn parse_file_name(self, name: &str) -> Option<NaiveDate> {
+        let stem = name.strip_suffix(".md")?;
+        match self {
+            Period::Day => NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok(),
+            Period::Month => NaiveDate::parse_from_str(&format!("{stem}-01"), "%Y-%m-%d").ok(),
+        }
+    }
+}
+
+struct DirectoryStore<'a> {
+    journal: &'a Journal,
+    period: Period,
+}
+
+impl DirectoryStore<'_> {
+    fn format(&self) -> &DayHeaderFormat {
+        &self.journal.day_header
+    }
+}
+
+impl JournalStore for DirectoryStore<'_> {
+    fn load_day(&self, date: NaiveDate) -> io::Result<String> {
+        let file = self.day_file(date);
+        if !file.exists() {
+            return Ok(String::new());
+        }
+        index::with_index(&file, self.format(), |index| index.day_content(date))
+    }
+
+    fn save_day(&self, date: NaiveDate, content: &str) -> io::Result<()> {
+        let file = self.day_file(date);
+        if content.trim().is_empty() && !file.exists() {
+            return Ok(());
+        }
+        index::save_day(&file, self.format(), date, content)?;
+        remove_if_empty(self.journal, &file)
+    }
+
+    fn load_journal(&self) -> io::Result<String> {
+        let mut sections = Vec::new();
+        for file in self.files()? {
+            let days = index::with_index(&file, self.format(), |index| {
+                index.days_text().trim_end().to_string()
+            })?;
+            if !days.is_empty() {
+                sections.push(days);
+            }
+        }
+        if sections.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(sections.join("\n\n") + "\n")
+    }
+
+    /// Only days that changed are written, so untouched files stay as they are.
+    fn save_journal(&self, content: &str) -> io::Result<()> {
+        let current = self.load_journal()?;
+        for change in day_changes(self.journal, &current, content) {
+            self.save_day(change.date, &change.after)?;
+        }
+        Ok(())
+    }
+
+    fn split_journal(&self, journal: &str) -> Vec<(NaiveDate, String)> {
+        split_days(journal, self.format())
+    }
+
+    fn days_in_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> io::Result<Vec<(NaiveDate, String)>> {
+        let mut days = Vec::new();
+        let mut period = Some(self.period.start_of(start));
+        while let Some(period_start) = period
+            && period_start <= end
+        {
+            let file = self.day_file(period_start);
+            if file.exists() {
+                days.extend(index::with_index(&file, self.format(), |index| {
+                    days_in_range(index, start, end)
+                })?);
+            }
+            period = self.period.next(period_start);
+        }
+        Ok(days)
+    }
+
+    fn day_file(&self, date: NaiveDate) -> PathBuf {
+        let year = self.journal.join(date.format("%Y").to_string());
+        match self.period {
+            Period::Day => year
+                .join(date.format("%m").to_string())
+                .join(date.format("%Y-%m-%d.md").to_string()),
+            Period::Month => year.join(date.format("%Y-%m.md").to_string()),
+        }
+    }
+
+    /// Journal files in date order.
+    fn files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files: Vec<(NaiveDate, PathBuf)> = walk(self.journal)?
+            .into_iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?;
+                Some((self.period.parse_file_name(name)?, path))
+            })
+            .collect();
+        files.sort();
+        Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        tree_modified(self.journal)
+    }
+
+    fn create(&self) -> io::Result<()> {
+        fs::create_dir_all(self.journal.path())
+    }
+}
+
+struct DailyNotesStore<'a> {
+    journal: &'a Journal,
+}
+
+impl DailyNotesStore<'_> {
+    /// The marker each note is introduced by in `load_journal`. Unlike a day
+    /// header it's matched exactly, so date headings inside notes aren't
+    /// mistaken for the start of another day.
+    fn marker(date: NaiveDate) -> String {
+        format!("# {}", date.format("%Y/%m/%d"))
+    }
+
+    fn parse_marker(line: &str) -> Option<NaiveDate> {
+        let date = NaiveDate::parse_from_str(line.strip_prefix("# ")?, "%Y/%m/%d").ok()?;
+        (line == Self::marker(date)).then_some(date)
+    }
+
+    /// The `---` fenced front matter a note starts with, as daily notes often
+    /// do, ending in a newline. Empty when the note has none or doesn't exist.
+    fn front_matter(&self, file: &Path) -> io::Result<String> {
+        if !file.exists() {
+            return Ok(String::new());
+        }
+        index::with_index(file, &self.journal.day_header, |index| {
+            let mut front_matter = split_front_matter(index.content()).0.to_string();
+            if !front_matter.is_empty() && !front_matter.ends_with('\n') {
+                front_matter.push('\n');
+            }
+            front_matter
+        })
+    }
+
+    /// Notes with their dates, in date order.
+    fn notes(&self) -> io::Result<Vec<(NaiveDate, PathBuf)>> {
+        let pattern = &self.journal.daily_notes_format;
+        let mut notes: Vec<(NaiveDate, PathBuf)> = walk(self.journal)?
+            .into_iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let relative = path.strip_prefix(self.journal.path()).ok()?.to_str()?;
+                let name = relative.strip_suffix(".md")?.replace('\\', "/");
+                let date = NaiveDate::parse_from_str(&name, pattern).ok()?;
+                Some((date, path))
+            })
+            .collect();
+        notes.sort();
+        Ok(notes)
+    }
+}
+
+impl JournalStore for DailyNotesStore<'_> {
+    fn load_day(&self, date: NaiveDate) -> io::Result<String> {
+        let file = self.day_file(date);
+        if !file.exists() {
+            return Ok(String::new());
+        }
+        index::with_index(&file, &self.journal.day_header, |index| {
+            split_front_matter(index.content()).1.trim_end().to_string()
+        })
+    }
+
+    /// A note's front matter isn't part of its day, so is kept as it was.
+    /// Notes are only removed once nothing at all is left in them.
+    fn save_day(&self, date: NaiveDate, content: &str) -> io::Result<()> {
+        let file = self.day_file(date);
+        let front_matter = self.front_matter(&file)?;
+        if content.trim().is_empty() {
+            if !file.exists() {
+                return Ok(());
+            }
+            index::save_journal(&file, &self.journal.day_header, &front_matter)?;
+            return remove_if_empty(self.journal, &file);
+        }
+        index::save_journal(
+            &file,
+            &self.journal.day_header,
+            &format!("{front_matter}{}\n", content.trim_end()),
+        )
+    }
+
+    fn load_journal(&self) -> io::Result<String> {
+        let mut sections = Vec::new();
+        for (date, _) in self.notes()? {
+            let content = self.load_day(date)?;
+            if !content.trim().is_empty() {
+                sections.push(format!("{}\n{content}", Self::marker(date)));
+            }
+        }
+        if sections.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(sections.join("\n\n") + "\n")
+    }
+
+    /// Only notes whose content changed are written.
+    fn save_journal(&self, content: &str) -> io::Result<()> {
+        let current = self.load_journal()?;
+        for change in day_changes(self.journal, &current, content) {
+            self.save_day(change.date, &change.after)?;
+        }
+        Ok(())
+    }
+
+    fn split_journal(&self, journal: &str) -> Vec<(NaiveDate, String)> {
+        let mut days: Vec<(NaiveDate, String)> = Vec::new();
+        for line in journal.lines() {
+            if let Some(date) = Self::parse_marker(line) {
+                days.push((date, String::new()));
+            } else if let Some((_, content)) = days.last_mut() {
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+        let mut seen = HashSet::new();
+        days.retain(|(date, _)| seen.insert(*date));
+        for (_, content) in &mut days {
+            content.truncate(content.trim_end().len());
+        }
+        days
+    }
+
+    fn days_in_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> io::Result<Vec<(NaiveDate, String)>> {
+        let mut days = Vec::new();
+        for date in start.iter_days().take_while(|date| *date <= end) {
+            let content = self.load_day(date)?;
+            if !content.is_empty() {
+                days.push((date, content));
+            }
+        }
+        Ok(days)
+    }
+
+    fn day_file(&self, date: NaiveDate) -> PathBuf {
+        self.journal.join(format!(
+            "{}.md",
+            date.format(&self.journal.daily_notes_format)
+        ))
+    }
+
+    fn files(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.notes()?.into_iter().map(|(_, path)| path).collect())
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        tree_modified(self.journal)
+    }
+
+    fn create(&self) -> io::Result<()> {
+        fs::create_dir_all(self.journal.path())
+    }
+
+    fn has_day_headers(&self) -> bool {
+        false
+    }
+}
+
+/// Splits a note into the `---` fenced front matter it starts with, if any,
+/// and the rest of the note.
+fn split_front_matter(note: &str) -> (&str, &str) {
+    let is_fence = |line: &str| line.trim_end_matches(['\n', '\r']) == "---";
+    let mut lines = note.split_inclusive('\n');
+    let Some(first) = lines.next().filter(|line| is_fence(line)) else {
+        return ("", note);
+    };
+    let mut end = first.len();
+    for line in lines {
+        end += line.len();
+        if is_fence(line) {
+            return note.split_at(end);
+        }
+    }
+    ("", note)
+}
+
+/// Deletes a file left with nothing in it, along with any directories under
+/// the journal that leaves empty.
+fn remove_if_empty(journal: &Journal, file: &Path) -> io::Result<()> {
+    let is_empty = index::with_index(file, &journal.day_header, |index| {
+        index.content().trim().is_empty()
+    })?;
+    let root = journal.path();
+    if !is_empty {
+        return Ok(());
+    }
+    fs::remove_file(file)?;
+    let mut dir = file.parent();
+    while let Some(current) = dir
+        && current != root
+        && current.starts_with(root)
+        && fs::remove_dir(current).is_ok()
+    {
+        dir = current.parent();
+    }
+    Ok(())
+}
+
+/// When anything under `root` last changed, without reading every file's
+/// mtime. Adding, deleting or atomically replacing a file touches its
+/// directory, so directories are checked, plus the files already read into
+/// the index, which are the only ones an in-place edit could leave stale.
+fn tree_modified(root: &Path) -> Option<SystemTime> {
+    let modified = modified_time(root)?;
+    let dirs = walk_dirs(root).unwrap_or_default();
+    Some(
+        dirs.into_iter()
+            .chain(index::indexed_files(root))
+            .filter_map(|path| modified_time(&path))
+            .fold(modified, SystemTime::max),
+    )
+}
+
+/// Every file and directory under `dir`, skipping hidden ones such as lock
+/// files and in-progress writes.
+fn walk(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    walk_filtered(dir, true)
+}
+
+/// Every directory under `dir`, skipping hidden ones.
+fn walk_dirs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    walk_filtered(dir, false)
+}
+
+fn walk_filtered(dir: &Path, include_files: bool) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let read_dir = match fs::read_dir(dir) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(paths),
+        result => result?,
+    };
+    for entry in read_dir {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let is_dir = entry.file_type()?.is_dir();
+        if is_dir {
+            paths.extend(walk_filtered(&path, include_files)?);
+        }
+        if is_dir || include_files {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
@@ -1,12 +1,10 @@
-use std::path::Path;
-
 use chrono::{Local, NaiveDate};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line as RatatuiLine, Span};
 use unicode_width::UnicodeWidthStr;
 
 use crate::calendar::CalendarStore;
-use crate::storage::{self, EntryType, SourceType};
+use crate::storage::{self, EntryType, Journal, SourceType};
 
 use super::shared::truncate_text;
 use super::theme;
@@ -156,7 +154,7 @@ fn fit_entries_to_height(
     result
 }
 
-pub fn collect_agenda_cache(calendar_store: &CalendarStore, path: &Path) -> AgendaCache {
+pub fn collect_agenda_cache(calendar_store: &CalendarStore, path: &Journal) -> AgendaCache {
     let today = Local::now().date_naive();
     let mut days = Vec::new();
     let mut max_width = theme::AGENDA_DATE_WIDTH;
@@ -86,7 +86,10 @@ pub fn render_calendar(f: &mut Frame<'_>, model: &CalendarModel<'_>, area: Rect)
     // Selected day styling
     let selected_info = model.day_cache.get(&model.selected);
     let selected_style = if model.selected == today {
-        Style::default().fg(theme::CALENDAR_TODAY).reversed().not_dim()
+        Style::default()
+            .fg(theme::CALENDAR_TODAY)
+            .reversed()
+            .not_dim()
     } else if selected_info.is_some_and(|i| i.has_incomplete_tasks) {
         Style::default()
             .fg(theme::CALENDAR_INCOMPLETE)
@@ -2,7 +2,7 @@ use ratatui::style::{Modifier, Style, Stylize};
 use unicode_width::UnicodeWidthStr;
 
 use crate::app::{App, EditContext, InputMode, ViewMode};
-use crate::storage::{EntryType, Line};
+use crate::storage::{Line, subtask_progress};
 use crate::ui::model::RowModel;
 
 use super::helpers::edit_text;
@@ -50,10 +50,7 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
     let mut visible_projected_idx = 0;
 
     for projected_entry in &state.projected_entries {
-        let is_completed = matches!(
-            projected_entry.entry_type,
-            EntryType::Task { completed: true }
-        );
+        let is_completed = projected_entry.entry_type.is_closed_task();
         if app.hide_completed && is_completed {
             continue;
         }
@@ -69,12 +66,26 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
             visible_idx,
             width,
         ));
+        if app.is_body_expanded(
+            &projected_entry.source_journal,
+            projected_entry.source_date,
+            &projected_entry.content,
+        ) {
+            rows.extend(rows::build_body_rows(
+                &projected_entry.body,
+                projected_entry.entry_type.prefix().width(),
+                width,
+                entry_style(&projected_entry.entry_type),
+            ));
+        }
     }
 
+    let path = app.active_path();
+
     let mut visible_entry_idx = 0;
     for &line_idx in &app.entry_indices {
         if let Line::Entry(entry) = &app.lines[line_idx] {
-            let is_completed = matches!(entry.entry_type, EntryType::Task { completed: true });
+            let is_completed = entry.entry_type.is_closed_task();
 
             if app.hide_completed && is_completed {
                 continue;
@@ -90,13 +101,13 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
 
             let text = edit_text(app, is_editing, &entry.content);
 
-            let prefix = entry.prefix();
+            let prefix = format!("{}{}", rows::nesting_indent(entry.depth), entry.prefix());
             let prefix_width = prefix.width();
 
             if is_editing {
                 let text_width = width.saturating_sub(prefix_width);
                 rows.extend(build_edit_rows_with_prefix_width(
-                    prefix,
+                    &prefix,
                     prefix_width,
                     content_style,
                     &text,
@@ -104,13 +115,24 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
                     None,
                 ));
             } else {
+                let body_expanded = app.is_body_expanded(path, app.current_date, &entry.content);
                 rows.push(rows::build_daily_entry_row(
                     app,
                     entry,
+                    subtask_progress(&app.lines, line_idx),
+                    body_expanded,
                     is_selected,
                     selection_idx,
                     width,
                 ));
+                if body_expanded {
+                    rows.extend(rows::build_body_rows(
+                        &entry.body,
+                        prefix_width,
+                        width,
+                        content_style,
+                    ));
+                }
             }
         }
     }
@@ -148,9 +170,10 @@ fn build_combined_daily_list(app: &App, width: usize) -> ListModel {
         let header_style = Style::default()
             .fg(theme::PALETTE_ACCENT)
             .add_modifier(Modifier::BOLD);
-        rows.push(RowModel::from_spans(vec![
-            ratatui::text::Span::styled(format!("── {} ──", group.project_name), header_style),
-        ]));
+        rows.push(RowModel::from_spans(vec![ratatui::text::Span::styled(
+            format!("── {} ──", group.project_name),
+            header_style,
+        )]));
 
         for projected_entry in &group.projected_entries {
             if app.hide_completed && !app.should_show_entry(projected_entry) {
@@ -176,6 +199,8 @@ fn build_combined_daily_list(app: &App, width: usize) -> ListModel {
                 rows.push(rows::build_daily_entry_row(
                     app,
                     entry,
+                    subtask_progress(&group.lines, line_idx),
+                    false,
                     is_selected,
                     flat_visible_idx,
                     width,
@@ -70,6 +70,21 @@ pub fn build_filter_list(app: &App, width: usize) -> ListModel {
         } else {
             rows.push(rows::build_filter_row(app, filter_entry, idx, width));
         }
+
+        if !is_editing_this
+            && app.is_body_expanded(
+                &filter_entry.source_journal,
+                filter_entry.source_date,
+                &filter_entry.content,
+            )
+        {
+            rows.extend(rows::build_body_rows(
+                &filter_entry.body,
+                prefix_width,
+                width,
+                content_style,
+            ));
+        }
     }
 
     if let InputMode::Edit(EditContext::FilterQuickAdd { entry_type, .. }) = &app.input_mode {
@@ -112,7 +127,7 @@ fn build_combined_filter_list(app: &App, width: usize) -> ListModel {
     let mut groups: BTreeMap<PathBuf, Vec<(usize, &crate::storage::Entry)>> = BTreeMap::new();
     for (idx, entry) in state.entries.iter().enumerate() {
         groups
-            .entry(entry.source_journal.clone())
+            .entry(entry.source_journal.to_path_buf())
             .or_default()
             .push((idx, entry));
     }
@@ -120,11 +135,11 @@ fn build_combined_filter_list(app: &App, width: usize) -> ListModel {
     // Build a lookup from journal path to project name
     let registry = ProjectRegistry::load();
     let resolve_name = |path: &PathBuf| -> String {
-        if path == app.journal_context.hub_path() {
+        if path == app.journal_context.hub_path().path() {
             return "Hub".to_string();
         }
         for project in &registry.projects {
-            if project.journal_path() == *path {
+            if project.journal_path().path() == path {
                 return project.name.clone();
             }
         }
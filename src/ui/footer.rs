@@ -42,6 +42,9 @@ pub enum FooterMode {
     Selection,
     CommandPaletteProjects,
     CommandPaletteTags,
+    CommandPaletteBackups,
+    CommandPaletteUndoHistory,
+    CommandPaletteCommits,
     FilterPrompt,
 }
 
@@ -64,6 +67,9 @@ impl FooterMode {
                 },
                 CommandPaletteMode::Projects => FooterMode::CommandPaletteProjects,
                 CommandPaletteMode::Tags => FooterMode::CommandPaletteTags,
+                CommandPaletteMode::Backups => FooterMode::CommandPaletteBackups,
+                CommandPaletteMode::UndoHistory => FooterMode::CommandPaletteUndoHistory,
+                CommandPaletteMode::Commits => FooterMode::CommandPaletteCommits,
             },
             InputMode::FilterPrompt => FooterMode::FilterPrompt,
             InputMode::Confirm(_) | InputMode::DatePicker(_) => match view {
@@ -84,6 +90,9 @@ impl FooterMode {
             FooterMode::Selection => "selection",
             FooterMode::CommandPaletteProjects => "command_palette_projects",
             FooterMode::CommandPaletteTags => "command_palette_tags",
+            FooterMode::CommandPaletteBackups => "command_palette_backups",
+            FooterMode::CommandPaletteUndoHistory => "command_palette_undo_history",
+            FooterMode::CommandPaletteCommits => "command_palette_commits",
             FooterMode::FilterPrompt => "filter_prompt",
         }
     }
@@ -97,9 +106,11 @@ impl FooterMode {
             FooterMode::Edit => KeyContext::Edit,
             FooterMode::Reorder => KeyContext::Reorder,
             FooterMode::Selection => KeyContext::Selection,
-            FooterMode::CommandPaletteProjects | FooterMode::CommandPaletteTags => {
-                KeyContext::CommandPalette
-            }
+            FooterMode::CommandPaletteProjects
+            | FooterMode::CommandPaletteTags
+            | FooterMode::CommandPaletteBackups
+            | FooterMode::CommandPaletteUndoHistory
+            | FooterMode::CommandPaletteCommits => KeyContext::CommandPalette,
             FooterMode::FilterPrompt => KeyContext::Edit,
         }
     }
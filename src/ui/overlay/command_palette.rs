@@ -7,9 +7,10 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
+use crate::app::actions::UndoStep;
 use crate::app::{CommandPaletteMode, CommandPaletteState, TagInfo};
 use crate::registry::{COMMANDS, Command, KeyActionId, KeyContext, get_keys_for_action};
-use crate::storage::ProjectRegistry;
+use crate::storage::{Backup, Commit, LineDiff, ProjectRegistry};
 
 use super::super::scroll_indicator::{ScrollIndicatorStyle, scroll_indicator_text};
 use super::super::surface::Surface;
@@ -21,6 +22,10 @@ pub struct CommandPaletteModel {
     pub selected: usize,
     pub projects: Vec<PaletteProject>,
     pub tags: Vec<PaletteTag>,
+    pub backups: Vec<PaletteBackup>,
+    pub backup_preview: Vec<LineDiff>,
+    pub undo_steps: Vec<PaletteUndoStep>,
+    pub commits: Vec<PaletteCommit>,
 }
 
 pub struct PaletteProject {
@@ -35,12 +40,48 @@ pub struct PaletteTag {
     pub count: usize,
 }
 
+pub struct PaletteBackup {
+    pub taken_at: String,
+    pub reason: String,
+}
+
+pub struct PaletteUndoStep {
+    pub description: String,
+    pub days: String,
+}
+
+pub struct PaletteCommit {
+    pub subject: String,
+    pub detail: String,
+}
+
+impl PaletteUndoStep {
+    fn new(step: &UndoStep) -> Self {
+        let mut dates: Vec<_> = step.changes.iter().map(|change| change.date).collect();
+        dates.sort();
+        dates.dedup();
+        let days = match dates.as_slice() {
+            [] => String::new(),
+            [date] => date.format("%Y/%m/%d").to_string(),
+            [first, rest @ ..] => format!("{} +{} days", first.format("%Y/%m/%d"), rest.len()),
+        };
+        Self {
+            description: step.description.past.clone(),
+            days,
+        }
+    }
+}
+
 impl CommandPaletteModel {
     #[must_use]
     pub fn new(
         state: &CommandPaletteState,
         tags: &[TagInfo],
         current_project_path: Option<&std::path::Path>,
+        backups: &[Backup],
+        backup_preview: &[LineDiff],
+        undo_steps: &[&UndoStep],
+        commits: &[Commit],
     ) -> Self {
         let registry = ProjectRegistry::load();
         let mut projects: Vec<_> = registry
@@ -65,11 +106,32 @@ impl CommandPaletteModel {
             })
             .collect();
 
+        let backups = backups
+            .iter()
+            .map(|b| PaletteBackup {
+                taken_at: b.taken_at.format("%Y/%m/%d %H:%M").to_string(),
+                reason: b.reason.replace('-', " "),
+            })
+            .collect();
+
         Self {
             mode: state.mode,
             selected: state.selected,
             projects,
             tags,
+            backups,
+            backup_preview: backup_preview.to_vec(),
+            undo_steps: undo_steps
+                .iter()
+                .map(|step| PaletteUndoStep::new(step))
+                .collect(),
+            commits: commits
+                .iter()
+                .map(|commit| PaletteCommit {
+                    subject: commit.subject.clone(),
+                    detail: format!("{} {}", commit.hash, commit.time),
+                })
+                .collect(),
         }
     }
 }
@@ -86,6 +148,9 @@ fn empty_message(mode: CommandPaletteMode) -> &'static str {
         CommandPaletteMode::Commands => theme::LABEL_EMPTY_COMMANDS,
         CommandPaletteMode::Projects => theme::LABEL_EMPTY_PROJECTS,
         CommandPaletteMode::Tags => theme::LABEL_EMPTY_TAGS,
+        CommandPaletteMode::Backups => theme::LABEL_EMPTY_BACKUPS,
+        CommandPaletteMode::UndoHistory => theme::LABEL_EMPTY_UNDO_HISTORY,
+        CommandPaletteMode::Commits => theme::LABEL_EMPTY_COMMITS,
     }
 }
 
@@ -94,6 +159,9 @@ fn tab_index(mode: CommandPaletteMode) -> usize {
         CommandPaletteMode::Commands => 0,
         CommandPaletteMode::Projects => 1,
         CommandPaletteMode::Tags => 2,
+        CommandPaletteMode::Backups => 3,
+        CommandPaletteMode::UndoHistory => 4,
+        CommandPaletteMode::Commits => 5,
     }
 }
 
@@ -164,7 +232,16 @@ pub fn render_command_palette(
 
     let padding = 1u16;
     let tabs_section = layout[0];
-    let list_area = layout[1];
+    let (list_area, preview_area) = if model.mode == CommandPaletteMode::Backups {
+        let list_height = (model.backups.len().max(1) + 1).min(layout[1].height as usize / 2);
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(list_height as u16), Constraint::Min(0)])
+            .split(layout[1]);
+        (split[0], Some(split[1]))
+    } else {
+        (layout[1], None)
+    };
     let footer_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(1), Constraint::Length(1)])
@@ -185,6 +262,9 @@ pub fn render_command_palette(
         theme::LABEL_TAB_COMMANDS,
         theme::LABEL_TAB_PROJECTS,
         theme::LABEL_TAB_TAGS,
+        theme::LABEL_TAB_BACKUPS,
+        theme::LABEL_TAB_UNDO_HISTORY,
+        theme::LABEL_TAB_COMMITS,
     ];
     let tabs = Tabs::new(tab_labels)
         .select(tab_index(model.mode))
@@ -275,6 +355,16 @@ pub fn render_command_palette(
         .bg(bg)
         .add_modifier(Modifier::BOLD);
 
+    if let Some(preview_area) = preview_area {
+        render_backup_preview(
+            f,
+            preview_area,
+            &model.backup_preview,
+            padding,
+            (bg, muted, header_style),
+        );
+    }
+
     match model.mode {
         CommandPaletteMode::Commands => {
             let commands = filtered_commands(model.mode);
@@ -420,6 +510,99 @@ pub fn render_command_palette(
                 }
             }
         }
+        CommandPaletteMode::Backups => {
+            let header_line = padded_line("Snapshots", list_width, padding);
+            lines.push(RatatuiLine::from(Span::styled(header_line, header_style)));
+
+            if model.backups.is_empty() {
+                let empty_line = padded_line(theme::LABEL_EMPTY_BACKUPS, list_width, padding);
+                lines.push(RatatuiLine::from(Span::styled(
+                    empty_line,
+                    Style::default().fg(muted).bg(bg),
+                )));
+            } else {
+                for (index, backup) in model.backups.iter().enumerate() {
+                    let is_selected = index == model.selected;
+                    if is_selected {
+                        selected_line = Some(lines.len());
+                    }
+                    lines.push(build_palette_item_line(
+                        PaletteItem {
+                            name: &backup.taken_at,
+                            description: &backup.reason,
+                            is_selected,
+                            is_available: true,
+                        },
+                        list_width,
+                        padding,
+                        bg,
+                        muted,
+                    ));
+                }
+            }
+        }
+        CommandPaletteMode::UndoHistory => {
+            let header_line = padded_line("Most Recent First", list_width, padding);
+            lines.push(RatatuiLine::from(Span::styled(header_line, header_style)));
+
+            if model.undo_steps.is_empty() {
+                let empty_line = padded_line(theme::LABEL_EMPTY_UNDO_HISTORY, list_width, padding);
+                lines.push(RatatuiLine::from(Span::styled(
+                    empty_line,
+                    Style::default().fg(muted).bg(bg),
+                )));
+            } else {
+                for (index, step) in model.undo_steps.iter().enumerate() {
+                    let is_selected = index == model.selected;
+                    if is_selected {
+                        selected_line = Some(lines.len());
+                    }
+                    lines.push(build_palette_item_line(
+                        PaletteItem {
+                            name: &step.description,
+                            description: &step.days,
+                            is_selected,
+                            is_available: true,
+                        },
+                        list_width,
+                        padding,
+                        bg,
+                        muted,
+                    ));
+                }
+            }
+        }
+        CommandPaletteMode::Commits => {
+            let header_line = padded_line("Made This Day", list_width, padding);
+            lines.push(RatatuiLine::from(Span::styled(header_line, header_style)));
+
+            if model.commits.is_empty() {
+                let empty_line = padded_line(theme::LABEL_EMPTY_COMMITS, list_width, padding);
+                lines.push(RatatuiLine::from(Span::styled(
+                    empty_line,
+                    Style::default().fg(muted).bg(bg),
+                )));
+            } else {
+                for (index, commit) in model.commits.iter().enumerate() {
+                    let is_selected = index == model.selected;
+                    if is_selected {
+                        selected_line = Some(lines.len());
+                    }
+                    lines.push(build_palette_item_line(
+                        PaletteItem {
+                            name: &commit.subject,
+                            description: &commit.detail,
+                            is_selected,
+                            is_available: true,
+                        },
+                        list_width,
+                        padding,
+                        bg,
+                        muted,
+                    ));
+                }
+            }
+        }
     }
 
     if lines.is_empty() {
@@ -475,3 +658,42 @@ pub fn render_command_palette(
         f.render_widget(footer, footer_area);
     }
 }
+
+/// Lists what restoring the selected backup would change, as removed and added lines.
+fn render_backup_preview(
+    f: &mut Frame<'_>,
+    area: Rect,
+    preview: &[LineDiff],
+    padding: usize,
+    (bg, muted, header_style): (Color, Color, Style),
+) {
+    let width = area.width as usize;
+    let mut lines = vec![
+        RatatuiLine::styled(" ".repeat(width), Style::default().bg(bg)),
+        RatatuiLine::from(Span::styled(
+            padded_line("Restoring Changes", width, padding),
+            header_style,
+        )),
+    ];
+
+    if preview.is_empty() {
+        lines.push(RatatuiLine::from(Span::styled(
+            padded_line("Identical to the current journal", width, padding),
+            Style::default().fg(muted).bg(bg),
+        )));
+    }
+    for diff in preview {
+        let (marker, text, color) = match diff {
+            LineDiff::Removed(text) => ("- ", text, theme::DIFF_REMOVED),
+            LineDiff::Added(text) => ("+ ", text, theme::DIFF_ADDED),
+            LineDiff::Unchanged(text) => ("  ", text, muted),
+        };
+        lines.push(RatatuiLine::from(Span::styled(
+            padded_line(&format!("{marker}{text}"), width, padding),
+            Style::default().fg(color).bg(bg),
+        )));
+    }
+
+    lines.truncate(area.height as usize);
+    f.render_widget(Paragraph::new(lines), area);
+}
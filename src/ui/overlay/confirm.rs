@@ -45,6 +45,47 @@ pub fn render_confirm_modal(f: &mut Frame<'_>, area: Rect, model: ConfirmModel)
                 theme::LABEL_CANNOT_UNDO.to_string(),
             ],
         ),
+        ConfirmContext::SaveConflict(date) => (
+            theme::TITLE_SAVE_CONFLICT,
+            [
+                format!(
+                    "An entry on {} was also changed on disk.",
+                    date.format("%Y/%m/%d")
+                ),
+                theme::MSG_KEEP_YOUR_VERSION.to_string(),
+            ],
+        ),
+        ConfirmContext::RestoreBackup(_) => (
+            theme::TITLE_RESTORE_BACKUP,
+            [
+                theme::MSG_RESTORE_BACKUP.to_string(),
+                theme::MSG_RESTORE_BACKS_UP_FIRST.to_string(),
+            ],
+        ),
+        ConfirmContext::ReformatJournal(example) => (
+            theme::TITLE_REFORMAT_JOURNAL,
+            [
+                format!("Rewrite every day header like \"{example}\"?"),
+                theme::MSG_JOURNAL_BACKED_UP_FIRST.to_string(),
+            ],
+        ),
+        ConfirmContext::ArchiveJournal { before, archive } => (
+            theme::TITLE_ARCHIVE,
+            [
+                format!(
+                    "Move days before {} into {archive}?",
+                    before.format("%Y/%m/%d")
+                ),
+                theme::MSG_JOURNAL_BACKED_UP_FIRST.to_string(),
+            ],
+        ),
+        ConfirmContext::FixJournal { fixable, example } => (
+            theme::TITLE_CHECK_JOURNAL,
+            [
+                format!("Fix {fixable} problems, like \"{example}\"?"),
+                theme::MSG_JOURNAL_BACKED_UP_FIRST.to_string(),
+            ],
+        ),
     };
 
     let popup_area = centered_rect(50, 30, area);
@@ -1,9 +1,10 @@
 use crate::app::{App, DATE_SUFFIX_WIDTH, EditContext, InputMode, ViewMode};
-use crate::cursor::{CursorBuffer, cursor_position_in_wrap};
+use crate::cursor::{CursorBuffer, cursor_position_in_lines};
 use crate::storage::Line;
 use unicode_width::UnicodeWidthStr;
 
 use super::context::RenderContext;
+use super::rows::nesting_indent;
 use super::scroll::{CursorContext, ensure_line_visible, ensure_selected_visible};
 use super::views::{
     list_content_height_for_daily, list_content_height_for_filter, list_content_width_for_daily,
@@ -23,7 +24,7 @@ fn build_cursor_context(
     let text_width = available_width.saturating_sub(prefix_width);
     let wrap_width = text_width.saturating_sub(1).max(1);
     let (cursor_row, cursor_col) =
-        cursor_position_in_wrap(buffer.content(), buffer.cursor_display_pos(), wrap_width);
+        cursor_position_in_lines(buffer.content(), buffer.cursor_char_pos(), wrap_width);
     CursorContext {
         prefix_width,
         cursor_row,
@@ -38,14 +39,18 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
     let visible_entry_count = app.visible_entry_count();
     let visible_calendar_count = app.visible_calendar_event_count();
     let hidden_row = if app.has_hidden_entries_row() { 1 } else { 0 };
+    // Expanded bodies push the entries below them down
+    let body_rows = app.body_rows();
+    let body_rows_before = |index: usize| body_rows.iter().take(index).sum::<usize>();
+    let total_body_rows = body_rows_before(body_rows.len());
 
     match &mut app.view {
         ViewMode::Filter(state) => {
             let scroll_height = list_content_height_for_filter(layout);
             ensure_selected_visible(
                 &mut state.scroll_offset,
-                filter_visual_line,
-                filter_total_lines,
+                filter_visual_line + body_rows_before(state.selected),
+                filter_total_lines + total_body_rows,
                 scroll_height,
             );
             if state.selected == 0 {
@@ -57,8 +62,8 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
             let offset = visible_calendar_count + hidden_row;
             ensure_selected_visible(
                 &mut state.scroll_offset,
-                state.selected + offset,
-                visible_entry_count + offset,
+                state.selected + offset + body_rows_before(state.selected),
+                visible_entry_count + offset + total_body_rows,
                 scroll_height,
             );
             if state.selected == 0 {
@@ -81,7 +86,7 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
                     buffer,
                     prefix_width,
                     available_width,
-                    state.entries.len(),
+                    state.entries.len() + total_body_rows,
                 ))
             }
             EditContext::FilterEdit { filter_index, .. } => {
@@ -92,7 +97,8 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
                     let prefix_width = filter_entry.entry_type.prefix().len();
                     let available_width =
                         list_content_width_for_filter(layout).saturating_sub(DATE_SUFFIX_WIDTH);
-                    build_cursor_context(buffer, prefix_width, available_width, *filter_index)
+                    let entry_start_line = *filter_index + body_rows_before(*filter_index);
+                    build_cursor_context(buffer, prefix_width, available_width, entry_start_line)
                 })
             }
             EditContext::Daily { entry_index } => app
@@ -100,19 +106,20 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
                 .get(*entry_index)
                 .and_then(|&i| {
                     if let Line::Entry(entry) = &app.lines[i] {
-                        Some(&entry.entry_type)
+                        Some(entry)
                     } else {
                         None
                     }
                 })
-                .map(|entry_type| {
-                    let prefix_width = entry_type.prefix().width();
+                .map(|entry| {
+                    let prefix_width = nesting_indent(entry.depth).len() + entry.prefix().width();
                     let available_width = list_content_width_for_daily(layout);
                     let hidden_row = if app.has_hidden_entries_row() { 1 } else { 0 };
                     let entry_start_line = app.visible_calendar_event_count()
                         + hidden_row
                         + app.visible_projected_count()
-                        + app.visible_entries_before(*entry_index);
+                        + app.visible_entries_before(*entry_index)
+                        + body_rows_before(app.view.selected());
                     build_cursor_context(buffer, prefix_width, available_width, entry_start_line)
                 }),
         }
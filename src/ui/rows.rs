@@ -7,12 +7,14 @@ use unicode_width::UnicodeWidthStr;
 
 use crate::app::{App, InputMode};
 use crate::calendar::CalendarEvent;
-use crate::storage::{Entry, EntryType, RawEntry, SourceType, strip_done_meta};
+use crate::storage::{
+    Entry, EntryType, RawEntry, SourceType, extract_scheduled_date, strip_done_meta,
+};
 
 use super::model::RowModel;
 use super::shared::{
-    date_suffix_style, entry_style, format_date_suffix, style_content, truncate_with_tags,
-    wrap_text,
+    date_suffix_style, entry_style, format_date_suffix, marker_style, style_content, truncate_text,
+    truncate_with_tags, wrap_text,
 };
 use super::theme;
 
@@ -67,12 +69,17 @@ pub fn build_projected_row(
     visible_idx: usize,
     width: usize,
 ) -> RowModel {
-    // Don't show source date for recurring entries - the creation date isn't meaningful
-    let suffix = if matches!(projected_entry.source_type, SourceType::Recurring) {
-        EntrySuffix::None
-    } else {
-        let (source_suffix, _) = format_date_suffix(projected_entry.source_date);
-        EntrySuffix::Date(source_suffix)
+    // Don't show source date for recurring entries - the creation date isn't meaningful.
+    // Overdue entries show when they were due instead of when they were written.
+    let suffix = match projected_entry.source_type {
+        SourceType::Recurring => EntrySuffix::None,
+        SourceType::Overdue => {
+            let due_date =
+                extract_scheduled_date(&projected_entry.content, projected_entry.source_date)
+                    .map_or(projected_entry.source_date, |scheduled| scheduled.date);
+            EntrySuffix::Date(format_date_suffix(due_date).0)
+        }
+        _ => EntrySuffix::Date(format_date_suffix(projected_entry.source_date).0),
     };
     build_entry_row(
         app,
@@ -84,18 +91,26 @@ pub fn build_projected_row(
             visible_idx,
             indicator: EntryIndicator::Projected(&projected_entry.source_type),
             suffix,
+            depth: 0,
+            body: body_marker(app, projected_entry),
         },
     )
 }
 
+/// `progress` is the (completed, total) count of tasks nested under the entry.
 pub fn build_daily_entry_row(
     app: &App,
     entry: &RawEntry,
+    progress: Option<(usize, usize)>,
+    body_expanded: bool,
     is_selected: bool,
     visible_idx: usize,
     width: usize,
 ) -> RowModel {
     let content = strip_done_meta(&entry.content);
+    let suffix = progress.map_or(EntrySuffix::None, |(done, total)| {
+        EntrySuffix::Progress(format!(" {done}/{total}"))
+    });
     build_entry_row(
         app,
         EntryRowSpec {
@@ -105,23 +120,28 @@ pub fn build_daily_entry_row(
             is_selected,
             visible_idx,
             indicator: EntryIndicator::Daily,
-            suffix: EntrySuffix::None,
+            suffix,
+            depth: entry.depth,
+            body: (entry.body.len(), body_expanded),
         },
     )
 }
 
 pub fn build_filter_selected_row(app: &App, entry: &Entry, index: usize, width: usize) -> RowModel {
     let (date_suffix, _) = format_date_suffix(entry.source_date);
+    let content = strip_done_meta(&entry.content);
     build_entry_row(
         app,
         EntryRowSpec {
             entry_type: &entry.entry_type,
-            text: &entry.content,
+            text: &content,
             width,
             is_selected: true,
             visible_idx: index,
             indicator: EntryIndicator::FilterSelected,
             suffix: EntrySuffix::Date(date_suffix),
+            depth: 0,
+            body: body_marker(app, entry),
         },
     )
 }
@@ -137,6 +157,7 @@ enum EntryIndicator<'a> {
 enum EntrySuffix {
     None,
     Date(String),
+    Progress(String),
 }
 
 struct EntryRowSpec<'a> {
@@ -147,22 +168,41 @@ struct EntryRowSpec<'a> {
     visible_idx: usize,
     indicator: EntryIndicator<'a>,
     suffix: EntrySuffix,
+    depth: usize,
+    /// (body line count, whether the body is expanded)
+    body: (usize, bool),
+}
+
+fn body_marker(app: &App, entry: &Entry) -> (usize, bool) {
+    let expanded = app.is_body_expanded(&entry.source_journal, entry.source_date, &entry.content);
+    (entry.body.len(), expanded)
 }
 
 fn build_entry_row(app: &App, spec: EntryRowSpec<'_>) -> RowModel {
     let content_style = entry_style(spec.entry_type);
+    let indent = nesting_indent(spec.depth);
     let prefix = spec.entry_type.prefix();
-    let prefix_width = prefix.width();
+    let prefix_width = indent.len() + prefix.width();
 
     let (suffix_text, suffix_width) = match spec.suffix {
         EntrySuffix::None => (None, 0),
-        EntrySuffix::Date(text) => {
+        EntrySuffix::Date(text) | EntrySuffix::Progress(text) => {
             let width = text.width();
             (Some(text), width)
         }
     };
 
-    let available = spec.width.saturating_sub(prefix_width + suffix_width);
+    // Entries with a body show how many lines it has until it's expanded
+    let body_marker = match spec.body {
+        (0, _) => None,
+        (_, true) => Some(format!(" {}", theme::GLYPH_BODY_EXPANDED)),
+        (lines, false) => Some(format!(" {}{lines}", theme::GLYPH_BODY_COLLAPSED)),
+    };
+    let marker_width = body_marker.as_ref().map_or(0, |marker| marker.width());
+
+    let available = spec
+        .width
+        .saturating_sub(prefix_width + suffix_width + marker_width);
     let display_text = truncate_with_tags(spec.text, available);
 
     let (first_char, rest_of_prefix) = split_prefix(prefix);
@@ -184,28 +224,67 @@ fn build_entry_row(app: &App, spec: EntryRowSpec<'_>) -> RowModel {
         }
     };
 
+    // Nested entries are indented ahead of the indicator so the cursor follows the nesting
+    let indicator = if indent.is_empty() {
+        indicator
+    } else {
+        Span::styled(format!("{indent}{}", indicator.content), indicator.style)
+    };
+
     let suffix_span = suffix_text.map(|text| Span::styled(text, date_suffix_style(content_style)));
 
+    let marker_span =
+        body_marker.map(|marker| Span::styled(marker, date_suffix_style(content_style)));
+    let mut content = style_content(&display_text, content_style);
+    content.extend(marker_span);
+
     RowModel::new(
         Some(indicator),
-        Some(Span::styled(rest_of_prefix, content_style)),
-        style_content(&display_text, content_style),
+        Some(Span::styled(
+            rest_of_prefix,
+            marker_style(spec.entry_type, content_style),
+        )),
+        content,
         suffix_span,
     )
 }
 
+/// Rows for an expanded body, aligned under its entry's content.
+pub fn build_body_rows(
+    body: &[String],
+    prefix_width: usize,
+    width: usize,
+    content_style: Style,
+) -> Vec<RowModel> {
+    let available = width.saturating_sub(prefix_width);
+    let style = date_suffix_style(content_style);
+    body.iter()
+        .map(|line| {
+            RowModel::new(
+                None,
+                Some(Span::raw(" ".repeat(prefix_width))),
+                style_content(&truncate_text(line, available), style),
+                None,
+            )
+        })
+        .collect()
+}
+
 pub fn build_filter_row(app: &App, entry: &Entry, index: usize, width: usize) -> RowModel {
     let (date_suffix, _) = format_date_suffix(entry.source_date);
+    let content = strip_done_meta(&entry.content);
     build_entry_row(
         app,
         EntryRowSpec {
             entry_type: &entry.entry_type,
-            text: &entry.content,
+            text: &content,
             width,
             is_selected: false,
             visible_idx: index,
             indicator: EntryIndicator::Filter,
             suffix: EntrySuffix::Date(date_suffix),
+            depth: 0,
+            body: body_marker(app, entry),
         },
     )
 }
@@ -219,7 +298,11 @@ pub fn build_edit_rows_with_prefix_width(
     suffix: Option<Span<'static>>,
 ) -> Vec<RowModel> {
     let wrap_width = text_width.saturating_sub(1).max(1);
-    let wrapped = wrap_text(text, wrap_width);
+    // Body lines after the first start rows of their own
+    let wrapped: Vec<String> = text
+        .split('\n')
+        .flat_map(|line| wrap_text(line, wrap_width))
+        .collect();
 
     if wrapped.is_empty() {
         return vec![RowModel::new(
@@ -253,6 +336,12 @@ pub fn build_message_row(message: &str, style: Style) -> RowModel {
     RowModel::from_spans(vec![Span::styled(message.to_string(), style)])
 }
 
+/// Leading whitespace for an entry nested `depth` levels deep.
+#[must_use]
+pub fn nesting_indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
 fn split_prefix(prefix: &str) -> (String, String) {
     let mut chars = prefix.chars();
     let first_char = chars.next().unwrap_or('-');
@@ -320,11 +409,13 @@ impl<'a> IndicatorResolver<'a> {
     ) -> Span<'static> {
         let indicator = match kind {
             SourceType::Recurring => theme::GLYPH_PROJECTED_RECURRING,
+            SourceType::Scheduled => theme::GLYPH_PROJECTED_SCHEDULED,
+            SourceType::Overdue => theme::GLYPH_PROJECTED_OVERDUE,
             SourceType::Local => unreachable!("projected entries are never Local"),
             SourceType::Calendar { .. } => theme::GLYPH_PROJECTED_CALENDAR,
         };
 
-        if is_cursor {
+        if is_cursor || matches!(kind, SourceType::Overdue) {
             Span::styled(indicator, Style::default().fg(theme::PROJECTED_DATE))
         } else {
             Span::styled(indicator.to_string(), content_style)
@@ -8,20 +8,40 @@ use super::theme;
 use unicode_width::UnicodeWidthStr;
 
 use crate::storage::{
-    EntryType, LAST_TRAILING_TAG_REGEX, RECURRING_REGEX, TAG_REGEX, TRAILING_TAGS_REGEX,
+    EntryType, LAST_TRAILING_TAG_REGEX, PRIORITY_REGEX, RECURRING_REGEX, SCHEDULED_REGEX,
+    TAG_REGEX, TRAILING_TAGS_REGEX, TaskState,
 };
 
 #[must_use]
 pub fn entry_style(entry_type: &EntryType) -> Style {
     match entry_type {
-        EntryType::Task { completed: true } => {
-            Style::default().add_modifier(ratatui::style::Modifier::DIM)
-        }
+        EntryType::Task {
+            state: TaskState::Done | TaskState::Migrated,
+        } => Style::default().add_modifier(ratatui::style::Modifier::DIM),
+        EntryType::Task {
+            state: TaskState::Cancelled,
+        } => Style::default()
+            .add_modifier(ratatui::style::Modifier::DIM | ratatui::style::Modifier::CROSSED_OUT),
         EntryType::Event => Style::default().add_modifier(ratatui::style::Modifier::ITALIC),
         _ => Style::default(),
     }
 }
 
+/// Style for an entry's `[ ]` marker, colored for tasks that are in progress
+/// or blocked so they stand out from open tasks.
+#[must_use]
+pub fn marker_style(entry_type: &EntryType, content_style: Style) -> Style {
+    match entry_type {
+        EntryType::Task {
+            state: TaskState::InProgress,
+        } => content_style.fg(theme::TASK_IN_PROGRESS),
+        EntryType::Task {
+            state: TaskState::Blocked,
+        } => content_style.fg(theme::TASK_BLOCKED),
+        _ => content_style,
+    }
+}
+
 #[must_use]
 pub fn format_date_suffix(date: NaiveDate) -> (String, usize) {
     let suffix = format!(" ({})", date.format("%m/%d"));
@@ -86,6 +106,8 @@ pub fn style_content(text: &str, base_style: Style) -> Vec<Span<'static>> {
 
     collect_matches(&TAG_REGEX, theme::TAG, &mut matches);
     collect_matches(&RECURRING_REGEX, theme::PROJECTED_DATE, &mut matches);
+    collect_matches(&SCHEDULED_REGEX, theme::PROJECTED_DATE, &mut matches);
+    collect_matches(&PRIORITY_REGEX, theme::PRIORITY, &mut matches);
 
     matches.sort_by_key(|(start, _, _)| *start);
 
@@ -16,6 +16,11 @@ pub const FILTER_BORDER: Color = Color::Magenta;
 // Content highlighting
 pub const TAG: Color = Color::Yellow;
 pub const PROJECTED_DATE: Color = Color::Red;
+pub const PRIORITY: Color = Color::LightRed;
+
+// Task state markers
+pub const TASK_IN_PROGRESS: Color = Color::Cyan;
+pub const TASK_BLOCKED: Color = Color::Red;
 
 // Confirm dialog
 pub const CONFIRM_BORDER: Color = Color::Blue;
@@ -24,6 +29,8 @@ pub const CONFIRM_NO: Color = Color::Red;
 
 // Command palette
 pub const PALETTE_ACCENT: Color = Color::Cyan;
+pub const DIFF_ADDED: Color = Color::Green;
+pub const DIFF_REMOVED: Color = Color::Red;
 
 // Border colors
 pub const BORDER_DEFAULT: Color = Color::White;
@@ -104,6 +111,10 @@ pub const GLYPH_UNSELECTED: &str = "○";
 pub const GLYPH_REORDER: &str = "↕";
 pub const GLYPH_PROJECTED_RECURRING: &str = "↺";
 pub const GLYPH_PROJECTED_CALENDAR: &str = "○";
+pub const GLYPH_PROJECTED_SCHEDULED: &str = "»";
+pub const GLYPH_PROJECTED_OVERDUE: &str = "!";
+pub const GLYPH_BODY_COLLAPSED: &str = "▸";
+pub const GLYPH_BODY_EXPANDED: &str = "▾";
 
 // Default color cycle for calendars (based on config order)
 pub const CALENDAR_COLORS: &[Color] = &[
@@ -160,9 +171,15 @@ pub const LABEL_MODE_FILTER: &str = "Filter";
 pub const LABEL_TAB_COMMANDS: &str = "Commands";
 pub const LABEL_TAB_PROJECTS: &str = "Projects";
 pub const LABEL_TAB_TAGS: &str = "Tags";
+pub const LABEL_TAB_BACKUPS: &str = "Backups";
+pub const LABEL_TAB_UNDO_HISTORY: &str = "Undo History";
+pub const LABEL_TAB_COMMITS: &str = "Commits";
 pub const LABEL_EMPTY_COMMANDS: &str = "No commands available";
 pub const LABEL_EMPTY_PROJECTS: &str = "No projects registered";
 pub const LABEL_EMPTY_TAGS: &str = "No tags found";
+pub const LABEL_EMPTY_BACKUPS: &str = "No backups yet";
+pub const LABEL_EMPTY_UNDO_HISTORY: &str = "Nothing to undo";
+pub const LABEL_EMPTY_COMMITS: &str = "No commits on this day";
 
 // UI Labels - Confirm dialogs
 pub const LABEL_CONFIRM_YES: &str = "[Y]";
@@ -175,10 +192,19 @@ pub const LABEL_CANNOT_UNDO: &str = "This cannot be undone.";
 pub const TITLE_CREATE_PROJECT: &str = " Create Project Journal ";
 pub const TITLE_DELETE_TAG: &str = " Delete Tag ";
 pub const TITLE_REMOVE_FROM_COMPLETED: &str = " Remove from Completed ";
+pub const TITLE_SAVE_CONFLICT: &str = " Journal Changed on Disk ";
+pub const TITLE_RESTORE_BACKUP: &str = " Restore Backup ";
+pub const TITLE_REFORMAT_JOURNAL: &str = " Reformat Journal ";
+pub const TITLE_CHECK_JOURNAL: &str = " Check Journal ";
+pub const TITLE_ARCHIVE: &str = " Archive ";
 
 // UI Labels - Confirm dialog messages
 pub const MSG_NO_PROJECT_JOURNAL: &str = "No project journal found.";
 pub const MSG_CREATE_PROJECT_JOURNAL: &str = "Create .corner/journal.md?";
+pub const MSG_KEEP_YOUR_VERSION: &str = "Keep your version? No keeps the one on disk.";
+pub const MSG_RESTORE_BACKUP: &str = "Replace the journal with this backup?";
+pub const MSG_RESTORE_BACKS_UP_FIRST: &str = "The current journal is backed up first.";
+pub const MSG_JOURNAL_BACKED_UP_FIRST: &str = "The journal is backed up first.";
 
 // UI Labels - Daily view
 #[must_use]
@@ -75,7 +75,13 @@ pub fn build_view_model(app: &App, context: &RenderContext, prep: RenderPrep) ->
             InputMode::CommandPalette(state) => Some(CommandPaletteModel::new(
                 state,
                 &app.cached_journal_tags,
-                app.journal_context.project_path(),
+                app.journal_context
+                    .project_path()
+                    .map(|journal| journal.path()),
+                &app.cached_backups,
+                &app.backup_preview,
+                &app.executor.undo_steps().collect::<Vec<_>>(),
+                &app.cached_commits,
             )),
             _ => None,
         },
@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use tempfile::TempDir;
+
+use corner::cli::{self, CliCommand, QueryFormat};
+use corner::config::Config;
+use corner::storage::{self, Journal, JournalContext, JournalSlot};
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()
+}
+
+fn hub_context(temp_dir: &TempDir, content: &str) -> JournalContext {
+    let journal_path = temp_dir.path().join("test_journal.md");
+    std::fs::write(&journal_path, content).expect("Failed to write journal");
+    JournalContext::new(Journal::new(journal_path), None, JournalSlot::Hub)
+}
+
+#[test]
+fn add_and_list_without_tui() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(&temp_dir, "# 2026/01/15\n- [ ] Existing task\n");
+    let config = Config {
+        favorite_tags: HashMap::from([("1".to_string(), "bug".to_string())]),
+        filters: HashMap::from([("bugs".to_string(), "!tasks #bug".to_string())]),
+        ..Config::default()
+    };
+
+    let args: Vec<String> = ["add", "fix", "flaky", "test", "#1"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let command = cli::parse_command(&args).unwrap().unwrap();
+    assert_eq!(command, CliCommand::Add("fix flaky test #1".to_string()));
+    cli::run(&command, &config, &context, today(), &mut Vec::new()).unwrap();
+
+    let add_note = CliCommand::Add("- Standup notes".to_string());
+    cli::run(&add_note, &config, &context, today(), &mut Vec::new()).unwrap();
+
+    let journal = std::fs::read_to_string(context.active_path()).unwrap();
+    assert!(journal.contains("- [ ] Existing task\n- [ ] fix flaky test #bug\n- Standup notes"));
+
+    let mut out = Vec::new();
+    let list = CliCommand::List("$bugs".to_string());
+    cli::run(&list, &config, &context, today(), &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "2026/01/15 - [ ] fix flaky test #bug\n"
+    );
+
+    let unknown = CliCommand::List("$missing".to_string());
+    assert!(cli::run(&unknown, &config, &context, today(), &mut Vec::new()).is_err());
+}
+
+#[test]
+fn query_exports_addressable_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(
+        &temp_dir,
+        "# 2026/01/14\n- [ ] Review PR #work\n- Just a note\n- [ ] Standup @every-weekday\n",
+    );
+    let config = Config::default();
+
+    let args: Vec<String> = ["query", "--jsonl", "!tasks"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let command = cli::parse_command(&args).unwrap().unwrap();
+    let mut out = Vec::new();
+    cli::run(&command, &config, &context, today(), &mut out).unwrap();
+
+    let output = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(record["entry_type"], "task");
+    assert_eq!(record["completed"], false);
+    assert_eq!(record["content"], "Review PR #work");
+    assert_eq!(record["tags"], serde_json::json!(["work"]));
+    assert_eq!(record["source_date"], "2026-01-14");
+    assert_eq!(record["line_index"], 0);
+    assert!(record["recurring"].is_null());
+
+    let recurring = CliCommand::Query {
+        query: "@recurring".to_string(),
+        format: QueryFormat::Json,
+    };
+    let mut out = Vec::new();
+    cli::run(&recurring, &config, &context, today(), &mut out).unwrap();
+    let records: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(records[0]["recurring"], "@every-weekday");
+    assert_eq!(records[0]["line_index"], 2);
+}
+
+#[test]
+fn check_reports_problems_and_fixes_day_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(
+        &temp_dir,
+        "- [ ] Stray task\n\n# 2026/01/15\r\n- [ ] Later day\r\n\n# 2026/01/14\n- [ ] Earlier <!-- done: yesterday -->\n\n# 2026/01/15\n- Second section <!-- done later -->\n",
+    );
+    let config = Config::default();
+    let path = context.active_path().display().to_string();
+
+    let args = vec!["check".to_string()];
+    let command = cli::parse_command(&args).unwrap().unwrap();
+    assert_eq!(command, CliCommand::Check { fix: false });
+    let mut out = Vec::new();
+    let err = cli::run(&command, &config, &context, today(), &mut out).unwrap_err();
+    assert!(err.to_string().contains("--fix"));
+    let expected = [
+        "1: entry before the first day header",
+        "3: CRLF line ending among LF lines",
+        "4: CRLF line ending among LF lines",
+        "6: 2026/01/14 comes after 2026/01/15",
+        "7: unreadable done comment",
+        "9: 2026/01/15 already has a header on line 3",
+    ];
+    let expected: Vec<String> = expected.iter().map(|p| format!("{path}:{p}")).collect();
+    assert_eq!(
+        String::from_utf8(out).unwrap().lines().collect::<Vec<_>>(),
+        expected
+    );
+
+    // Days are sorted and merged; problems without one right answer remain
+    let mut out = Vec::new();
+    let fix = CliCommand::Check { fix: true };
+    assert!(cli::run(&fix, &config, &context, today(), &mut out).is_err());
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!(
+            "Fixed 4 problems\n{path}:1: entry before the first day header\n{path}:4: unreadable done comment\n"
+        )
+    );
+    assert_eq!(
+        std::fs::read_to_string(context.active_path()).unwrap(),
+        "- [ ] Stray task\n\n# 2026/01/14\n- [ ] Earlier <!-- done: yesterday -->\n\n# 2026/01/15\n- [ ] Later day\n- Second section <!-- done later -->\n"
+    );
+}
+
+#[test]
+fn import_maps_todo_txt_and_taskwarrior_tasks_onto_days() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(&temp_dir, "# 2026/01/10\n- [ ] Existing task\n");
+    let config = Config::default();
+
+    let todo = temp_dir.path().join("todo.txt");
+    std::fs::write(
+        &todo,
+        "(A) 2026-01-10 Call the bank +Finance @phone due:2026-01-20\n\
+         x 2026-01-12 2026-01-11 Renew passport +Travel pri:B\n\
+         Hidden chore h:1\n\
+         Water plants\n",
+    )
+    .unwrap();
+    let args: Vec<String> = ["import", "--dry-run", todo.to_str().unwrap()]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let command = cli::parse_command(&args).unwrap().unwrap();
+    let mut out = Vec::new();
+    cli::run(&command, &config, &context, today(), &mut out).unwrap();
+    let path = context.active_path().display().to_string();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!(
+            "2026/01/10 - [ ] Call the bank !1 @due-2026/01/20 #Finance #phone\n\
+             2026/01/11 - [x] Renew passport !2 #Travel <!-- done: 2026-01-12 -->\n\
+             2026/01/15 - [ ] Water plants\n\
+             Would import 3 tasks into 3 days of {path}\n\
+             Skipped line 3: hidden task\n"
+        )
+    );
+    assert_eq!(
+        std::fs::read_to_string(context.active_path()).unwrap(),
+        "# 2026/01/10\n- [ ] Existing task\n"
+    );
+
+    // A --format without a value, an unknown option or a second file is an
+    // error rather than a guess at what was meant
+    let todo = todo.to_str().unwrap();
+    for args in [
+        vec!["import", todo, "--format", "--dry-run"],
+        vec!["import", todo, "--fromat", "todotxt"],
+        vec!["import", todo, "other.txt"],
+    ] {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        let err = cli::parse_command(&args).unwrap().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    let export = temp_dir.path().join("export.json");
+    std::fs::write(
+        &export,
+        r#"[
+  {"description": "Fix the gate", "status": "pending", "entry": "20260113T120000Z",
+   "project": "Home.Garden", "tags": ["weekend"], "priority": "H",
+   "annotations": [{"entry": "20260113T121500Z", "description": "Hinge is rusted"}]},
+  {"description": "Old idea", "status": "deleted", "entry": "20260101T120000Z"}
+]"#,
+    )
+    .unwrap();
+    for _ in 0..2 {
+        let import = CliCommand::Import {
+            file: export.display().to_string(),
+            format: None,
+            dry_run: false,
+            hub: false,
+        };
+        cli::run(&import, &config, &context, today(), &mut Vec::new()).unwrap();
+    }
+    // Importing twice adds each task once
+    assert_eq!(
+        std::fs::read_to_string(context.active_path()).unwrap(),
+        "# 2026/01/10\n- [ ] Existing task\n\n# 2026/01/13\n- [ ] Fix the gate !1 #Home-Garden #weekend\n  Hinge is rusted\n"
+    );
+}
+
+#[test]
+fn export_ics_writes_events_tasks_and_recurrences_with_stable_uids() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(
+        &temp_dir,
+        "# 2026/01/12\n\
+         * Team offsite @on-01/14\n\
+         - [ ] Send report !2 @due-01/16\n  Include Q4 numbers\n\
+         - [x] Filed taxes\n\
+         - Plain note\n\
+         - [ ] Standup @every-weekday\n\
+         \n# 2026/01/13\n\
+         * Dentist, 3pm; bring card\n\
+         - [/] Review PR @every-31-count-3\n",
+    );
+    let mut config = Config::default();
+
+    let command = cli::parse_command(&["export-ics".to_string()])
+        .unwrap()
+        .unwrap();
+    let mut out = Vec::new();
+    cli::run(&command, &config, &context, today(), &mut out).unwrap();
+    let feed = String::from_utf8(out).unwrap();
+    assert!(feed.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(feed.ends_with("END:VCALENDAR\r\n"));
+    let components: Vec<&str> = feed.split("BEGIN:V").skip(2).collect();
+    assert_eq!(components.len(), 5);
+
+    let offsite = components[0];
+    assert!(offsite.starts_with("EVENT\r\n"));
+    assert!(offsite.contains("DTSTART;VALUE=DATE:20260114\r\nDTEND;VALUE=DATE:20260115\r\n"));
+    assert!(offsite.contains("SUMMARY:Team offsite\r\n"));
+
+    let report = components[1];
+    assert!(report.starts_with("TODO\r\n"));
+    assert!(report.contains("DTSTART;VALUE=DATE:20260112\r\nDUE;VALUE=DATE:20260116\r\n"));
+    assert!(report.contains("SUMMARY:Send report\r\nDESCRIPTION:Include Q4 numbers\r\n"));
+    assert!(report.contains("STATUS:NEEDS-ACTION\r\nPRIORITY:5\r\n"));
+
+    let standup = components[2];
+    assert!(standup.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\r\nSUMMARY:Standup\r\n"));
+    assert!(components[3].contains("SUMMARY:Dentist\\, 3pm\\; bring card\r\n"));
+    // The first occurrence of a month-end recurrence is its DTSTART
+    let review = components[4];
+    assert!(review.contains("DTSTART;VALUE=DATE:20260131\r\n"));
+    assert!(review.contains("RRULE:FREQ=MONTHLY;BYMONTHDAY=28,29,30,31;BYSETPOS=-1;COUNT=3\r\n"));
+    assert!(review.contains("STATUS:IN-PROCESS\r\n"));
+
+    // UIDs only change when their entry does
+    let uids = |feed: &str| -> Vec<String> {
+        feed.lines()
+            .filter(|line| line.starts_with("UID:"))
+            .map(ToString::to_string)
+            .collect()
+    };
+    let output = temp_dir.path().join("out.ics");
+    let to_file = cli::parse_command(&[
+        "export-ics".to_string(),
+        "--output".to_string(),
+        output.display().to_string(),
+    ])
+    .unwrap()
+    .unwrap();
+    cli::run(&to_file, &config, &context, today(), &mut Vec::new()).unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), feed);
+
+    // An --output without a file is an error rather than a print to stdout
+    let missing = cli::parse_command(&["export-ics".to_string(), "-o".to_string()])
+        .unwrap()
+        .unwrap();
+    let mut out = Vec::new();
+    let err = cli::run(&missing, &config, &context, today(), &mut out).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(out.is_empty());
+
+    // Commands that change the journal rewrite the feed beside it
+    config.ics_export = true;
+    let add = CliCommand::Add("* Dentist at 4pm".to_string());
+    cli::run(&add, &config, &context, today(), &mut Vec::new()).unwrap();
+    let written = std::fs::read_to_string(storage::ics_path(context.active_path())).unwrap();
+    let (before, after) = (uids(&feed), uids(&written));
+    assert_eq!(after.len(), 6);
+    assert_eq!(before[..], after[..5]);
+    assert!(written.contains("SUMMARY:Dentist at 4pm\r\n"));
+}
+
+#[test]
+fn report_lists_done_open_and_blocked_work_for_the_range() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(
+        &temp_dir,
+        "# 2026/01/10\n\
+         - [x] Finished late <!-- done: 2026-01-14 -->\n\
+         - [x] Finished long ago <!-- done: 2026-01-10 -->\n\
+         - [ ] Old open task\n\
+         - [ ] Prep demo @due-01/15\n\
+         \n# 2026/01/14\n\
+         - [x] Ship login fix #auth\n\
+         - [!] Deploy release\n\
+         - Waiting on design review #blocker\n\
+         - Plain note\n\
+         \n# 2026/01/15\n\
+         - [/] Write release notes\n\
+         - [-] Dropped idea\n",
+    );
+    let mut config = Config::default();
+
+    let command = cli::parse_command(&["report".to_string()])
+        .unwrap()
+        .unwrap();
+    let mut out = Vec::new();
+    cli::run(&command, &config, &context, today(), &mut out).unwrap();
+    let report = String::from_utf8(out).unwrap();
+    assert!(report.starts_with(
+        "## Hub\n\n\
+         **Done**\n- Finished late\n- Ship login fix #auth\n\n\
+         **Open**\n- Prep demo @due-01/15\n- Write release notes\n\n\
+         **Blockers**\n- Deploy release\n- Waiting on design review #blocker\n"
+    ));
+
+    config.report_template = Some("{journal} {start}-{end}\n{blockers}".to_string());
+    let command = cli::parse_command(&[
+        "report".to_string(),
+        "--from".to_string(),
+        "2026/01/15".to_string(),
+        "--text".to_string(),
+    ])
+    .unwrap()
+    .unwrap();
+    let mut out = Vec::new();
+    cli::run(&command, &config, &context, today(), &mut out).unwrap();
+    assert!(
+        String::from_utf8(out)
+            .unwrap()
+            .starts_with("Hub 2026/01/15-2026/01/15\n- none\n")
+    );
+
+    // A date flag without a value or an unknown option is refused while parsing
+    for args in [
+        vec!["report", "--from"],
+        vec!["report", "--to", "--text"],
+        vec!["report", "--form", "yesterday"],
+    ] {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        let err = cli::parse_command(&args).unwrap().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    // A range that ends before it starts is refused rather than reported empty
+    let command = CliCommand::Report {
+        from: Some("today".to_string()),
+        to: Some("yesterday".to_string()),
+        format: None,
+    };
+    let mut out = Vec::new();
+    let err = cli::run(&command, &config, &context, today(), &mut out).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn git_branch_tags_stamp_project_entries_and_commits_list_by_day() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .env("GIT_AUTHOR_DATE", "2026-01-15T10:30:00")
+            .env("GIT_COMMITTER_DATE", "2026-01-15T10:30:00")
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    std::fs::create_dir_all(repo.join(".corner")).unwrap();
+    git(&["init", "-q", "-b", "feature/login"]);
+
+    let hub_path = temp_dir.path().join("hub.md");
+    let project_path = repo.join(".corner").join("journal.md");
+    std::fs::write(&hub_path, "").unwrap();
+    std::fs::write(&project_path, "").unwrap();
+    let context = JournalContext::new(
+        Journal::new(hub_path),
+        Some(Journal::new(project_path)),
+        JournalSlot::Project,
+    );
+    let config = Config {
+        git_branch_tags: true,
+        ..Config::default()
+    };
+
+    let add = CliCommand::Add("Fix login redirect".to_string());
+    cli::run(&add, &config, &context, today(), &mut Vec::new()).unwrap();
+    let tagged = CliCommand::Add("Review session expiry #branch-feature-login".to_string());
+    cli::run(&tagged, &config, &context, today(), &mut Vec::new()).unwrap();
+
+    let journal = std::fs::read_to_string(context.active_path()).unwrap();
+    assert!(journal.contains("- [ ] Fix login redirect #branch-feature-login\n"));
+    assert!(journal.contains("- [ ] Review session expiry #branch-feature-login\n"));
+    assert!(!journal.contains("#branch-feature-login #branch-feature-login"));
+
+    let mut out = Vec::new();
+    let list = CliCommand::List("branch:feature/login".to_string());
+    cli::run(&list, &config, &context, today(), &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+
+    git(&[
+        "commit",
+        "-q",
+        "--allow-empty",
+        "-m",
+        "Handle expired sessions",
+    ]);
+    let root = storage::repo_root(context.active_path()).unwrap();
+    let commits = storage::commits_on(&root, today()).unwrap();
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].subject, "Handle expired sessions");
+    assert_eq!(commits[0].time, "10:30");
+    let next_day = today().succ_opt().unwrap();
+    assert!(storage::commits_on(&root, next_day).unwrap().is_empty());
+}
+
+#[test]
+fn list_queries_combine_with_or_groups_and_phrases() {
+    let temp_dir = TempDir::new().unwrap();
+    let context = hub_context(
+        &temp_dir,
+        "# 2026/01/15\n\
+         - [ ] Fix crash #bug\n\
+         - [ ] Flaky test #regression\n\
+         - [x] Old fix #bug\n\
+         - Notes from code review\n\
+         - Review the code\n\
+         - [ ] Page rotation #oncall\n\
+         - [ ] Deploy #work\n",
+    );
+    let config = Config {
+        filters: HashMap::from([("urgent".to_string(), "#bug | #regression".to_string())]),
+        ..Config::default()
+    };
+    let list = |query: &str| -> std::io::Result<Vec<String>> {
+        let mut out = Vec::new();
+        cli::run(
+            &CliCommand::List(query.to_string()),
+            &config,
+            &context,
+            today(),
+            &mut out,
+        )?;
+        Ok(String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| line.trim_start_matches("2026/01/15 ").to_string())
+            .collect())
+    };
+
+    assert_eq!(
+        list("#bug OR #regression").unwrap(),
+        [
+            "- [ ] Fix crash #bug",
+            "- [ ] Flaky test #regression",
+            "- [x] Old fix #bug"
+        ]
+    );
+    assert_eq!(
+        list("(#work | #oncall) !tasks").unwrap(),
+        ["- [ ] Page rotation #oncall", "- [ ] Deploy #work"]
+    );
+    assert_eq!(
+        list("\"code review\"").unwrap(),
+        ["- Notes from code review"]
+    );
+    assert_eq!(
+        list("#bug -(#bug !completed)").unwrap(),
+        ["- [ ] Fix crash #bug"]
+    );
+    // Saved filters with OR expand as a group
+    assert_eq!(
+        list("$urgent !tasks").unwrap(),
+        ["- [ ] Fix crash #bug", "- [ ] Flaky test #regression"]
+    );
+
+    let error = |query: &str| list(query).unwrap_err().to_string();
+    assert_eq!(
+        error("(#bug #work"),
+        "Invalid filter: Missing ) for this group (column 1)"
+    );
+    assert_eq!(
+        error("#bug OR"),
+        "Invalid filter: OR needs a filter on each side (column 6)"
+    );
+    assert_eq!(
+        error("#bug \"code"),
+        "Invalid filter: Missing closing quote (column 6)"
+    );
+    assert_eq!(
+        error("!tasks !bogus"),
+        "Invalid filter: Unknown entry type !bogus (column 8)"
+    );
+}
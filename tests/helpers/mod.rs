@@ -38,9 +38,9 @@ impl TestContext {
         let journal_path = temp_dir.path().join("test_journal.md");
         std::fs::write(&journal_path, "").expect("Failed to create journal");
 
-        let context = JournalContext::new(journal_path, None, JournalSlot::Hub);
-
         let config = Config::default();
+        let context = JournalContext::new(config.journal(journal_path), None, JournalSlot::Hub);
+
         let app = App::new_with_context(config, date, context, None, Surface::default())
             .expect("Failed to create app");
 
@@ -61,7 +61,7 @@ impl TestContext {
         let journal_path = temp_dir.path().join("test_journal.md");
         std::fs::write(&journal_path, content).expect("Failed to write journal");
 
-        let context = JournalContext::new(journal_path, None, JournalSlot::Hub);
+        let context = JournalContext::new(config.journal(journal_path), None, JournalSlot::Hub);
         let app = App::new_with_context(config, date, context, None, Surface::default())
             .expect("Failed to create app");
 
@@ -0,0 +1,502 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::time::Duration;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use corner::storage::{
+    ArchiveOptions, DayHeaderFormat, Journal, JournalLayout, Line, RawEntry, Recurrence,
+    RecurrenceEnd, RecurringPattern, archive_journal, archive_path, collect_filtered_entries,
+    collect_journal_tags, extract_day_content, extract_scheduled_date, journal_modified, load_day,
+    load_day_lines, load_journal, normalize_entry_structure, parse_filter_query, parse_lines,
+    reformat_journal, save_day, save_journal, scan_days_in_range, scan_recurring_in_range,
+    serialize_lines, toggle_entry_complete, update_day_content,
+};
+use tempfile::TempDir;
+
+const DAY_COUNT: u64 = 10_000;
+
+fn synthetic_journal(first: NaiveDate) -> String {
+    let mut journal = String::new();
+    for offset in 0..DAY_COUNT {
+        let date = first + Days::new(offset);
+        let _ = writeln!(journal, "# {}", date.format("%Y/%m/%d"));
+        let _ = writeln!(journal, "- [ ] Task for day {offset} #work");
+        let _ = writeln!(journal, "- Note {offset}");
+        let _ = writeln!(journal, "* Event {offset}\n");
+    }
+    journal
+}
+
+#[test]
+fn indexed_saves_match_full_rewrites() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = Journal::new(temp_dir.path().join("journal.md"));
+    let mut expected = "# 2026/01/10\n- [ ] A\n\n# 2026/01/20\n- [ ] B\n".to_string();
+    fs::write(&path, &expected).unwrap();
+
+    let edits = [
+        ("2026/01/15", "- [ ] Inserted between"),
+        ("2026/01/05", "- [ ] Inserted first"),
+        ("2026/01/25", "- [ ] Appended"),
+        ("2026/01/10", "- [x] A\n  - [ ] Child"),
+        ("2026/01/20", ""),
+        ("2026/01/05", ""),
+        ("2026/01/15", "* Replaced"),
+    ];
+    for (date, content) in edits {
+        let date = NaiveDate::parse_from_str(date, "%Y/%m/%d").unwrap();
+        save_day(date, &path, content).unwrap();
+        expected = update_day_content(&expected, date, content);
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+        assert_eq!(load_day(date, &path).unwrap(), content);
+    }
+
+    // External edits are picked up even when made between indexed calls
+    let external = "# 2026/02/01\n- [ ] Edited elsewhere\n";
+    fs::write(&path, external).unwrap();
+    assert_eq!(load_journal(&path).unwrap(), external);
+    let date = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+    assert_eq!(load_day(date, &path).unwrap(), "- [ ] Edited elsewhere");
+}
+
+#[test]
+fn indexed_day_access_on_large_journal_skips_file_reads() {
+    let first = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let path = Journal::new(temp_dir.path().join("journal.md"));
+    let journal = synthetic_journal(first);
+    fs::write(&path, &journal).unwrap();
+
+    let sample: Vec<NaiveDate> = (0..DAY_COUNT)
+        .step_by(97)
+        .map(|offset| first + Days::new(offset))
+        .collect();
+
+    let indexed: Vec<String> = sample
+        .iter()
+        .map(|date| load_day(*date, &path).unwrap())
+        .collect();
+    let naive: Vec<String> = sample
+        .iter()
+        .map(|date| extract_day_content(&journal, *date))
+        .collect();
+    assert_eq!(indexed, naive);
+
+    // Once indexed, loads don't read the file: a same-size edit that keeps its
+    // mtime goes unseen until the file's stamp changes
+    let write_keeping_stamp = |content: &str| {
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        fs::write(&path, content).unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(modified).unwrap();
+    };
+    write_keeping_stamp(&journal.replace("Task for day", "Task for dxy"));
+    let cached: Vec<String> = sample
+        .iter()
+        .map(|date| load_day(*date, &path).unwrap())
+        .collect();
+    assert_eq!(cached, naive);
+    write_keeping_stamp(&journal);
+
+    // Saves go through the index and still produce the same file as a full rewrite
+    let mut expected = journal;
+    for date in sample.iter().take(20) {
+        let content = format!("- [x] Rewritten {date}");
+        save_day(*date, &path, &content).unwrap();
+        expected = update_day_content(&expected, *date, &content);
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+
+    // Saves keep the index current rather than dropping it, so loads after
+    // them are still served without reading the file
+    write_keeping_stamp(&expected.replace("Rewritten", "Rewrxtten"));
+    for date in sample.iter().take(20) {
+        assert_eq!(
+            load_day(*date, &path).unwrap(),
+            format!("- [x] Rewritten {date}")
+        );
+    }
+    write_keeping_stamp(&expected);
+
+    let last = first + Days::new(DAY_COUNT - 1);
+    let scanned = scan_days_in_range(last - Days::new(41), last, &path).unwrap();
+    assert_eq!(scanned.len(), 42);
+    assert!(scanned.values().all(|info| info.has_incomplete_tasks));
+}
+
+#[test]
+fn directory_layouts_keep_each_period_in_its_own_file() {
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%Y/%m/%d").unwrap();
+    let cases = [
+        (
+            JournalLayout::Daily,
+            [
+                "2026/10/2026-10-16.md",
+                "2026/10/2026-10-17.md",
+                "2026/11/2026-11-02.md",
+            ],
+        ),
+        (
+            JournalLayout::Monthly,
+            ["2026/2026-10.md", "2026/2026-10.md", "2026/2026-11.md"],
+        ),
+    ];
+
+    for (layout, files) in cases {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Journal::new(temp_dir.path().join("journal")).with_layout(layout);
+
+        save_day(date("2026/10/16"), &root, "- [ ] First").unwrap();
+        save_day(date("2026/11/02"), &root, "- Third").unwrap();
+        save_day(date("2026/10/17"), &root, "- [ ] Second #work").unwrap();
+        for (day, file) in ["2026/10/16", "2026/10/17", "2026/11/02"].iter().zip(files) {
+            let content = fs::read_to_string(root.join(file)).unwrap();
+            assert!(content.contains(&format!("# {day}\n")), "{file}: {content}");
+        }
+        assert_eq!(
+            load_day(date("2026/10/17"), &root).unwrap(),
+            "- [ ] Second #work"
+        );
+
+        // The whole journal reads as one document in date order
+        let journal = load_journal(&root).unwrap();
+        assert_eq!(
+            journal,
+            "# 2026/10/16\n- [ ] First\n\n# 2026/10/17\n- [ ] Second #work\n\n# 2026/11/02\n- Third\n"
+        );
+
+        // Whole-journal rewrites only touch the files of days that changed
+        let november = root.join(files[2]);
+        let november_before = fs::metadata(&november).unwrap().modified().unwrap();
+        let before = journal_modified(&root).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        save_journal(&root, &journal.replace("#work", "#job")).unwrap();
+        assert_eq!(
+            load_day(date("2026/10/17"), &root).unwrap(),
+            "- [ ] Second #job"
+        );
+        assert_eq!(
+            fs::metadata(&november).unwrap().modified().unwrap(),
+            november_before
+        );
+        assert!(journal_modified(&root).unwrap() > before);
+
+        let scanned = scan_days_in_range(date("2026/10/01"), date("2026/10/31"), &root).unwrap();
+        assert_eq!(scanned.len(), 2);
+        assert!(scanned[&date("2026/10/16")].has_incomplete_tasks);
+
+        // Emptying a period removes its file and any directories left empty
+        save_day(date("2026/11/02"), &root, "").unwrap();
+        assert!(!november.exists());
+        assert!(!root.join("2026/11").exists());
+        assert!(root.join("2026").exists());
+    }
+}
+
+#[test]
+fn daily_notes_folder_keeps_note_content_around_entries() {
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%Y/%m/%d").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let root = Journal::new(temp_dir.path().join("Daily")).with_layout(JournalLayout::DailyNotes);
+    fs::create_dir_all(root.join(".obsidian")).unwrap();
+    fs::write(root.join(".obsidian/2026-10-01.md"), "- [ ] Hidden\n").unwrap();
+    fs::write(root.join("Ideas.md"), "- [ ] Not a day #work\n").unwrap();
+    let note = "---\ntags:\n  - daily\n---\n# 2026-10-15\n\nSlept well.\n\n## Tasks\n- [ ] Call the bank #work\n- [ ] Standup @every-weekday\n";
+    fs::write(root.join("2026-10-15.md"), note).unwrap();
+    fs::write(root.join("2026-10-16.md"), "- [ ] Send invoice #work\n").unwrap();
+
+    // Everything in a note but its front matter is the day's content; only
+    // list items are entries
+    let lines = load_day_lines(date("2026/10/15"), &root).unwrap();
+    let entries: Vec<&str> = lines
+        .iter()
+        .filter_map(|line| match line {
+            Line::Entry(entry) => Some(entry.content.as_str()),
+            Line::Raw(_) => None,
+        })
+        .collect();
+    assert_eq!(entries, ["Call the bank #work", "Standup @every-weekday"]);
+    assert!(
+        load_day(date("2026/10/15"), &root)
+            .unwrap()
+            .starts_with("# 2026-10-15\n")
+    );
+
+    // Saving a day rewrites only the entries, leaving the rest of the note alone
+    let content = load_day(date("2026/10/15"), &root).unwrap();
+    save_day(
+        date("2026/10/15"),
+        &root,
+        &content.replace("- [ ] Call", "- [x] Call"),
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(root.join("2026-10-15.md")).unwrap(),
+        note.replace("- [ ] Call", "- [x] Call")
+    );
+
+    // Filters, tags and recurring entries see every note, and only notes
+    let work = collect_filtered_entries(&parse_filter_query("#work").unwrap(), &root).unwrap();
+    let sources: Vec<NaiveDate> = work.iter().map(|entry| entry.source_date).collect();
+    assert_eq!(sources, [date("2026/10/15"), date("2026/10/16")]);
+    assert_eq!(collect_journal_tags(&root).unwrap(), ["work"]);
+    let recurring = scan_recurring_in_range(date("2026/10/19"), date("2026/10/20"), &root).unwrap();
+    assert_eq!(recurring.len(), 2);
+
+    // A date heading inside a note doesn't start another day
+    let journal = load_journal(&root).unwrap();
+    save_journal(&root, &journal.replace("Send invoice", "Send invoices")).unwrap();
+    assert_eq!(
+        fs::read_to_string(root.join("2026-10-16.md")).unwrap(),
+        "- [ ] Send invoices #work\n"
+    );
+    assert!(
+        fs::read_to_string(root.join("2026-10-15.md"))
+            .unwrap()
+            .contains("# 2026-10-15\n\nSlept well.")
+    );
+
+    // New days become new notes, and emptied notes are removed
+    save_day(date("2026/10/17"), &root, "- [ ] Plan the week").unwrap();
+    assert_eq!(
+        fs::read_to_string(root.join("2026-10-17.md")).unwrap(),
+        "- [ ] Plan the week\n"
+    );
+    save_day(date("2026/10/17"), &root, "").unwrap();
+    assert!(!root.join("2026-10-17.md").exists());
+
+    // Emptying a note's day leaves its front matter behind
+    save_day(date("2026/10/15"), &root, "").unwrap();
+    assert_eq!(
+        fs::read_to_string(root.join("2026-10-15.md")).unwrap(),
+        "---\ntags:\n  - daily\n---\n"
+    );
+    assert_eq!(load_day(date("2026/10/15"), &root).unwrap(), "");
+
+    // Logseq names its journal pages differently
+    let logseq = Journal::new(temp_dir.path().join("journals"))
+        .with_layout(JournalLayout::DailyNotes)
+        .with_daily_notes_format("%Y_%m_%d");
+    save_day(date("2026/10/16"), &logseq, "- [ ] Water plants").unwrap();
+    assert!(logseq.join("2026_10_16.md").exists());
+    assert_eq!(
+        collect_filtered_entries(&parse_filter_query("t").unwrap(), &logseq)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn archived_days_still_load_save_and_filter_through_the_journal() {
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%Y/%m/%d").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let journal = Journal::new(temp_dir.path().join("journal.md"));
+    fs::write(
+        &journal,
+        "# 2026/01/05\n- [x] Old done\n- Old note #work\n\n# 2026/01/06\n- [ ] Old open task\n\n# 2026/01/07\n- [ ] Standup @every-weekday\n\n# 2026/10/15\n- [ ] Recent #work\n",
+    )
+    .unwrap();
+
+    // Only finished days without recurring entries move by default
+    let mut options = ArchiveOptions {
+        before: date("2026/07/18"),
+        completed_only: true,
+        by_year: false,
+    };
+    assert_eq!(archive_journal(&journal, &options).unwrap(), 1);
+    let archive = archive_path(&journal, None);
+    assert_eq!(
+        fs::read_to_string(&archive).unwrap(),
+        "# 2026/01/05\n- [x] Old done\n- Old note #work\n"
+    );
+    assert!(!fs::read_to_string(&journal).unwrap().contains("2026/01/05"));
+
+    // Archived days load and save in place, and show up in the calendar
+    assert_eq!(
+        load_day(date("2026/01/05"), &journal).unwrap(),
+        "- [x] Old done\n- Old note #work"
+    );
+    save_day(
+        date("2026/01/05"),
+        &journal,
+        "- [x] Old done\n- Old note #job",
+    )
+    .unwrap();
+    assert!(fs::read_to_string(&archive).unwrap().contains("#job"));
+    assert!(!fs::read_to_string(&journal).unwrap().contains("2026/01/05"));
+    let scanned = scan_days_in_range(date("2026/01/01"), date("2026/01/31"), &journal).unwrap();
+    assert_eq!(scanned.len(), 3);
+
+    // Filters reach into the archive unless their range stops short of it
+    let filter = parse_filter_query("!notes").unwrap();
+    let notes = collect_filtered_entries(&filter, &journal).unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].source_journal, journal);
+    let filter = parse_filter_query("!notes 2026/10/01..").unwrap();
+    assert!(
+        collect_filtered_entries(&filter, &journal)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Yearly archives can take open tasks too
+    options.completed_only = false;
+    options.by_year = true;
+    assert_eq!(archive_journal(&journal, &options).unwrap(), 1);
+    assert!(archive_path(&journal, Some(2026)).exists());
+    assert_eq!(
+        load_day(date("2026/01/06"), &journal).unwrap(),
+        "- [ ] Old open task"
+    );
+    assert_eq!(
+        fs::read_to_string(&journal).unwrap(),
+        "# 2026/01/07\n- [ ] Standup @every-weekday\n\n# 2026/10/15\n- [ ] Recent #work\n"
+    );
+}
+
+#[test]
+fn configured_day_headers_are_written_read_and_reformatted() {
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%Y/%m/%d").unwrap();
+    assert!(DayHeaderFormat::new(2, "%b %-d").is_none());
+    assert!(DayHeaderFormat::new(7, "%Y-%m-%d").is_none());
+    let default = DayHeaderFormat::default();
+    assert_eq!(default.parse("# 2024/03/05"), Some(date("2024/03/05")));
+    for line in ["## 2024/03/05", "# 2024-03-05", "# 2024/03/05 retro notes"] {
+        assert_eq!(default.parse(line), None, "{line}");
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = Journal::new(temp_dir.path().join("journal.md"));
+    let long = DayHeaderFormat::new(1, "%A, %B %-d, %Y")
+        .unwrap()
+        .with_read_patterns(&["## %Y-%m-%d".to_string()]);
+    let path = path.with_day_header(long.clone());
+
+    // Legacy headers and read patterns start days; dated sub-headings don't
+    let iso_day = "- Iso\n### 2024-03-05 retro notes\n# 2026/10/14 (moved)\n- Retro";
+    fs::write(
+        &path,
+        format!("Preamble\n\n# 2026/10/14\n- [ ] Old\n\n## 2026-10-15\n{iso_day}\n"),
+    )
+    .unwrap();
+    assert_eq!(load_day(date("2026/10/14"), &path).unwrap(), "- [ ] Old");
+    assert_eq!(load_day(date("2026/10/15"), &path).unwrap(), iso_day);
+    assert_eq!(load_day(date("2024/03/05"), &path).unwrap(), "");
+
+    save_day(date("2026/10/16"), &path, "- New").unwrap();
+    assert!(
+        fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("# Friday, October 16, 2026\n- New\n")
+    );
+    assert_eq!(load_day(date("2026/10/16"), &path).unwrap(), "- New");
+
+    // Saving an existing day keeps its header as written
+    save_day(date("2026/10/15"), &path, "- Iso edited").unwrap();
+    assert!(
+        fs::read_to_string(&path)
+            .unwrap()
+            .contains("## 2026-10-15\n- Iso edited\n")
+    );
+    save_day(date("2026/10/15"), &path, iso_day).unwrap();
+
+    let changed = reformat_journal(&path, &long).unwrap();
+    assert_eq!(changed, 2);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!(
+            "Preamble\n\n# Wednesday, October 14, 2026\n- [ ] Old\n\n\
+             # Thursday, October 15, 2026\n{iso_day}\n\n\
+             # Friday, October 16, 2026\n- New\n"
+        )
+    );
+
+    // Reformatting to headers the journal can't read is refused
+    let dotted = DayHeaderFormat::new(3, "%d.%m.%Y").unwrap();
+    let before = fs::read_to_string(&path).unwrap();
+    assert!(reformat_journal(&path, &dotted).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+    // Read patterns, with their own heading level, let a journal move formats
+    let dotted = dotted.with_read_patterns(&["# %A, %B %-d, %Y".to_string()]);
+    let path = path.with_day_header(dotted.clone());
+    assert_eq!(reformat_journal(&path, &dotted).unwrap(), 3);
+    let iso = DayHeaderFormat::new(2, "%Y-%m-%d")
+        .unwrap()
+        .with_read_patterns(&["### %d.%m.%Y".to_string()]);
+    let path = path.with_day_header(iso.clone());
+    assert_eq!(reformat_journal(&path, &iso).unwrap(), 3);
+    let after = fs::read_to_string(&path).unwrap();
+    assert!(after.contains("## 2026-10-14\n- [ ] Old\n\n## 2026-10-15\n"));
+    assert!(after.contains("### 2024-03-05 retro notes\n# 2026/10/14 (moved)\n"));
+}
+
+#[test]
+fn counted_recurrences_end_at_their_last_occurrence() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let recurrence = |pattern, count| Recurrence {
+        pattern,
+        start: date(2026, 1, 15),
+        end: Some(RecurrenceEnd::Count(count)),
+    };
+    let weekly = recurrence(RecurringPattern::Weekly(Weekday::Fri), 3);
+    assert_eq!(weekly.last_date(), Some(date(2026, 1, 30)));
+
+    // A count longer than the scanned years ends after the occurrences found,
+    // not on the first day
+    let yearly = recurrence(RecurringPattern::Yearly(3, 5), 200);
+    let last = yearly.last_date().unwrap();
+    assert_eq!((last.month(), last.day()), (3, 5));
+    assert!(last.year() > 2070);
+    assert!(yearly.occurs_on(date(2027, 3, 5)));
+}
+
+#[test]
+fn only_parseable_scheduled_dates_are_moved_to_the_trailing_section() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+    assert!(extract_scheduled_date("ping foo@on-call", date).is_none());
+    assert!(extract_scheduled_date("I'm @on-call this week", date).is_none());
+    for content in ["ping foo@on-call about it", "I'm @on-call this week"] {
+        assert_eq!(
+            normalize_entry_structure(content),
+            (content.to_string(), None)
+        );
+    }
+    assert_eq!(
+        normalize_entry_structure("Pay rent @due-01/15 today"),
+        ("Pay rent today @due-01/15".to_string(), None)
+    );
+}
+
+#[test]
+fn tab_and_odd_width_indentation_survive_saves() {
+    let content =
+        "- [ ] Plan\n\t- [ ] Tabbed\n\t\tdetails\n\t\t- Deeper\n   - Three spaces\n     with body";
+    let lines = parse_lines(content);
+    assert_eq!(serialize_lines(&lines), content);
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = Journal::new(temp_dir.path().join("journal.md"));
+    let date = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+    save_day(date, &path, content).unwrap();
+    toggle_entry_complete(date, &path, 1, None).unwrap();
+    assert_eq!(
+        load_day(date, &path).unwrap(),
+        content.replace("[ ] Tabbed", "[x] Tabbed")
+    );
+}
+
+#[test]
+fn body_lines_that_look_like_entries_stay_in_the_body() {
+    let mut entry = RawEntry::new_task("Plan");
+    entry.set_text("Plan\n- first step\n  * an aside\n\\- already escaped\nplain");
+    let lines = vec![Line::Entry(entry)];
+
+    let written = serialize_lines(&lines);
+    assert_eq!(
+        written,
+        "- [ ] Plan\n  \\- first step\n    \\* an aside\n  \\\\- already escaped\n  plain"
+    );
+    assert_eq!(parse_lines(&written), lines);
+}
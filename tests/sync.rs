@@ -1,6 +1,7 @@
 mod helpers;
 
 use chrono::NaiveDate;
+use corner::app::{ConfirmContext, InputMode};
 use crossterm::event::KeyCode;
 use helpers::TestContext;
 
@@ -104,3 +105,103 @@ fn scroll_keeps_selection_visible() {
 
     ctx.verify_invariants();
 }
+
+#[test]
+fn external_edit_during_save_prompts_instead_of_overwriting() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/14\n- [ ] Yesterday\n\n# 2026/01/15\n- [ ] Mine\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Another instance changes a different day: saves merge without a prompt
+    let other_day = "# 2026/01/14\n- [ ] Yesterday edited\n\n# 2026/01/15\n- [ ] Mine\n";
+    std::fs::write(ctx.journal_path(), other_day).unwrap();
+    ctx.press(KeyCode::Char('i'));
+    ctx.press(KeyCode::End);
+    ctx.type_str(" one");
+    ctx.press(KeyCode::Enter);
+    assert!(matches!(ctx.app.input_mode, InputMode::Normal));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("Yesterday edited"));
+    assert!(journal.contains("- [ ] Mine one"));
+
+    // Another instance changes this day while we edit it
+    ctx.press(KeyCode::Char('i'));
+    ctx.press(KeyCode::End);
+    ctx.type_str(" two");
+    let theirs = ctx.read_journal().replace("Mine one", "Theirs");
+    std::fs::write(ctx.journal_path(), &theirs).unwrap();
+    ctx.press(KeyCode::Enter);
+
+    assert!(matches!(
+        ctx.app.input_mode,
+        InputMode::Confirm(ConfirmContext::SaveConflict(d)) if d == date
+    ));
+    assert_eq!(ctx.read_journal(), theirs);
+
+    // Declining keeps the version on disk
+    ctx.press(KeyCode::Char('n'));
+    assert_eq!(ctx.read_journal(), theirs);
+    assert!(ctx.screen_contains("Theirs"));
+    ctx.verify_invariants();
+
+    // Accepting writes ours over it
+    ctx.press(KeyCode::Char('i'));
+    ctx.press(KeyCode::End);
+    ctx.type_str(" three");
+    let theirs = ctx.read_journal().replace("Theirs", "Theirs again");
+    std::fs::write(ctx.journal_path(), &theirs).unwrap();
+    ctx.press(KeyCode::Enter);
+    ctx.press(KeyCode::Char('y'));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("- [ ] Theirs three"));
+    assert!(!journal.contains("Theirs again"));
+    assert!(ctx.screen_contains("Theirs three"));
+
+    // Writes leave no temp files behind
+    let leftovers: Vec<_> = std::fs::read_dir(ctx.temp_dir.path())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty());
+    ctx.verify_invariants();
+}
+
+#[test]
+fn external_edit_to_other_entries_merges_into_save() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Alpha\n- [ ] Beta\n- [ ] Gamma\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Edit Beta while another program completes Gamma and adds an entry
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('i'));
+    ctx.press(KeyCode::End);
+    ctx.type_str(" edited");
+    let theirs = "# 2026/01/15\n- [ ] Alpha\n- [ ] Beta\n- [x] Gamma\n- [ ] Delta\n";
+    std::fs::write(ctx.journal_path(), theirs).unwrap();
+    ctx.press(KeyCode::Enter);
+
+    assert!(matches!(ctx.app.input_mode, InputMode::Normal));
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/15\n- [ ] Alpha\n- [ ] Beta edited\n- [x] Gamma\n- [ ] Delta\n"
+    );
+    assert!(ctx.screen_contains("Beta edited"));
+    assert!(ctx.screen_contains("Delta"));
+    assert_eq!(ctx.entry_count(), 4);
+
+    // Adjacent edits on both sides still merge line by line
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('i'));
+    ctx.press(KeyCode::End);
+    ctx.type_str(" mine");
+    let theirs = ctx.read_journal().replace("Beta edited", "Beta theirs");
+    std::fs::write(ctx.journal_path(), theirs).unwrap();
+    ctx.press(KeyCode::Enter);
+    assert!(matches!(ctx.app.input_mode, InputMode::Normal));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("- [ ] Alpha mine\n- [ ] Beta theirs\n"));
+    ctx.verify_invariants();
+}
@@ -1,6 +1,13 @@
 mod helpers;
 
 use chrono::NaiveDate;
+use corner::app::{App, CommandPaletteMode, ConfirmContext, InputMode};
+use corner::config::Config;
+use corner::registry::COMMANDS;
+use corner::storage::{
+    EntryType, Journal, JournalContext, JournalSlot, Line, LineDiff, list_backups,
+};
+use corner::ui::surface::Surface;
 use crossterm::event::{KeyCode, KeyModifiers};
 use helpers::TestContext;
 
@@ -208,3 +215,617 @@ fn delete_tag_from_completed_preserves_incomplete_tasks() {
 
     ctx.verify_invariants();
 }
+
+#[test]
+fn scheduled_entries_project_and_become_overdue() {
+    let today = chrono::Local::now().date_naive();
+    let written = today - chrono::Days::new(5);
+    let due = today - chrono::Days::new(2);
+    let content = format!(
+        "# {}\n- [ ] Renew passport @due-{}\n- [ ] Call dentist @on-{}\n",
+        written.format("%Y/%m/%d"),
+        due.format("%Y/%m/%d"),
+        today.format("%Y/%m/%d"),
+    );
+    let mut ctx = TestContext::with_journal_content(today, &content);
+
+    // Overdue task leads the list, followed by the entry scheduled for today
+    let lines = ctx.render_daily();
+    let overdue = lines.iter().position(|l| l.contains("Renew passport"));
+    let scheduled = lines.iter().position(|l| l.contains("Call dentist"));
+    assert!(overdue.is_some() && overdue < scheduled);
+
+    ctx.app.quick_filter("@overdue").unwrap();
+    assert!(ctx.screen_contains("Renew passport"));
+    assert!(!ctx.screen_contains("Call dentist"));
+
+    // Completing the overdue projection completes the source entry
+    ctx.app.goto_day(today).unwrap();
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_journal().contains("- [x] Renew passport"));
+    assert!(!ctx.screen_contains("Renew passport"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn extended_recurrence_patterns_and_end_conditions() {
+    let date = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+    let content = "# 2026/01/06\n- [ ] Sprint planning @every-2w-tue\n- [ ] Pay invoices @every-last-fri\n- [ ] Stretch @every-day-count-3\n- [ ] Retro @every-thu-until-01/20\n";
+    let mut ctx = TestContext::with_journal_content(date(15), content);
+
+    // Thursday 01/15: within the until date; the count ran out on 01/08
+    assert!(ctx.screen_contains("Retro"));
+    assert!(!ctx.screen_contains("Stretch"));
+
+    ctx.app.goto_day(date(8)).unwrap();
+    assert!(ctx.screen_contains("Stretch"));
+
+    ctx.app.goto_day(date(13)).unwrap();
+    assert!(!ctx.screen_contains("Sprint planning"));
+    ctx.app.goto_day(date(20)).unwrap();
+    assert!(ctx.screen_contains("Sprint planning"));
+
+    ctx.app.goto_day(date(23)).unwrap();
+    assert!(!ctx.screen_contains("Pay invoices"));
+    ctx.app.goto_day(date(30)).unwrap();
+    assert!(ctx.screen_contains("Pay invoices"));
+
+    let dates = corner::storage::scan_recurring_in_range(date(16), date(31), ctx.app.active_path())
+        .unwrap();
+    assert!(!dates.contains(&date(22)));
+    assert!(dates.contains(&date(20)) && dates.contains(&date(30)));
+
+    // Completing an occurrence is tracked per date like any recurring entry
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(
+        ctx.read_journal()
+            .contains("Pay invoices @every-last-fri <!-- done: 2026-01-30 -->")
+    );
+    ctx.verify_invariants();
+}
+
+#[test]
+fn nested_entries_keep_indentation_and_carry_children() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Launch\n  - [ ] Write docs\n  - [x] Fix bug\n    - Repro steps\n- [ ] Other\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    let launch = ctx.find_line("Launch").unwrap();
+    assert!(launch.ends_with("Launch 1/2"));
+    assert!(
+        ctx.find_line("Repro steps")
+            .unwrap()
+            .starts_with("    - Repro")
+    );
+
+    // Toggling a parent carries its subtasks, and indentation survives the save
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_journal().contains(
+        "- [x] Launch\n  - [x] Write docs\n  - [x] Fix bug\n    - Repro steps\n- [ ] Other"
+    ));
+    assert!(ctx.screen_contains("Launch 2/2"));
+
+    // Deleting a parent removes the subtree; undo restores it in place
+    ctx.press(KeyCode::Char('d'));
+    assert!(!ctx.read_journal().contains("Write docs"));
+    assert!(ctx.read_journal().contains("- [ ] Other"));
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.read_journal().contains(
+        "- [x] Launch\n  - [x] Write docs\n  - [x] Fix bug\n    - Repro steps\n- [ ] Other"
+    ));
+
+    // Moving a nested entry takes its children and re-roots them
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('>'));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("# 2026/01/16\n- [x] Fix bug\n  - Repro steps"));
+    assert!(ctx.screen_contains("Launch 1/1"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn backups_snapshot_before_tag_delete_and_restore_from_palette() {
+    let backup_dir = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        backup_count: 2,
+        backup_dir: Some(backup_dir.path().to_string_lossy().into_owned()),
+        ..Config::default()
+    };
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Ship it #release\n- [ ] Write notes\n";
+    let mut ctx = TestContext::with_config_and_content(date, content, config);
+    let journal_path = ctx.journal_path();
+    let backups = || list_backups(&journal_path, backup_dir.path()).unwrap();
+
+    // Opening the journal takes the daily snapshot
+    assert_eq!(backups().len(), 1);
+    assert_eq!(backups()[0].reason, "daily");
+    assert_eq!(backups()[0].content().unwrap(), content);
+
+    ctx.app.confirm_delete_tag("release").unwrap();
+    assert!(!ctx.read_journal().contains("#release"));
+    // The pre-delete journal matches the daily snapshot, so no duplicate is kept
+    assert_eq!(backups().len(), 1);
+
+    ctx.app.open_palette(CommandPaletteMode::Backups);
+    assert_eq!(ctx.app.cached_backups.len(), 1);
+    assert!(
+        ctx.app
+            .backup_preview
+            .contains(&LineDiff::Added("- [ ] Ship it #release".to_string()))
+    );
+
+    ctx.press(KeyCode::Enter);
+    assert!(matches!(
+        ctx.app.input_mode,
+        InputMode::Confirm(ConfirmContext::RestoreBackup(_))
+    ));
+    ctx.press(KeyCode::Char('y'));
+    assert_eq!(ctx.read_journal(), content);
+    assert!(ctx.screen_contains("Ship it #release"));
+
+    // The journal replaced by the restore was snapshotted, within the rotation limit
+    let after = backups();
+    assert_eq!(after.len(), 2);
+    assert_eq!(after[0].reason, "before-restore");
+    assert!(!after[0].content().unwrap().contains("#release"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn undo_history_survives_navigation_and_restart() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Keep\n- [ ] Oops\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('d'));
+    assert!(!ctx.read_journal().contains("Oops"));
+
+    // Changing day no longer discards the step
+    ctx.press(KeyCode::Char('h'));
+    ctx.press(KeyCode::Enter);
+    ctx.type_str("Other day");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.read_journal().contains("Other day"));
+
+    // Another line of the 15th is completed outside Corner
+    let journal = ctx.read_journal().replace("- [ ] Keep", "- [x] Keep");
+    std::fs::write(ctx.journal_path(), journal).unwrap();
+
+    // Restarting loads the history saved beside the journal
+    let context = JournalContext::new(Journal::new(ctx.journal_path()), None, JournalSlot::Hub);
+    ctx.app =
+        App::new_with_context(Config::default(), date, context, None, Surface::default()).unwrap();
+
+    ctx.app.open_palette(CommandPaletteMode::UndoHistory);
+    let steps: Vec<_> = ctx
+        .app
+        .executor
+        .undo_steps()
+        .map(|step| step.description.past.clone())
+        .collect();
+    assert_eq!(steps, ["Created entry", "Deleted entry"]);
+
+    // Undo back to and including the delete
+    ctx.press(KeyCode::Down);
+    ctx.press(KeyCode::Enter);
+    assert!(matches!(ctx.app.input_mode, InputMode::Normal));
+
+    let journal = ctx.read_journal();
+    assert!(!journal.contains("Other day"));
+    assert!(journal.contains("- [x] Keep\n- [ ] Oops"));
+    assert!(ctx.screen_contains("Oops"));
+    assert!(!ctx.app.executor.can_undo());
+
+    // Redo replays the steps in order
+    ctx.press(KeyCode::Char('U'));
+    assert!(!ctx.read_journal().contains("Oops"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn pasted_entries_undo_after_navigation_and_restart() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Keep\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.app
+        .paste_entries_from_text("- [ ] First paste\n- Second paste")
+        .unwrap();
+    assert!(
+        ctx.read_journal()
+            .contains("- [ ] Keep\n- [ ] First paste\n- Second paste")
+    );
+
+    // Leaving the day drops the live reverse action
+    ctx.press(KeyCode::Char('h'));
+    ctx.press(KeyCode::Char('l'));
+    ctx.press(KeyCode::Char('u'));
+    assert_eq!(ctx.read_journal(), content);
+
+    // A restart keeps the redo step, which pastes the entries back
+    let context = JournalContext::new(Journal::new(ctx.journal_path()), None, JournalSlot::Hub);
+    ctx.app =
+        App::new_with_context(Config::default(), date, context, None, Surface::default()).unwrap();
+    ctx.press(KeyCode::Char('U'));
+    assert!(
+        ctx.read_journal()
+            .contains("- [ ] Keep\n- [ ] First paste\n- Second paste")
+    );
+    assert!(ctx.screen_contains("Second paste"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn extended_task_states_mark_filter_and_tidy() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Plan\n- [/] Build\n- [X] Ship\n- [!] Review\n- [-] Dropped\n- [>] Moved\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Every state parses as a task rather than a note
+    assert!(ctx.app.lines.iter().all(|line| matches!(
+        line,
+        Line::Entry(entry) if matches!(entry.entry_type, EntryType::Task { .. })
+    )));
+
+    ctx.app.quick_filter("!tasks").unwrap();
+    assert!(ctx.screen_contains("Plan") && ctx.screen_contains("Build"));
+    assert!(ctx.screen_contains("Review") && !ctx.screen_contains("Ship"));
+    assert!(!ctx.screen_contains("Dropped") && !ctx.screen_contains("Moved"));
+    ctx.app.quick_filter("!active !cancelled").unwrap();
+    assert!(ctx.screen_contains("Build") && ctx.screen_contains("Dropped"));
+    assert!(!ctx.screen_contains("Plan") && !ctx.screen_contains("Moved"));
+
+    // `m` cycles the task state and undoes like any other change
+    ctx.app.goto_day(date).unwrap();
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('m'));
+    assert!(ctx.read_journal().contains("- [/] Plan"));
+    ctx.press(KeyCode::Char('m'));
+    assert!(ctx.read_journal().contains("- [!] Plan"));
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.read_journal().contains("- [/] Plan"));
+
+    // Palette commands set a state directly
+    let index = COMMANDS
+        .iter()
+        .position(|command| command.name == "mark-cancelled")
+        .unwrap();
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('q'));
+    for _ in 0..index {
+        ctx.press(KeyCode::Down);
+    }
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.read_journal().contains("- [-] Build"));
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.read_journal().contains("- [/] Build"));
+
+    // Unlisted states tidy with open or completed tasks
+    ctx.press(KeyCode::Char('s'));
+    assert!(ctx.read_journal().contains(
+        "# 2026/01/15\n- [X] Ship\n- [-] Dropped\n- [>] Moved\n- [/] Plan\n- [/] Build\n- [!] Review\n"
+    ));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn priorities_filter_sort_and_tidy() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/14\n- [ ] Old chore !3\n\n# 2026/01/15\n- [ ] Plain\n- [ ] Changelog !3\n- [x] Shipped !1\n- [ ] Crash !1\n- Wow!1 not a priority\n";
+    let config = Config {
+        tidy_order: ["prio", "uncompleted", "notes", "completed"]
+            .map(String::from)
+            .to_vec(),
+        ..Config::default()
+    };
+    let mut ctx = TestContext::with_config_and_content(date, content, config);
+
+    ctx.app.quick_filter("!p1 !p3").unwrap();
+    assert!(ctx.screen_contains("Crash") && ctx.screen_contains("Changelog"));
+    assert!(!ctx.screen_contains("Plain") && !ctx.screen_contains("Wow"));
+
+    // Priority first, then date
+    ctx.app.quick_filter("!tasks @by-priority").unwrap();
+    let lines = ctx.render_filter();
+    let row = |text: &str| lines.iter().position(|l| l.contains(text)).unwrap();
+    assert!(row("Crash") < row("Old chore"));
+    assert!(row("Old chore") < row("Changelog"));
+    assert!(row("Changelog") < row("Plain"));
+
+    // Closed tasks stay out of the prio bucket
+    ctx.app.goto_day(date).unwrap();
+    ctx.press(KeyCode::Char('s'));
+    assert!(ctx.read_journal().contains(
+        "# 2026/01/15\n- [ ] Crash !1\n- [ ] Changelog !3\n- [ ] Plain\n- Wow!1 not a priority\n- [x] Shipped !1\n"
+    ));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn completing_tasks_records_completion_date_for_done_filter() {
+    let today = chrono::Local::now().date_naive();
+    let written = today - chrono::Days::new(20);
+    let content = format!(
+        "# {}\n- [ ] Old task\n  - [ ] Subtask\n- [ ] Untouched\n",
+        written.format("%Y/%m/%d")
+    );
+    let config = Config {
+        record_completion_dates: true,
+        ..Config::default()
+    };
+    let mut ctx = TestContext::with_config_and_content(written, &content, config);
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+    let stamp = format!("<!-- done: {} -->", today.format("%Y-%m-%d"));
+    let journal = ctx.read_journal();
+    assert!(journal.contains(&format!("- [x] Old task {stamp}")));
+    assert!(journal.contains(&format!("  - [x] Subtask {stamp}")));
+    assert!(!ctx.screen_contains("<!--"));
+
+    // done: matches on completion date rather than the day written
+    ctx.app.quick_filter("done:today").unwrap();
+    assert!(ctx.screen_contains("Old task") && ctx.screen_contains("Subtask"));
+    assert!(!ctx.screen_contains("Untouched") && !ctx.screen_contains("<!--"));
+    ctx.app.quick_filter("done:..d7").unwrap();
+    assert!(!ctx.screen_contains("Old task"));
+
+    // Reopening clears the stamp
+    ctx.app.goto_day(written).unwrap();
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(!ctx.read_journal().contains("<!--"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn entry_bodies_collapse_expand_edit_and_move_with_entry() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    let content = "# 2026/01/14\n- [ ] Fix login\n  Behind the proxy\n    curl -I x\n- [ ] Other\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Collapsed to a line count until expanded
+    assert!(ctx.screen_contains("Fix login ▸2"));
+    assert!(!ctx.screen_contains("curl"));
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('e'));
+    assert!(ctx.screen_contains("Behind the proxy") && ctx.screen_contains("  curl -I x"));
+
+    // The edit buffer holds the body; Alt+Enter starts another line
+    ctx.press(KeyCode::Char('i'));
+    ctx.press_with_modifiers(KeyCode::Enter, KeyModifiers::ALT);
+    ctx.type_str("See #42");
+    ctx.press_with_modifiers(KeyCode::Enter, KeyModifiers::ALT);
+    ctx.type_str("- not a subtask");
+    ctx.press(KeyCode::Enter);
+    // A body line that looks like an entry is escaped so it reads back as body
+    let body = "- [ ] Fix login\n  Behind the proxy\n    curl -I x\n  See #42\n  \\- not a subtask";
+    assert!(ctx.read_journal().contains(body));
+    assert!(ctx.read_journal().contains("subtask\n- [ ] Other"));
+    ctx.app.goto_day(date.succ_opt().unwrap()).unwrap();
+    ctx.app.goto_day(date).unwrap();
+    assert!(ctx.screen_contains("- not a subtask"));
+    assert_eq!(ctx.app.entry_indices.len(), 2);
+
+    // Text searches match body lines
+    ctx.app.quick_filter("curl").unwrap();
+    assert!(ctx.screen_contains("Fix login") && !ctx.screen_contains("Other"));
+
+    // Deferring carries the body along
+    ctx.app.goto_day(date).unwrap();
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('>'));
+    let journal = ctx.read_journal();
+    assert!(journal.contains(&format!("# 2026/01/15\n{body}")));
+    assert!(journal.contains("# 2026/01/14\n- [ ] Other"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn expanded_bodies_follow_their_entry_when_lines_shift() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    let content = "# 2026/01/14\n- [ ] First\n  first details\n- [ ] Second\n  second details\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('e'));
+    assert!(ctx.screen_contains("first details"));
+
+    // Deleting the expanded entry doesn't expand the one that moves up
+    ctx.press(KeyCode::Char('d'));
+    assert!(!ctx.screen_contains("second details"));
+    assert!(ctx.screen_contains("Second ▸1"));
+
+    // Undo brings the entry back still expanded
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.screen_contains("first details"));
+    assert!(!ctx.screen_contains("second details"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn auto_commit_commits_journal_changes_and_refuses_mid_merge() {
+    let config = Config {
+        auto_commit: true,
+        ..Config::default()
+    };
+    let mut ctx = TestContext::with_config_and_content(
+        NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+        "# 2026/01/15\n- [ ] Write docs\n- [ ] Ship release\n- [ ] Tag version\n",
+        config,
+    );
+    let repo = ctx.temp_dir.path().to_path_buf();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    git(&["init", "-q"]);
+    git(&["config", "user.name", "Test"]);
+    git(&["config", "user.email", "test@example.com"]);
+    git(&["add", "test_journal.md"]);
+    git(&["commit", "-q", "-m", "Start journal"]);
+    std::fs::write(repo.join("notes.txt"), "staged elsewhere").unwrap();
+    git(&["add", "notes.txt"]);
+
+    ctx.press(KeyCode::Char('d'));
+    assert_eq!(ctx.app.auto_commit().unwrap(), 1);
+    assert_eq!(git(&["log", "-1", "--format=%s"]), "Deleted entry\n");
+    // Only the journal is committed; other staged work is left alone
+    assert_eq!(
+        git(&["show", "--name-only", "--format="]),
+        "test_journal.md\n"
+    );
+    assert_eq!(git(&["diff", "--cached", "--name-only"]), "notes.txt\n");
+
+    ctx.press(KeyCode::Char('d'));
+    ctx.press(KeyCode::Char('d'));
+    let merge_head = repo.join(".git").join("MERGE_HEAD");
+    std::fs::write(&merge_head, "").unwrap();
+    let err = ctx.app.auto_commit().unwrap_err();
+    assert!(err.to_string().contains("merge is in progress"));
+    assert_eq!(git(&["rev-list", "--count", "HEAD"]), "2\n");
+
+    // Refused changes stay pending and go into the next commit
+    std::fs::remove_file(&merge_head).unwrap();
+    assert_eq!(ctx.app.auto_commit().unwrap(), 1);
+    assert_eq!(
+        git(&["log", "-1", "--format=%B"]),
+        "Deleted entry and 1 more\n\n- Deleted entry\n- Deleted entry\n\n"
+    );
+    assert_eq!(ctx.app.auto_commit().unwrap(), 0);
+    assert!(git(&["status", "--porcelain", "test_journal.md"]).is_empty());
+
+    // Undos and redos say so rather than repeating what the step did
+    ctx.press(KeyCode::Char('u'));
+    assert_eq!(ctx.app.auto_commit().unwrap(), 1);
+    assert_eq!(git(&["log", "-1", "--format=%s"]), "Undo: Deleted entry\n");
+    ctx.app.redo().unwrap();
+    assert_eq!(ctx.app.auto_commit().unwrap(), 1);
+    assert_eq!(git(&["log", "-1", "--format=%s"]), "Redo: Deleted entry\n");
+}
+
+#[test]
+fn auto_commit_includes_archives_the_journal_writes() {
+    let config = Config {
+        auto_commit: true,
+        ..Config::default()
+    };
+    let mut ctx = TestContext::with_config_and_content(
+        NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+        "# 2025/06/01\n- [x] Old task\n\n# 2026/01/15\n- [ ] Write docs\n",
+        config,
+    );
+    let repo = ctx.temp_dir.path().to_path_buf();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    git(&["init", "-q"]);
+    git(&["config", "user.name", "Test"]);
+    git(&["config", "user.email", "test@example.com"]);
+    git(&["add", "test_journal.md"]);
+    git(&["commit", "-q", "-m", "Start journal"]);
+
+    // Archiving commits the archive it created along with the journal
+    ctx.app
+        .confirm_archive_journal(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap())
+        .unwrap();
+    assert_eq!(ctx.app.auto_commit().unwrap(), 1);
+    assert_eq!(git(&["log", "-1", "--format=%s"]), "Archived 1 days\n");
+    assert_eq!(
+        git(&["show", "--name-only", "--format="]),
+        "test_journal.archive.md\ntest_journal.md\n"
+    );
+
+    // Edits to an archived day are saved to, and committed in, the archive
+    ctx.app
+        .goto_day(NaiveDate::from_ymd_opt(2025, 6, 1).unwrap())
+        .unwrap();
+    ctx.press(KeyCode::Char('d'));
+    assert_eq!(ctx.app.auto_commit().unwrap(), 1);
+    assert_eq!(
+        git(&["show", "--name-only", "--format="]),
+        "test_journal.archive.md\n"
+    );
+    assert!(
+        git(&[
+            "status",
+            "--porcelain",
+            "test_journal.md",
+            "test_journal.archive.md"
+        ])
+        .is_empty()
+    );
+}
+
+#[test]
+fn ics_feed_is_rewritten_after_saves_not_on_each_one() {
+    let config = Config {
+        ics_export: true,
+        ..Config::default()
+    };
+    let mut ctx = TestContext::with_config_and_content(
+        NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+        "# 2026/01/15\n- [ ] Write docs\n- [ ] Ship release\n",
+        config,
+    );
+    let feed = ctx.temp_dir.path().join("test_journal.ics");
+
+    ctx.press(KeyCode::Char('d'));
+    ctx.app.export_ics_if_settled();
+    assert!(!feed.exists());
+    ctx.app.export_ics_feeds().unwrap();
+    let written = std::fs::read_to_string(&feed).unwrap();
+    assert!(written.contains("SUMMARY:Write docs\r\n"));
+    assert!(!written.contains("Ship release"));
+
+    // Undoing changes the journal again, and the feed follows once written
+    ctx.press(KeyCode::Char('u'));
+    assert_eq!(std::fs::read_to_string(&feed).unwrap(), written);
+    ctx.app.export_ics_feeds().unwrap();
+    assert!(
+        std::fs::read_to_string(&feed)
+            .unwrap()
+            .contains("SUMMARY:Ship release\r\n")
+    );
+}
+
+#[test]
+fn auto_commit_refuses_journals_ignored_by_gitignore() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    std::fs::create_dir_all(repo.join(".corner")).unwrap();
+    std::fs::write(repo.join(".gitignore"), ".corner\n").unwrap();
+    let journal = repo.join(".corner").join("journal.md");
+    std::fs::write(&journal, "# 2026/01/15\n- Note\n").unwrap();
+
+    let err = corner::storage::commit_journal(&[journal], "Created entry").unwrap_err();
+    assert!(err.to_string().contains("ignored by .gitignore"));
+}